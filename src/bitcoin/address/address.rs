@@ -0,0 +1,360 @@
+use std::fmt;
+
+use crate::bitcoin::{
+    error::BitcoinError,
+    hashes::{hash160, sha256},
+    network::Network,
+    taproot::{tweak_internal_key, x_only_public_key},
+    types::ScriptBuf,
+};
+
+use super::{base58, bech32};
+
+/// The data committed to by an address, which determines its output script.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Payload {
+    /// HASH160 of a public key, for P2PKH addresses.
+    PubkeyHash([u8; 20]),
+    /// HASH160 of a redeem script, for P2SH addresses.
+    ScriptHash([u8; 20]),
+    /// A SegWit witness program, for P2WPKH, P2WSH and P2TR addresses.
+    WitnessProgram {
+        /// The witness version, from 0 to 16.
+        version: u8,
+        /// The witness program, from 2 to 40 bytes.
+        program: Vec<u8>,
+    },
+}
+
+/// A Bitcoin address bound to the [`Network`] it is valid on.
+///
+/// ###### Example:
+///
+/// ```rust
+/// let address = Address::parse("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4", Network::Mainnet).unwrap();
+///
+/// let txout = TxOut {
+///     value: Amount::from_sat(10000),
+///     script_pubkey: address.script_pubkey(),
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Address {
+    network: Network,
+    payload: Payload,
+}
+
+impl Address {
+    /// Creates a P2PKH address from a serialized (compressed or uncompressed) public key.
+    pub fn p2pkh(public_key: &[u8], network: Network) -> Self {
+        Self {
+            network,
+            payload: Payload::PubkeyHash(hash160(public_key)),
+        }
+    }
+
+    /// Creates a P2SH address from a redeem script.
+    pub fn p2sh(redeem_script: &ScriptBuf, network: Network) -> Self {
+        Self {
+            network,
+            payload: Payload::ScriptHash(hash160(redeem_script.as_bytes())),
+        }
+    }
+
+    /// Creates a P2WPKH address from a compressed public key.
+    pub fn p2wpkh(public_key: &[u8], network: Network) -> Result<Self, BitcoinError> {
+        if !network.supports_segwit() {
+            return Err(BitcoinError::UnsupportedNetwork(network));
+        }
+        if public_key.len() != 33 {
+            return Err(BitcoinError::InvalidAddress(
+                "P2WPKH requires a 33 byte compressed public key".to_string(),
+            ));
+        }
+        Ok(Self {
+            network,
+            payload: Payload::WitnessProgram {
+                version: 0,
+                program: hash160(public_key).to_vec(),
+            },
+        })
+    }
+
+    /// Creates a P2WSH address from a witness script.
+    pub fn p2wsh(witness_script: &ScriptBuf, network: Network) -> Result<Self, BitcoinError> {
+        if !network.supports_segwit() {
+            return Err(BitcoinError::UnsupportedNetwork(network));
+        }
+        Ok(Self {
+            network,
+            payload: Payload::WitnessProgram {
+                version: 0,
+                program: sha256(witness_script.as_bytes()).to_vec(),
+            },
+        })
+    }
+
+    /// Creates a P2TR address from an internal public key and the optional root of a script tree.
+    ///
+    /// The key may be compressed, uncompressed or x-only. It is tweaked as in BIP-341, use
+    /// [`TapTreeBuilder`](crate::bitcoin::taproot::TapTreeBuilder) to build the script tree.
+    pub fn p2tr(
+        internal_key: &[u8],
+        merkle_root: Option<&[u8; 32]>,
+        network: Network,
+    ) -> Result<Self, BitcoinError> {
+        if !network.supports_segwit() {
+            return Err(BitcoinError::UnsupportedNetwork(network));
+        }
+        let internal_key = x_only_public_key(internal_key)?;
+        let (output_key, _) = tweak_internal_key(&internal_key, merkle_root)?;
+        Self::p2tr_tweaked(output_key, network)
+    }
+
+    /// Creates a P2TR address from an already tweaked x-only output key.
+    pub fn p2tr_tweaked(output_key: [u8; 32], network: Network) -> Result<Self, BitcoinError> {
+        if !network.supports_segwit() {
+            return Err(BitcoinError::UnsupportedNetwork(network));
+        }
+        Ok(Self {
+            network,
+            payload: Payload::WitnessProgram {
+                version: 1,
+                program: output_key.to_vec(),
+            },
+        })
+    }
+
+    /// Creates the address paying to the given output script.
+    pub fn from_script(script_pubkey: &ScriptBuf, network: Network) -> Result<Self, BitcoinError> {
+        let bytes = script_pubkey.as_bytes();
+        let payload = if script_pubkey.is_p2pkh() {
+            Payload::PubkeyHash(bytes[3..23].try_into().expect("20 bytes"))
+        } else if script_pubkey.is_p2sh() {
+            Payload::ScriptHash(bytes[2..22].try_into().expect("20 bytes"))
+        } else if let Some(version) = script_pubkey
+            .witness_version()
+            .filter(|_| network.supports_segwit())
+        {
+            Payload::WitnessProgram {
+                version,
+                program: bytes[2..].to_vec(),
+            }
+        } else {
+            return Err(BitcoinError::UnsupportedScript);
+        };
+
+        Ok(Self { network, payload })
+    }
+
+    /// Parses an address, checking that it is valid on the given network.
+    pub fn parse(address: &str, network: Network) -> Result<Self, BitcoinError> {
+        if let Ok((hrp, version, program)) = bech32::decode_segwit_address(address) {
+            if hrp != network.bech32_hrp() {
+                return Err(BitcoinError::InvalidAddress(format!(
+                    "Address {address} is not valid on {network}"
+                )));
+            }
+            return Ok(Self {
+                network,
+                payload: Payload::WitnessProgram { version, program },
+            });
+        }
+
+        let data = base58::decode_check(address).map_err(BitcoinError::InvalidAddress)?;
+        if data.len() != 21 {
+            return Err(BitcoinError::InvalidAddress(format!(
+                "Invalid base58 address length: {}",
+                data.len()
+            )));
+        }
+        let hash: [u8; 20] = data[1..].try_into().expect("20 bytes");
+        let payload = match data[0] {
+            prefix if prefix == network.p2pkh_prefix() => Payload::PubkeyHash(hash),
+            prefix if prefix == network.p2sh_prefix() => Payload::ScriptHash(hash),
+            _ => {
+                return Err(BitcoinError::InvalidAddress(format!(
+                    "Address {address} is not valid on {network}"
+                )))
+            }
+        };
+
+        Ok(Self { network, payload })
+    }
+
+    /// Returns the network the address is valid on.
+    pub const fn network(&self) -> Network {
+        self.network
+    }
+
+    /// Returns the payload of the address.
+    pub const fn payload(&self) -> &Payload {
+        &self.payload
+    }
+
+    /// Generates the output script paying to this address.
+    pub fn script_pubkey(&self) -> ScriptBuf {
+        match &self.payload {
+            Payload::PubkeyHash(hash) => ScriptBuf::new_p2pkh(hash),
+            Payload::ScriptHash(hash) => ScriptBuf::new_p2sh(hash),
+            Payload::WitnessProgram { version, program } => {
+                ScriptBuf::new_witness_program(*version, program)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let encoded = match &self.payload {
+            Payload::PubkeyHash(hash) => {
+                let mut data = vec![self.network.p2pkh_prefix()];
+                data.extend_from_slice(hash);
+                base58::encode_check(&data)
+            }
+            Payload::ScriptHash(hash) => {
+                let mut data = vec![self.network.p2sh_prefix()];
+                data.extend_from_slice(hash);
+                base58::encode_check(&data)
+            }
+            Payload::WitnessProgram { version, program } => {
+                bech32::encode_segwit_address(self.network.bech32_hrp(), *version, program)
+            }
+        };
+        write!(f, "{encoded}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    const PUBLIC_KEY: &str = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+
+    fn rust_bitcoin_public_key() -> bitcoin::PublicKey {
+        bitcoin::PublicKey::from_str(PUBLIC_KEY).unwrap()
+    }
+
+    #[test]
+    fn test_p2pkh_and_p2wpkh_against_rust_bitcoin() {
+        let public_key = hex::decode(PUBLIC_KEY).unwrap();
+
+        for (network, rust_bitcoin_network) in [
+            (Network::Mainnet, bitcoin::Network::Bitcoin),
+            (Network::Testnet4, bitcoin::Network::Testnet),
+            (Network::Regtest, bitcoin::Network::Regtest),
+        ] {
+            let p2pkh = Address::p2pkh(&public_key, network);
+            let expected = bitcoin::Address::p2pkh(rust_bitcoin_public_key(), rust_bitcoin_network);
+            assert_eq!(p2pkh.to_string(), expected.to_string());
+            assert_eq!(
+                p2pkh.script_pubkey().as_bytes(),
+                expected.script_pubkey().as_bytes()
+            );
+
+            let p2wpkh = Address::p2wpkh(&public_key, network).unwrap();
+            let expected = bitcoin::Address::p2wpkh(
+                &bitcoin::CompressedPublicKey::try_from(rust_bitcoin_public_key()).unwrap(),
+                rust_bitcoin_network,
+            );
+            assert_eq!(p2wpkh.to_string(), expected.to_string());
+            assert_eq!(
+                p2wpkh.script_pubkey().as_bytes(),
+                expected.script_pubkey().as_bytes()
+            );
+        }
+    }
+
+    #[test]
+    fn test_p2sh_and_p2wsh_against_rust_bitcoin() {
+        let script_hex = format!("21{PUBLIC_KEY}ac");
+        let script = ScriptBuf::from_hex(&script_hex).unwrap();
+        let rust_bitcoin_script = bitcoin::ScriptBuf::from_hex(&script_hex).unwrap();
+
+        let p2sh = Address::p2sh(&script, Network::Mainnet);
+        let expected =
+            bitcoin::Address::p2sh(&rust_bitcoin_script, bitcoin::Network::Bitcoin).unwrap();
+        assert_eq!(p2sh.to_string(), expected.to_string());
+
+        let p2wsh = Address::p2wsh(&script, Network::Signet).unwrap();
+        let expected = bitcoin::Address::p2wsh(&rust_bitcoin_script, bitcoin::Network::Signet);
+        assert_eq!(p2wsh.to_string(), expected.to_string());
+
+        assert_eq!(
+            Address::p2wsh(&script, Network::Dogecoin),
+            Err(BitcoinError::UnsupportedNetwork(Network::Dogecoin))
+        );
+    }
+
+    #[test]
+    fn test_p2tr_against_rust_bitcoin() {
+        let public_key = hex::decode(PUBLIC_KEY).unwrap();
+        let internal_key = rust_bitcoin_public_key().inner.x_only_public_key().0;
+        let merkle_root = [0x42; 32];
+        let secp = bitcoin::secp256k1::Secp256k1::verification_only();
+
+        for root in [None, Some(merkle_root)] {
+            let p2tr = Address::p2tr(&public_key, root.as_ref(), Network::Mainnet).unwrap();
+            let expected = bitcoin::Address::p2tr(
+                &secp,
+                internal_key,
+                root.map(bitcoin::TapNodeHash::assume_hidden),
+                bitcoin::Network::Bitcoin,
+            );
+            assert_eq!(p2tr.to_string(), expected.to_string());
+            assert_eq!(
+                p2tr.script_pubkey().as_bytes(),
+                expected.script_pubkey().as_bytes()
+            );
+        }
+
+        assert_eq!(
+            Address::p2tr(&public_key, None, Network::Dogecoin),
+            Err(BitcoinError::UnsupportedNetwork(Network::Dogecoin))
+        );
+        assert_eq!(
+            Address::p2tr_tweaked([0x42; 32], Network::Dogecoin),
+            Err(BitcoinError::UnsupportedNetwork(Network::Dogecoin))
+        );
+    }
+
+    #[test]
+    fn test_parse_and_display_roundtrip() {
+        let addresses = [
+            ("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa", Network::Mainnet),
+            ("3J98t1WpEZ73CNmQviecrnyiWrnqRhWNLy", Network::Mainnet),
+            (
+                "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+                Network::Mainnet,
+            ),
+            (
+                "tb1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3q0sl5k7",
+                Network::Testnet4,
+            ),
+            (
+                "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0",
+                Network::Mainnet,
+            ),
+        ];
+
+        for (address, network) in addresses {
+            let parsed = Address::parse(address, network).unwrap();
+            assert_eq!(parsed.to_string(), address);
+            assert_eq!(
+                Address::from_script(&parsed.script_pubkey(), network).unwrap(),
+                parsed
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_rejects_address_from_other_network() {
+        assert!(Address::parse("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa", Network::Testnet).is_err());
+        assert!(Address::parse(
+            "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+            Network::Regtest
+        )
+        .is_err());
+    }
+}
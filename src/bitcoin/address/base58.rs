@@ -0,0 +1,26 @@
+//! Base58Check encoding of legacy addresses.
+use crate::bitcoin::hashes::sha256d;
+
+/// Encodes the payload in base58, appending the first four bytes of its sha256d as checksum.
+pub fn encode_check(payload: &[u8]) -> String {
+    let mut data = payload.to_vec();
+    data.extend_from_slice(&sha256d(payload)[..4]);
+    bs58::encode(data).into_string()
+}
+
+/// Decodes a Base58Check string, verifying and stripping its checksum.
+pub fn decode_check(s: &str) -> Result<Vec<u8>, String> {
+    let mut data = bs58::decode(s)
+        .into_vec()
+        .map_err(|e| format!("Failed to decode base58: {e}"))?;
+    if data.len() < 4 {
+        return Err("Base58Check data too short".to_string());
+    }
+
+    let checksum = data.split_off(data.len() - 4);
+    if sha256d(&data)[..4] != checksum[..] {
+        return Err("Invalid Base58Check checksum".to_string());
+    }
+
+    Ok(data)
+}
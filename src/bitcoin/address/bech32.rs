@@ -0,0 +1,245 @@
+//! Bech32 and Bech32m encoding of SegWit addresses (BIP-173 and BIP-350).
+
+/// The 32 characters of the bech32 alphabet, also used by CashAddr.
+pub(crate) const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+const GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+
+/// The maximum length of a bech32 encoded SegWit address.
+const MAX_LENGTH: usize = 90;
+
+/// The checksum variant of a bech32 string.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Variant {
+    /// Original bech32 checksum, used by witness version 0 (BIP-173).
+    Bech32,
+    /// Modified bech32m checksum, used by witness versions 1 to 16 (BIP-350).
+    Bech32m,
+}
+
+impl Variant {
+    const fn constant(&self) -> u32 {
+        match self {
+            Self::Bech32 => 1,
+            Self::Bech32m => 0x2bc830a3,
+        }
+    }
+}
+
+fn polymod(values: &[u8]) -> u32 {
+    let mut chk: u32 = 1;
+    for value in values {
+        let top = chk >> 25;
+        chk = ((chk & 0x1ffffff) << 5) ^ u32::from(*value);
+        for (i, generator) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                chk ^= generator;
+            }
+        }
+    }
+    chk
+}
+
+fn hrp_expand(hrp: &str) -> Vec<u8> {
+    let mut expanded: Vec<u8> = hrp.bytes().map(|b| b >> 5).collect();
+    expanded.push(0);
+    expanded.extend(hrp.bytes().map(|b| b & 0x1f));
+    expanded
+}
+
+fn create_checksum(hrp: &str, data: &[u8], variant: Variant) -> [u8; 6] {
+    let mut values = hrp_expand(hrp);
+    values.extend_from_slice(data);
+    values.extend_from_slice(&[0; 6]);
+    let polymod = polymod(&values) ^ variant.constant();
+
+    let mut checksum = [0u8; 6];
+    for (i, c) in checksum.iter_mut().enumerate() {
+        *c = ((polymod >> (5 * (5 - i))) & 0x1f) as u8;
+    }
+    checksum
+}
+
+/// Regroups a slice of `from`-bit values into `to`-bit values.
+pub fn convert_bits(data: &[u8], from: u32, to: u32, pad: bool) -> Result<Vec<u8>, String> {
+    let mut acc: u32 = 0;
+    let mut bits: u32 = 0;
+    let max_value: u32 = (1 << to) - 1;
+    let mut result = Vec::with_capacity(data.len() * from as usize / to as usize + 1);
+
+    for value in data {
+        let value = u32::from(*value);
+        if value >> from != 0 {
+            return Err("Invalid data value".to_string());
+        }
+        acc = (acc << from) | value;
+        bits += from;
+        while bits >= to {
+            bits -= to;
+            result.push(((acc >> bits) & max_value) as u8);
+        }
+    }
+
+    if pad {
+        if bits > 0 {
+            result.push(((acc << (to - bits)) & max_value) as u8);
+        }
+    } else if bits >= from || ((acc << (to - bits)) & max_value) != 0 {
+        return Err("Invalid padding".to_string());
+    }
+
+    Ok(result)
+}
+
+/// Encodes the human readable part and 5-bit data with the given checksum variant.
+pub fn encode(hrp: &str, data: &[u8], variant: Variant) -> String {
+    let checksum = create_checksum(hrp, data, variant);
+    let mut encoded = String::with_capacity(hrp.len() + 1 + data.len() + checksum.len());
+    encoded.push_str(hrp);
+    encoded.push('1');
+    for value in data.iter().chain(checksum.iter()) {
+        encoded.push(CHARSET[*value as usize] as char);
+    }
+    encoded
+}
+
+/// Decodes a bech32 or bech32m string into its lowercase human readable part and 5-bit data.
+pub fn decode(s: &str) -> Result<(String, Vec<u8>, Variant), String> {
+    if s.len() > MAX_LENGTH {
+        return Err("Invalid bech32 length".to_string());
+    }
+    let has_lower = s.bytes().any(|b| b.is_ascii_lowercase());
+    let has_upper = s.bytes().any(|b| b.is_ascii_uppercase());
+    if has_lower && has_upper {
+        return Err("Mixed case bech32 string".to_string());
+    }
+    let s = s.to_ascii_lowercase();
+
+    let separator = s
+        .rfind('1')
+        .ok_or_else(|| "Missing bech32 separator".to_string())?;
+    if separator == 0 || separator + 7 > s.len() {
+        return Err("Invalid bech32 separator position".to_string());
+    }
+
+    let (hrp, data_part) = (&s[..separator], &s[separator + 1..]);
+    if hrp.bytes().any(|b| !(33..=126).contains(&b)) {
+        return Err("Invalid bech32 human readable part".to_string());
+    }
+
+    let data = data_part
+        .bytes()
+        .map(|b| {
+            CHARSET
+                .iter()
+                .position(|c| *c == b)
+                .map(|p| p as u8)
+                .ok_or_else(|| format!("Invalid bech32 character: {}", b as char))
+        })
+        .collect::<Result<Vec<u8>, String>>()?;
+
+    let mut values = hrp_expand(hrp);
+    values.extend_from_slice(&data);
+    let variant = match polymod(&values) {
+        c if c == Variant::Bech32.constant() => Variant::Bech32,
+        c if c == Variant::Bech32m.constant() => Variant::Bech32m,
+        _ => return Err("Invalid bech32 checksum".to_string()),
+    };
+
+    Ok((hrp.to_string(), data[..data.len() - 6].to_vec(), variant))
+}
+
+/// Encodes a SegWit address from its witness version and program.
+pub fn encode_segwit_address(hrp: &str, version: u8, program: &[u8]) -> String {
+    let variant = if version == 0 {
+        Variant::Bech32
+    } else {
+        Variant::Bech32m
+    };
+    let mut data = vec![version];
+    data.extend(convert_bits(program, 8, 5, true).expect("8-bit values always convert"));
+    encode(hrp, &data, variant)
+}
+
+/// Decodes a SegWit address, returning its human readable part, witness version and program.
+pub fn decode_segwit_address(s: &str) -> Result<(String, u8, Vec<u8>), String> {
+    let (hrp, data, variant) = decode(s)?;
+    let (version, program) = data
+        .split_first()
+        .ok_or_else(|| "Empty witness program".to_string())?;
+    let version = *version;
+    if version > 16 {
+        return Err(format!("Invalid witness version: {version}"));
+    }
+
+    let expected_variant = if version == 0 {
+        Variant::Bech32
+    } else {
+        Variant::Bech32m
+    };
+    if variant != expected_variant {
+        return Err("Invalid checksum variant for witness version".to_string());
+    }
+
+    let program = convert_bits(program, 5, 8, false)?;
+    if !(2..=40).contains(&program.len()) {
+        return Err("Invalid witness program length".to_string());
+    }
+    if version == 0 && program.len() != 20 && program.len() != 32 {
+        return Err("Invalid witness version 0 program length".to_string());
+    }
+
+    Ok((hrp, version, program))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_bip173_and_bip350_vectors() {
+        let (hrp, version, program) =
+            decode_segwit_address("BC1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7KV8F3T4").unwrap();
+        assert_eq!(hrp, "bc");
+        assert_eq!(version, 0);
+        assert_eq!(
+            hex::encode(program),
+            "751e76e8199196d454941c45d1b3a323f1433bd6"
+        );
+
+        let (hrp, version, program) =
+            decode_segwit_address("bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0")
+                .unwrap();
+        assert_eq!(hrp, "bc");
+        assert_eq!(version, 1);
+        assert_eq!(
+            hex::encode(program),
+            "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"
+        );
+    }
+
+    #[test]
+    fn test_decode_rejects_invalid_addresses() {
+        // Witness version 1 with a bech32 checksum (BIP-350)
+        assert!(decode_segwit_address(
+            "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqh2y7hd"
+        )
+        .is_err());
+        // Mixed case
+        assert!(decode_segwit_address("bc1qW508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4").is_err());
+        // Invalid checksum
+        assert!(decode_segwit_address("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t5").is_err());
+    }
+
+    #[test]
+    fn test_encode_roundtrip() {
+        let program = hex::decode("751e76e8199196d454941c45d1b3a323f1433bd6").unwrap();
+        let address = encode_segwit_address("bc", 0, &program);
+
+        assert_eq!(address, "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4");
+        assert_eq!(
+            decode_segwit_address(&address).unwrap(),
+            ("bc".to_string(), 0, program)
+        );
+    }
+}
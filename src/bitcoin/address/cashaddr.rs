@@ -0,0 +1,215 @@
+//! CashAddr encoding of Bitcoin Cash and eCash addresses.
+//!
+//! CashAddr encodes the same P2PKH and P2SH payloads as base58 addresses, with a network prefix
+//! and a 40 bit BCH checksum over the bech32 alphabet.
+use super::{
+    address::Payload,
+    bech32::{convert_bits, CHARSET},
+};
+use crate::bitcoin::error::BitcoinError;
+
+/// Prefix of Bitcoin Cash mainnet addresses.
+pub const BITCOIN_CASH_PREFIX: &str = "bitcoincash";
+/// Prefix of Bitcoin Cash testnet addresses.
+pub const BITCOIN_CASH_TESTNET_PREFIX: &str = "bchtest";
+/// Prefix of Bitcoin Cash regtest addresses.
+pub const BITCOIN_CASH_REGTEST_PREFIX: &str = "bchreg";
+/// Prefix of eCash mainnet addresses.
+pub const ECASH_PREFIX: &str = "ecash";
+/// Prefix of eCash testnet addresses.
+pub const ECASH_TESTNET_PREFIX: &str = "ectest";
+
+const GENERATOR: [u64; 5] = [
+    0x98_f2bc_8e61,
+    0x79_b76d_99e2,
+    0xf3_3e5f_b3c4,
+    0xae_2eab_e2a8,
+    0x1e_4f43_e470,
+];
+
+/// Number of 5 bit groups in the checksum.
+const CHECKSUM_LENGTH: usize = 8;
+
+fn polymod(values: &[u8]) -> u64 {
+    let mut chk: u64 = 1;
+    for value in values {
+        let top = chk >> 35;
+        chk = ((chk & 0x07_ffff_ffff) << 5) ^ u64::from(*value);
+        for (i, generator) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                chk ^= generator;
+            }
+        }
+    }
+    chk ^ 1
+}
+
+fn prefix_expand(prefix: &str) -> Vec<u8> {
+    let mut expanded: Vec<u8> = prefix.bytes().map(|b| b & 0x1f).collect();
+    expanded.push(0);
+    expanded
+}
+
+/// Encodes a P2PKH or P2SH payload as a CashAddr address with the given prefix.
+pub fn encode(prefix: &str, payload: &Payload) -> Result<String, BitcoinError> {
+    // The version byte holds the type in bits 3 to 6 and the hash size in bits 0 to 2,
+    // where 0 stands for 160 bits.
+    let (version, hash) = match payload {
+        Payload::PubkeyHash(hash) => (0u8, hash),
+        Payload::ScriptHash(hash) => (1 << 3, hash),
+        Payload::WitnessProgram { .. } => {
+            return Err(BitcoinError::InvalidAddress(
+                "CashAddr does not support witness programs".to_string(),
+            ))
+        }
+    };
+    let mut data = vec![version];
+    data.extend_from_slice(hash);
+    let data = convert_bits(&data, 8, 5, true).map_err(BitcoinError::InvalidAddress)?;
+
+    let mut values = prefix_expand(prefix);
+    values.extend_from_slice(&data);
+    values.extend_from_slice(&[0; CHECKSUM_LENGTH]);
+    let checksum = polymod(&values);
+
+    let mut encoded = format!("{prefix}:");
+    encoded.extend(data.iter().map(|&d| CHARSET[d as usize] as char));
+    encoded.extend(
+        (0..CHECKSUM_LENGTH)
+            .map(|i| CHARSET[((checksum >> (5 * (7 - i))) & 0x1f) as usize] as char),
+    );
+    Ok(encoded)
+}
+
+/// Decodes a CashAddr address, checking that its prefix, which may be omitted, is `prefix`.
+pub fn decode(address: &str, prefix: &str) -> Result<Payload, BitcoinError> {
+    let invalid = |reason: &str| BitcoinError::InvalidAddress(format!("{reason}: {address}"));
+
+    if address.bytes().any(|b| b.is_ascii_lowercase())
+        && address.bytes().any(|b| b.is_ascii_uppercase())
+    {
+        return Err(invalid("Mixed case CashAddr address"));
+    }
+    let lowercase = address.to_ascii_lowercase();
+    let payload = match lowercase.split_once(':') {
+        Some((address_prefix, payload)) if address_prefix == prefix => payload,
+        Some(_) => return Err(invalid("Unexpected CashAddr prefix")),
+        None => lowercase.as_str(),
+    };
+
+    let values = payload
+        .bytes()
+        .map(|b| CHARSET.iter().position(|&c| c == b).map(|p| p as u8))
+        .collect::<Option<Vec<u8>>>()
+        .ok_or_else(|| invalid("Invalid CashAddr character"))?;
+    if values.len() <= CHECKSUM_LENGTH {
+        return Err(invalid("CashAddr address too short"));
+    }
+
+    let mut checked = prefix_expand(prefix);
+    checked.extend_from_slice(&values);
+    if polymod(&checked) != 0 {
+        return Err(invalid("Invalid CashAddr checksum"));
+    }
+
+    let data = convert_bits(&values[..values.len() - CHECKSUM_LENGTH], 5, 8, false)
+        .map_err(BitcoinError::InvalidAddress)?;
+    // Only 160 bit hashes are supported, so the size bits of the version byte must be 0.
+    if data.len() != 21 {
+        return Err(invalid("Unsupported CashAddr hash size"));
+    }
+    let hash: [u8; 20] = data[1..].try_into().expect("20 bytes");
+    match data[0] {
+        0 => Ok(Payload::PubkeyHash(hash)),
+        0x08 => Ok(Payload::ScriptHash(hash)),
+        _ => Err(invalid("Unsupported CashAddr version")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitcoin::{address::Address, network::Network};
+
+    #[test]
+    fn test_encode_spec_vectors() {
+        let hash: [u8; 20] = hex::decode("f5bf48b397dae70be82b3cca4793f8eb2b6cdac9")
+            .unwrap()
+            .try_into()
+            .unwrap();
+
+        assert_eq!(
+            encode(BITCOIN_CASH_PREFIX, &Payload::PubkeyHash(hash)).unwrap(),
+            "bitcoincash:qr6m7j9njldwwzlg9v7v53unlr4jkmx6eylep8ekg2"
+        );
+        assert_eq!(
+            encode(BITCOIN_CASH_TESTNET_PREFIX, &Payload::ScriptHash(hash)).unwrap(),
+            "bchtest:pr6m7j9njldwwzlg9v7v53unlr4jkmx6eyvwc0uz5t"
+        );
+    }
+
+    #[test]
+    fn test_legacy_address_conversion() {
+        for (legacy, cashaddr) in [
+            (
+                "1BpEi6DfDAUFd7GtittLSdBeYJvcoaVggu",
+                "bitcoincash:qpm2qsznhks23z7629mms6s4cwef74vcwvy22gdx6a",
+            ),
+            (
+                "3CWFddi6m4ndiGyKqzYvsFYagqDLPVMTzC",
+                "bitcoincash:ppm2qsznhks23z7629mms6s4cwef74vcwvn0h829pq",
+            ),
+        ] {
+            let payload = Address::parse(legacy, Network::Mainnet)
+                .unwrap()
+                .payload()
+                .clone();
+
+            assert_eq!(encode(BITCOIN_CASH_PREFIX, &payload).unwrap(), cashaddr);
+            assert_eq!(decode(cashaddr, BITCOIN_CASH_PREFIX).unwrap(), payload);
+            assert_eq!(
+                decode(&cashaddr.to_uppercase(), BITCOIN_CASH_PREFIX).unwrap(),
+                payload
+            );
+            assert_eq!(
+                decode(&cashaddr["bitcoincash:".len()..], BITCOIN_CASH_PREFIX).unwrap(),
+                payload
+            );
+        }
+    }
+
+    #[test]
+    fn test_ecash_roundtrip() {
+        let payload = Payload::PubkeyHash([0x11; 20]);
+        let address = encode(ECASH_PREFIX, &payload).unwrap();
+
+        assert!(address.starts_with("ecash:q"));
+        assert_eq!(decode(&address, ECASH_PREFIX).unwrap(), payload);
+        // The checksum commits to the prefix.
+        assert!(decode(&address["ecash:".len()..], BITCOIN_CASH_PREFIX).is_err());
+    }
+
+    #[test]
+    fn test_decode_rejects_invalid_addresses() {
+        let address = "bitcoincash:qpm2qsznhks23z7629mms6s4cwef74vcwvy22gdx6a";
+
+        for invalid in [
+            "bitcoincash:qpm2qsznhks23z7629mms6s4cwef74vcwvy22gdx6b",
+            "bchtest:qpm2qsznhks23z7629mms6s4cwef74vcwvy22gdx6a",
+            "bitcoincash:Qpm2qsznhks23z7629mms6s4cwef74vcwvy22gdx6a",
+            "bitcoincash:qpm2qsznhks23z7629mms6s4cwef74vcwvy22gdx6i",
+            "bitcoincash:qpm2qsz",
+        ] {
+            assert!(decode(invalid, BITCOIN_CASH_PREFIX).is_err(), "{invalid}");
+        }
+        assert!(decode(address, BITCOIN_CASH_PREFIX).is_ok());
+        assert!(encode(
+            BITCOIN_CASH_PREFIX,
+            &Payload::WitnessProgram {
+                version: 0,
+                program: vec![0; 20]
+            }
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,8 @@
+#![allow(clippy::module_inception)]
+
+mod address;
+pub mod base58;
+pub mod bech32;
+pub mod cashaddr;
+
+pub use self::address::{Address, Payload};
@@ -1,14 +1,18 @@
 use borsh::{BorshDeserialize, BorshSerialize};
 use near_sdk::serde::{Deserialize, Serialize};
 use schemars::JsonSchema;
-use sha2::{Digest, Sha256};
 use std::io::{BufRead, Write};
 
 use super::{
+    bitcoind::BitcoindTransaction,
     constants::{SEGWIT_FLAG, SEGWIT_MARKER},
     encoding::{decode::MAX_VEC_SIZE, utils::VarInt, Decodable, Encodable, ToU64},
+    error::BitcoinError,
+    hashes::{sha256, sha256d, tagged_hash},
+    network::Network,
     types::{
-        EcdsaSighashType, LockTime, ScriptBuf, TransactionType, TxIn, TxOut, Version, Witness,
+        Amount, EcdsaSighashType, Hash, LockTime, ScriptBuf, TapSighashType, TransactionType, TxIn,
+        TxOut, Txid, Utxo, Version, Witness, SIGHASH_FORKID,
     },
 };
 
@@ -92,13 +96,6 @@ pub struct BitcoinTransaction {
     pub output: Vec<TxOut>,
 }
 
-// Function to compute sha256d (double SHA-256)
-fn sha256d(data: &[u8]) -> Vec<u8> {
-    let hash1 = Sha256::digest(data);
-    let hash2 = Sha256::digest(hash1);
-    hash2.to_vec()
-}
-
 impl BitcoinTransaction {
     /// Encode the transaction into a vector of bytes
     pub fn serialize(&self) -> Vec<u8> {
@@ -109,6 +106,74 @@ impl BitcoinTransaction {
         buffer
     }
 
+    /// Decode a transaction from its consensus encoding, with or without witness data
+    pub fn deserialize(bytes: &[u8]) -> Result<Self, BitcoinError> {
+        let mut reader = bytes;
+        let tx = Self::decode(&mut reader)?;
+        if !reader.is_empty() {
+            return Err(BitcoinError::Encoding(format!(
+                "{} trailing bytes after transaction",
+                reader.len()
+            )));
+        }
+        Ok(tx)
+    }
+
+    /// Encode the transaction without its witness data, as committed to by the txid
+    pub fn serialize_without_witness(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+
+        let _ = self.version.encode(&mut buffer);
+        let _ = self.input.encode(&mut buffer);
+        let _ = self.output.encode(&mut buffer);
+        let _ = self.lock_time.encode(&mut buffer);
+
+        buffer
+    }
+
+    /// Computes the transaction id, the sha256d of the transaction without witness data
+    pub fn compute_txid(&self) -> Txid {
+        Txid(Hash::from_internal_bytes(sha256d(
+            &self.serialize_without_witness(),
+        )))
+    }
+
+    /// Computes the witness transaction id (BIP-141), which equals the txid without witnesses
+    pub fn compute_wtxid(&self) -> Txid {
+        Txid(Hash::from_internal_bytes(sha256d(&self.serialize())))
+    }
+
+    /// Weight of the transaction in weight units (BIP-141)
+    pub fn weight(&self) -> u64 {
+        let base_size = self.serialize_without_witness().len() as u64;
+        let total_size = self.serialize().len() as u64;
+
+        base_size * 3 + total_size
+    }
+
+    /// Virtual size of the transaction in vbytes, its weight divided by 4 and rounded up
+    pub fn vsize(&self) -> u64 {
+        self.weight().div_ceil(4)
+    }
+
+    /// Sorts the inputs and outputs lexicographically, as specified by [BIP-69].
+    ///
+    /// Inputs are ordered by the previous transaction id, in its displayed byte order, then by
+    /// output index. Outputs are ordered by value, then by output script bytes. Any sighash must
+    /// be computed after sorting, as it commits to the order.
+    ///
+    /// [BIP-69]: https://github.com/bitcoin/bips/blob/master/bip-0069.mediawiki
+    pub fn sort_bip69(&mut self) {
+        self.input.sort_by(|a, b| {
+            let (a, b) = (&a.previous_output, &b.previous_output);
+            (a.txid.0 .0, a.vout).cmp(&(b.txid.0 .0, b.vout))
+        });
+        self.output.sort_by(|a, b| {
+            (a.value.to_sat(), a.script_pubkey.as_bytes())
+                .cmp(&(b.value.to_sat(), b.script_pubkey.as_bytes()))
+        });
+    }
+
     /// Encode a legacy transaction into a vector of bytes
     pub fn build_for_signing_legacy(&self, sighash_type: EcdsaSighashType) -> Vec<u8> {
         let mut buffer = Vec::new();
@@ -122,28 +187,49 @@ impl BitcoinTransaction {
     }
 
     /// Attach a script sig to the transaction
+    ///
+    /// # Panics
+    ///
+    /// Panics if the transaction type is SegWit or the input index is out of range.
+    /// See [`Self::try_build_with_script_sig`] for a non-panicking version.
     pub fn build_with_script_sig(
         &mut self,
         input_index: usize,
         script_sig: ScriptBuf,
         tx_type: TransactionType,
     ) -> Vec<u8> {
+        self.try_build_with_script_sig(input_index, script_sig, tx_type)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Attach a script sig to the transaction, returning an error instead of panicking
+    pub fn try_build_with_script_sig(
+        &mut self,
+        input_index: usize,
+        script_sig: ScriptBuf,
+        tx_type: TransactionType,
+    ) -> Result<Vec<u8>, BitcoinError> {
         match tx_type {
             TransactionType::P2PKH | TransactionType::P2SH => {
-                self.input[input_index].script_sig = script_sig;
+                self.input_mut(input_index)?.script_sig = script_sig;
             }
-            TransactionType::P2WPKH | TransactionType::P2WSH => {
-                panic!("Use build_with_witness for SegWit transactions");
+            TransactionType::P2WPKH | TransactionType::P2WSH | TransactionType::P2TR => {
+                return Err(BitcoinError::ScriptSigForSegwit);
             }
         }
 
         let mut buffer = Vec::new();
-        let _ = self.encode(&mut buffer);
+        self.encode(&mut buffer)?;
 
-        buffer
+        Ok(buffer)
     }
 
     /// Encode the transaction for signing in SegWit format
+    ///
+    /// # Panics
+    ///
+    /// Panics if the transaction is version 1 or the input index is out of range.
+    /// See [`Self::try_build_for_signing_segwit`] for a non-panicking version.
     pub fn build_for_signing_segwit(
         &self,
         sighash_type: EcdsaSighashType,
@@ -151,43 +237,295 @@ impl BitcoinTransaction {
         script_code: &ScriptBuf,
         value: u64,
     ) -> Vec<u8> {
-        if self.version != Version::Two {
-            panic!("SegWit transactions must be version 2");
+        self.try_build_for_signing_segwit(sighash_type, input_index, script_code, value)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Encode the transaction for signing in SegWit format, returning an error instead of panicking
+    ///
+    /// Version 1 transactions are rejected. Version 0 is accepted for the BIP-322 `to_sign`
+    /// virtual transaction, see [`message::to_sign`](super::message::to_sign).
+    pub fn try_build_for_signing_segwit(
+        &self,
+        sighash_type: EcdsaSighashType,
+        input_index: usize,
+        script_code: &ScriptBuf,
+        value: u64,
+    ) -> Result<Vec<u8>, BitcoinError> {
+        if self.version == Version::One {
+            return Err(BitcoinError::InvalidSegwitVersion(self.version));
         }
+        self.input(input_index)?;
 
         let mut buffer = Vec::new();
 
-        self.encode_for_sighash_for_segwit(&mut buffer, input_index, script_code, value);
+        self.encode_for_sighash_for_segwit(&mut buffer, input_index, script_code, value)?;
 
         // Sighash type
         buffer.extend_from_slice(&(sighash_type as u32).to_le_bytes());
 
-        buffer
+        Ok(buffer)
     }
 
     /// Function to attach a witness to the transaction
+    ///
+    /// # Panics
+    ///
+    /// Panics if the transaction type is not SegWit or the input index is out of range.
+    /// See [`Self::try_build_with_witness`] for a non-panicking version.
     pub fn build_with_witness(
         &mut self,
         input_index: usize,
         witness: Vec<Vec<u8>>,
         tx_type: TransactionType,
     ) -> Vec<u8> {
+        self.try_build_with_witness(input_index, witness, tx_type)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Function to attach a witness to the transaction, returning an error instead of panicking
+    pub fn try_build_with_witness(
+        &mut self,
+        input_index: usize,
+        witness: Vec<Vec<u8>>,
+        tx_type: TransactionType,
+    ) -> Result<Vec<u8>, BitcoinError> {
         match tx_type {
-            TransactionType::P2WPKH | TransactionType::P2WSH => {
-                self.input[input_index].witness = Witness::from_slice(&witness);
+            TransactionType::P2WPKH | TransactionType::P2WSH | TransactionType::P2TR => {
+                self.input_mut(input_index)?.witness = Witness::from_slice(&witness);
             }
             TransactionType::P2PKH | TransactionType::P2SH => {
-                panic!("Use build_with_script_sig for non-SegWit transactions");
+                return Err(BitcoinError::WitnessForLegacy);
             }
         }
 
         let mut buffer = Vec::new();
 
-        let _ = self
-            .encode(&mut buffer)
-            .expect("Failed to encode transaction");
+        self.encode(&mut buffer)?;
 
-        buffer
+        Ok(buffer)
+    }
+
+    /// Computes the legacy (pre-SegWit) signature hash of an input.
+    ///
+    /// Unlike [`Self::build_for_signing_legacy`], which serializes the transaction as is, the
+    /// script sig of the signed input is replaced by `script_code` and the others are emptied.
+    pub fn legacy_signature_hash(
+        &self,
+        input_index: usize,
+        script_code: &ScriptBuf,
+        sighash_type: EcdsaSighashType,
+    ) -> Result<[u8; 32], BitcoinError> {
+        self.input(input_index)?;
+
+        let mut tx = self.clone();
+        for (i, input) in tx.input.iter_mut().enumerate() {
+            input.script_sig = if i == input_index {
+                script_code.clone()
+            } else {
+                ScriptBuf::default()
+            };
+            input.witness = Witness::default();
+        }
+
+        Ok(sha256d(&tx.build_for_signing_legacy(sighash_type)))
+    }
+
+    /// Computes the SegWit v0 (BIP-143) signature hash of an input spending `value`.
+    pub fn segwit_v0_signature_hash(
+        &self,
+        input_index: usize,
+        script_code: &ScriptBuf,
+        value: Amount,
+        sighash_type: EcdsaSighashType,
+    ) -> Result<[u8; 32], BitcoinError> {
+        self.input(input_index)?;
+
+        let mut buffer = Vec::new();
+        self.encode_for_sighash_for_segwit(&mut buffer, input_index, script_code, value.to_sat())?;
+        buffer.extend_from_slice(&(sighash_type as u32).to_le_bytes());
+
+        Ok(sha256d(&buffer))
+    }
+
+    /// Computes the Bitcoin Cash and eCash signature hash of an input spending `value`.
+    ///
+    /// Both chains use the BIP-143 digest for every input type, P2PKH and P2SH included, with
+    /// [`SIGHASH_FORKID`] set in the hash type and `fork_value` in its upper 24 bits, which is
+    /// zero on both. The signature must be followed by the byte `sighash_type | SIGHASH_FORKID`.
+    ///
+    /// Fails with [`BitcoinError::InvalidForkValue`] if `fork_value` does not fit in 24 bits.
+    pub fn forkid_signature_hash(
+        &self,
+        input_index: usize,
+        script_code: &ScriptBuf,
+        value: Amount,
+        sighash_type: EcdsaSighashType,
+        fork_value: u32,
+    ) -> Result<[u8; 32], BitcoinError> {
+        self.input(input_index)?;
+        if fork_value > 0x00ff_ffff {
+            return Err(BitcoinError::InvalidForkValue(fork_value));
+        }
+        let hash_type = (fork_value << 8) | SIGHASH_FORKID | sighash_type as u32;
+
+        let mut buffer = Vec::new();
+        self.encode_for_sighash_for_segwit(&mut buffer, input_index, script_code, value.to_sat())?;
+        buffer.extend_from_slice(&hash_type.to_le_bytes());
+
+        Ok(sha256d(&buffer))
+    }
+
+    /// Computes the Taproot (BIP-341) key path signature hash of an input.
+    ///
+    /// `spent_outputs` must contain the outputs spent by every input of the transaction, in
+    /// order, since the signature commits to all their amounts and scripts.
+    pub fn taproot_key_spend_signature_hash(
+        &self,
+        input_index: usize,
+        spent_outputs: &[TxOut],
+        annex: Option<&[u8]>,
+        sighash_type: TapSighashType,
+    ) -> Result<[u8; 32], BitcoinError> {
+        self.input(input_index)?;
+        if spent_outputs.len() != self.input.len() {
+            return Err(BitcoinError::SpentOutputsMismatch {
+                expected: self.input.len(),
+                actual: spent_outputs.len(),
+            });
+        }
+
+        let mut prevouts = Vec::new();
+        let mut sequences = Vec::new();
+        for input in &self.input {
+            input.previous_output.encode(&mut prevouts)?;
+            input.sequence.encode(&mut sequences)?;
+        }
+        let mut amounts = Vec::new();
+        let mut script_pubkeys = Vec::new();
+        for spent_output in spent_outputs {
+            spent_output.value.encode(&mut amounts)?;
+            spent_output.script_pubkey.encode(&mut script_pubkeys)?;
+        }
+        let mut outputs = Vec::new();
+        for output in &self.output {
+            output.encode(&mut outputs)?;
+        }
+
+        // Sighash epoch
+        let mut buffer = vec![0x00];
+
+        // Hash type, version and lock time
+        buffer.push(sighash_type as u8);
+        self.version.encode(&mut buffer)?;
+        self.lock_time.encode(&mut buffer)?;
+
+        // Single SHA-256 of prevouts, amounts, script pubkeys, sequences and outputs
+        buffer.extend_from_slice(&sha256(&prevouts));
+        buffer.extend_from_slice(&sha256(&amounts));
+        buffer.extend_from_slice(&sha256(&script_pubkeys));
+        buffer.extend_from_slice(&sha256(&sequences));
+        buffer.extend_from_slice(&sha256(&outputs));
+
+        // Spend type: key path, with or without annex
+        buffer.push(u8::from(annex.is_some()));
+
+        // Input index
+        buffer.extend_from_slice(&(input_index as u32).to_le_bytes());
+
+        if let Some(annex) = annex {
+            let mut encoded_annex = Vec::new();
+            VarInt(annex.len().to_u64()).encode(&mut encoded_annex)?;
+            encoded_annex.extend_from_slice(annex);
+            buffer.extend_from_slice(&sha256(&encoded_annex));
+        }
+
+        Ok(tagged_hash("TapSighash", &buffer))
+    }
+
+    /// Returns the outputs spent by the inputs, in input order, looked up by outpoint in `utxos`.
+    ///
+    /// The UTXOs may be given in any order, so they stay valid after the inputs are sorted.
+    pub fn spent_outputs(&self, utxos: &[Utxo]) -> Result<Vec<TxOut>, BitcoinError> {
+        self.input_utxos(utxos)
+            .map(|utxos| utxos.into_iter().map(|utxo| utxo.txout.clone()).collect())
+    }
+
+    /// Computes the fee paid by the transaction: the value of the spent UTXOs minus the outputs.
+    ///
+    /// Fails with [`BitcoinError::AmountOverflow`] if either total does not fit in a `u64`.
+    pub fn fee(&self, utxos: &[Utxo]) -> Result<Amount, BitcoinError> {
+        let inputs = self
+            .input_utxos(utxos)?
+            .iter()
+            .try_fold(Amount::ZERO, |total, utxo| total.checked_add(utxo.value()))
+            .ok_or(BitcoinError::AmountOverflow)?;
+        let outputs = self
+            .output
+            .iter()
+            .try_fold(Amount::ZERO, |total, output| {
+                total.checked_add(output.value)
+            })
+            .ok_or(BitcoinError::AmountOverflow)?;
+
+        inputs
+            .checked_sub(outputs)
+            .ok_or(BitcoinError::InsufficientFunds { inputs, outputs })
+    }
+
+    /// Computes the ECDSA signature hash of an input, taking the script code, value and spend
+    /// type from the UTXO it spends.
+    ///
+    /// P2PKH and P2SH spends use the legacy signature hash, SegWit v0 spends the BIP-143 one.
+    /// Taproot spends are rejected: use [`Self::taproot_key_spend_signature_hash`] with
+    /// [`Self::spent_outputs`] instead.
+    pub fn ecdsa_signature_hash(
+        &self,
+        input_index: usize,
+        utxos: &[Utxo],
+        sighash_type: EcdsaSighashType,
+    ) -> Result<[u8; 32], BitcoinError> {
+        let input = self.input(input_index)?;
+        let utxo = find_utxo(utxos, input, input_index)?;
+        let script_code = utxo.script_code()?;
+
+        match utxo.spend_type {
+            TransactionType::P2PKH | TransactionType::P2SH => {
+                self.legacy_signature_hash(input_index, &script_code, sighash_type)
+            }
+            TransactionType::P2WPKH | TransactionType::P2WSH => {
+                self.segwit_v0_signature_hash(input_index, &script_code, utxo.value(), sighash_type)
+            }
+            TransactionType::P2TR => Err(BitcoinError::UnsupportedScript),
+        }
+    }
+
+    fn input(&self, input_index: usize) -> Result<&TxIn, BitcoinError> {
+        let len = self.input.len();
+        self.input
+            .get(input_index)
+            .ok_or(BitcoinError::InputIndexOutOfRange {
+                index: input_index,
+                len,
+            })
+    }
+
+    fn input_utxos<'a>(&self, utxos: &'a [Utxo]) -> Result<Vec<&'a Utxo>, BitcoinError> {
+        self.input
+            .iter()
+            .enumerate()
+            .map(|(index, input)| find_utxo(utxos, input, index))
+            .collect()
+    }
+
+    fn input_mut(&mut self, input_index: usize) -> Result<&mut TxIn, BitcoinError> {
+        let len = self.input.len();
+        self.input
+            .get_mut(input_index)
+            .ok_or(BitcoinError::InputIndexOutOfRange {
+                index: input_index,
+                len,
+            })
     }
 
     fn encode_for_sighash_for_segwit(
@@ -196,22 +534,14 @@ impl BitcoinTransaction {
         input_index: usize,
         script_code: &ScriptBuf,
         value: u64,
-    ) {
+    ) -> Result<(), std::io::Error> {
         // Version
-        self.version.encode(buffer).unwrap();
-
-        let has_witness = self.input.iter().any(|input| !input.witness.is_empty());
-
-        if has_witness {
-            // Marker and Flag
-            buffer.push(SEGWIT_MARKER);
-            buffer.push(SEGWIT_FLAG);
-        }
+        self.version.encode(buffer)?;
 
         // Hash prevouts
         let mut prevouts = Vec::new();
         for input in &self.input {
-            input.previous_output.encode(&mut prevouts).unwrap();
+            input.previous_output.encode(&mut prevouts)?;
         }
         let prevouts_hash = sha256d(&prevouts);
         buffer.extend_from_slice(&prevouts_hash);
@@ -219,36 +549,35 @@ impl BitcoinTransaction {
         // Hash sequences
         let mut sequences = Vec::new();
         for input in &self.input {
-            input.sequence.encode(&mut sequences).unwrap();
+            input.sequence.encode(&mut sequences)?;
         }
         let sequences_hash = sha256d(&sequences);
         buffer.extend_from_slice(&sequences_hash);
 
         // Outpoint
-        self.input[input_index]
-            .previous_output
-            .encode(buffer)
-            .unwrap();
+        self.input[input_index].previous_output.encode(buffer)?;
 
         // Script code
-        script_code.encode(buffer).unwrap();
+        script_code.encode(buffer)?;
 
         // Value
         buffer.extend_from_slice(&value.to_le_bytes());
 
         // Sequence
-        self.input[input_index].sequence.encode(buffer).unwrap();
+        self.input[input_index].sequence.encode(buffer)?;
 
         // Hash outputs
         let mut outputs = Vec::new();
         for output in &self.output {
-            output.encode(&mut outputs).unwrap();
+            output.encode(&mut outputs)?;
         }
         let outputs_hash = sha256d(&outputs);
         buffer.extend_from_slice(&outputs_hash);
 
         // Locktime
-        self.lock_time.encode(buffer).unwrap();
+        self.lock_time.encode(buffer)?;
+
+        Ok(())
     }
 
     /// Returns whether or not to serialize transaction as specified in BIP-144.
@@ -260,6 +589,20 @@ impl BitcoinTransaction {
         self.input.is_empty()
     }
 
+    /// Parse the verbose JSON of a transaction as returned by `bitcoind decoderawtransaction`
+    pub fn from_bitcoind_json(json: &str) -> Result<Self, near_sdk::serde_json::Error> {
+        let tx: BitcoindTransaction = near_sdk::serde_json::from_str(json)?;
+        Self::try_from(tx).map_err(near_sdk::serde::de::Error::custom)
+    }
+
+    /// Serialise the transaction as the verbose JSON of `bitcoind decoderawtransaction`
+    ///
+    /// The network is used to render the addresses of the output scripts.
+    pub fn to_bitcoind_json(&self, network: Network) -> String {
+        near_sdk::serde_json::to_string(&BitcoindTransaction::from_transaction(self, network))
+            .expect("bitcoind JSON serialization does not fail")
+    }
+
     /// Serialise a JSON representation of the transaction into a BitcoinTransaction struct
     pub fn from_json(json: &str) -> Result<Self, near_sdk::serde_json::Error> {
         let tx: Self = near_sdk::serde_json::from_str(json)?;
@@ -352,6 +695,63 @@ impl Encodable for BitcoinTransaction {
         Ok(len)
     }
 }
+
+impl Decodable for BitcoinTransaction {
+    fn decode_from_finite_reader<R: BufRead + ?Sized>(r: &mut R) -> Result<Self, std::io::Error> {
+        let version = Version::decode_from_finite_reader(r)?;
+        let mut input = Vec::<TxIn>::decode_from_finite_reader(r)?;
+
+        // An empty input list is the BIP-141 marker, followed by the flag.
+        if !input.is_empty() {
+            let output = Vec::<TxOut>::decode_from_finite_reader(r)?;
+            let lock_time = LockTime::decode_from_finite_reader(r)?;
+            return Ok(Self {
+                version,
+                lock_time,
+                input,
+                output,
+            });
+        }
+
+        if u8::decode_from_finite_reader(r)? != SEGWIT_FLAG {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "Unsupported SegWit flag",
+            ));
+        }
+        input = Vec::<TxIn>::decode_from_finite_reader(r)?;
+        let output = Vec::<TxOut>::decode_from_finite_reader(r)?;
+        for txin in &mut input {
+            txin.witness = Witness::decode_from_finite_reader(r)?;
+        }
+        if !input.is_empty() && input.iter().all(|txin| txin.witness.is_empty()) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "SegWit flag set but no witnesses present",
+            ));
+        }
+        let lock_time = LockTime::decode_from_finite_reader(r)?;
+
+        Ok(Self {
+            version,
+            lock_time,
+            input,
+            output,
+        })
+    }
+}
+
+fn find_utxo<'a>(
+    utxos: &'a [Utxo],
+    input: &TxIn,
+    input_index: usize,
+) -> Result<&'a Utxo, BitcoinError> {
+    utxos
+        .iter()
+        .find(|utxo| utxo.outpoint == input.previous_output)
+        .ok_or(BitcoinError::MissingUtxo { index: input_index })
+}
+
 #[cfg(test)]
 mod tests {
     // Omni imports
@@ -800,4 +1200,183 @@ mod tests {
         let result: Result<BitcoinTransaction, _> = serde_json::from_str(json_data);
         assert!(result.is_ok(), "Failed to deserialize: {:?}", result.err());
     }
+
+    fn omni_tx_with_single_input(version: Version) -> OmniBitcoinTransaction {
+        OmniBitcoinTransaction {
+            version,
+            lock_time: LockTime::from_height(0).unwrap(),
+            input: vec![TxIn {
+                previous_output: OmniOutPoint {
+                    txid: OmniTxid(OmniHash::all_zeros()),
+                    vout: 0,
+                },
+                script_sig: OmniScriptBuf::default(),
+                sequence: OmniSequence::default(),
+                witness: OmniWitness::default(),
+            }],
+            output: vec![TxOut {
+                value: OmniAmount::from_sat(10000),
+                script_pubkey: OmniScriptBuf::default(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_deserialize_against_rust_bitcoin() {
+        let mut segwit_tx = omni_tx_with_single_input(Version::Two);
+        segwit_tx.input[0].witness = OmniWitness::from_slice(&[vec![0x30; 71], vec![0x02; 33]]);
+
+        for omni_tx in [omni_tx_with_single_input(Version::One), segwit_tx] {
+            let bytes = omni_tx.serialize();
+            let rust_bitcoin_tx: RustBitcoinTransaction =
+                bitcoin::consensus::deserialize(&bytes).unwrap();
+
+            assert_eq!(
+                OmniBitcoinTransaction::deserialize(&bytes).unwrap(),
+                omni_tx
+            );
+            assert_eq!(bitcoin::consensus::serialize(&rust_bitcoin_tx), bytes);
+        }
+
+        let mut bytes = omni_tx_with_single_input(Version::One).serialize();
+        bytes.push(0x00);
+        assert!(OmniBitcoinTransaction::deserialize(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_forkid_signature_hash_against_rust_bitcoin_bip143_digest() {
+        let omni_tx = omni_tx_with_single_input(Version::One);
+        let script_code =
+            OmniScriptBuf::from_hex("76a914cb8a3018cf279311b148cb8d13728bd8cbe95bda88ac").unwrap();
+
+        let mut rust_bitcoin_tx: RustBitcoinTransaction =
+            bitcoin::consensus::deserialize(&omni_tx.serialize()).unwrap();
+        let mut buffer = Vec::new();
+        SighashCache::new(&mut rust_bitcoin_tx)
+            .segwit_v0_encode_signing_data_to(
+                &mut buffer,
+                0,
+                &ScriptBuf::from_bytes(script_code.as_bytes().to_vec()),
+                Amount::from_sat(50_000),
+                EcdsaSighashType::All,
+            )
+            .unwrap();
+
+        // The digest is BIP-143 with the FORKID flag, and the fork value, in the hash type.
+        for (fork_value, hash_type) in [(0, [0x41, 0, 0, 0]), (0xabcdef, [0x41, 0xef, 0xcd, 0xab])]
+        {
+            let len = buffer.len();
+            buffer[len - 4..].copy_from_slice(&hash_type);
+
+            assert_eq!(
+                omni_tx
+                    .forkid_signature_hash(
+                        0,
+                        &script_code,
+                        OmniAmount::from_sat(50_000),
+                        OmniSighashType::All,
+                        fork_value
+                    )
+                    .unwrap(),
+                sha256d(&buffer)
+            );
+        }
+
+        assert_eq!(
+            omni_tx.forkid_signature_hash(
+                0,
+                &script_code,
+                OmniAmount::ZERO,
+                OmniSighashType::All,
+                0x0100_0000
+            ),
+            Err(BitcoinError::InvalidForkValue(0x0100_0000))
+        );
+    }
+
+    #[test]
+    fn test_try_build_for_signing_segwit_rejects_version_1() {
+        let omni_tx = omni_tx_with_single_input(Version::One);
+
+        let result = omni_tx.try_build_for_signing_segwit(
+            OmniSighashType::All,
+            0,
+            &OmniScriptBuf::default(),
+            0,
+        );
+
+        assert_eq!(
+            result,
+            Err(BitcoinError::InvalidSegwitVersion(Version::One))
+        );
+    }
+
+    #[test]
+    fn test_try_build_for_signing_segwit_accepts_version_3() {
+        let v2 = omni_tx_with_single_input(Version::Two)
+            .try_build_for_signing_segwit(OmniSighashType::All, 0, &OmniScriptBuf::default(), 0)
+            .unwrap();
+        let v3 = omni_tx_with_single_input(Version::Three)
+            .try_build_for_signing_segwit(OmniSighashType::All, 0, &OmniScriptBuf::default(), 0)
+            .unwrap();
+
+        assert_eq!(v3[..4], [3, 0, 0, 0]);
+        assert_eq!(v3[4..], v2[4..]);
+
+        // Version 0 is used by BIP-322 virtual transactions
+        let v0 = omni_tx_with_single_input(Version::Zero)
+            .try_build_for_signing_segwit(OmniSighashType::All, 0, &OmniScriptBuf::default(), 0)
+            .unwrap();
+        assert_eq!(v0[..4], [0, 0, 0, 0]);
+        assert_eq!(v0[4..], v2[4..]);
+    }
+
+    #[test]
+    fn test_try_build_for_signing_segwit_rejects_out_of_range_input() {
+        let omni_tx = omni_tx_with_single_input(Version::Two);
+
+        let result = omni_tx.try_build_for_signing_segwit(
+            OmniSighashType::All,
+            1,
+            &OmniScriptBuf::default(),
+            0,
+        );
+
+        assert_eq!(
+            result,
+            Err(BitcoinError::InputIndexOutOfRange { index: 1, len: 1 })
+        );
+    }
+
+    #[test]
+    fn test_try_build_with_script_sig_and_witness_reject_wrong_transaction_type() {
+        let mut omni_tx = omni_tx_with_single_input(Version::Two);
+
+        let result =
+            omni_tx.try_build_with_script_sig(0, OmniScriptBuf::default(), TransactionType::P2WPKH);
+        assert_eq!(result, Err(BitcoinError::ScriptSigForSegwit));
+
+        let result = omni_tx.try_build_with_witness(0, vec![vec![0x01]], TransactionType::P2PKH);
+        assert_eq!(result, Err(BitcoinError::WitnessForLegacy));
+
+        // The transaction is left untouched
+        assert_eq!(omni_tx, omni_tx_with_single_input(Version::Two));
+    }
+
+    #[test]
+    fn test_try_build_with_witness_matches_panicking_version() {
+        let mut omni_tx = omni_tx_with_single_input(Version::Two);
+        let mut expected_tx = omni_tx.clone();
+
+        let result = omni_tx
+            .try_build_with_witness(0, vec![vec![0x01]], TransactionType::P2WPKH)
+            .unwrap();
+        let expected = expected_tx.build_with_witness(0, vec![vec![0x01]], TransactionType::P2WPKH);
+
+        assert_eq!(result, expected);
+        assert_eq!(
+            omni_tx.try_build_with_witness(2, vec![], TransactionType::P2WPKH),
+            Err(BitcoinError::InputIndexOutOfRange { index: 2, len: 1 })
+        );
+    }
 }
@@ -1,15 +1,27 @@
 //! Transaction builder for Bitcoin transactions
 use super::{
     bitcoin_transaction::BitcoinTransaction,
-    types::{LockTime, TxIn, TxOut, Version},
+    error::BitcoinError,
+    network::Network,
+    types::{Amount, LockTime, Sequence, TxIn, TxOut, Utxo, Version},
 };
 use crate::transaction_builder::TxBuilder;
 
+/// Builds a [`BitcoinTransaction`], created with [`Self::new`] and configured with its setters.
+///
+/// New options may be added in any release, so the builder cannot be created as a struct
+/// literal.
+#[derive(Clone)]
+#[non_exhaustive]
 pub struct BitcoinTransactionBuilder {
     pub version: Option<Version>,
     pub lock_time: Option<LockTime>,
     pub inputs: Option<Vec<TxIn>>,
     pub outputs: Option<Vec<TxOut>>,
+    pub utxos: Option<Vec<Utxo>>,
+    pub network: Option<Network>,
+    pub bip69: bool,
+    pub anti_fee_sniping: Option<(u32, [u8; 32])>,
 }
 
 impl Default for BitcoinTransactionBuilder {
@@ -20,12 +32,7 @@ impl Default for BitcoinTransactionBuilder {
 
 impl TxBuilder<BitcoinTransaction> for BitcoinTransactionBuilder {
     fn build(&self) -> BitcoinTransaction {
-        BitcoinTransaction {
-            version: self.version.expect("Missing version"),
-            lock_time: self.lock_time.expect("Missing lock time"),
-            input: self.inputs.clone().expect("Missing inputs"),
-            output: self.outputs.clone().expect("Missing outputs"),
-        }
+        self.try_build().unwrap_or_else(|e| panic!("{e}"))
     }
 }
 
@@ -36,9 +43,77 @@ impl BitcoinTransactionBuilder {
             lock_time: None,
             inputs: None,
             outputs: None,
+            utxos: None,
+            network: None,
+            bip69: false,
+            anti_fee_sniping: None,
         }
     }
 
+    /// Builds the transaction, returning an error instead of panicking.
+    ///
+    /// When a network is set, outputs below its dust threshold are rejected. A version 3
+    /// transaction may carry a single ephemeral dust output, such as a zero value anchor,
+    /// which Bitcoin Core relays as long as the transaction pays no fee. When UTXOs are set,
+    /// the ephemeral dust output is only allowed if the fee is zero.
+    ///
+    /// When UTXOs are set, every input must spend one of them and the outputs may not spend
+    /// more than they provide. Without inputs, one input per UTXO is created.
+    pub fn try_build(&self) -> Result<BitcoinTransaction, BitcoinError> {
+        let lock_time = match self.anti_fee_sniping {
+            Some((tip_height, seed)) => anti_fee_sniping_lock_time(tip_height, &seed)?,
+            None => self
+                .lock_time
+                .ok_or(BitcoinError::MissingField("lock time"))?,
+        };
+        let mut tx = BitcoinTransaction {
+            version: self.version.ok_or(BitcoinError::MissingField("version"))?,
+            lock_time,
+            input: match (&self.inputs, &self.utxos) {
+                (Some(inputs), _) => inputs.clone(),
+                (None, Some(utxos)) => utxos
+                    .iter()
+                    .map(|utxo| utxo.to_txin(Sequence::MAX))
+                    .collect(),
+                (None, None) => return Err(BitcoinError::MissingField("inputs")),
+            },
+            output: self
+                .outputs
+                .clone()
+                .ok_or(BitcoinError::MissingField("outputs"))?,
+        };
+
+        if self.anti_fee_sniping.is_some() {
+            // The lock time is only enforced if at least one input is not final.
+            for input in tx.input.iter_mut().filter(|i| i.sequence == Sequence::MAX) {
+                input.sequence = Sequence::ENABLE_LOCKTIME_NO_RBF;
+            }
+        }
+        if self.bip69 {
+            tx.sort_bip69();
+        }
+        let fee = match &self.utxos {
+            Some(utxos) => Some(tx.fee(utxos)?),
+            None => None,
+        };
+
+        if let Some(network) = self.network {
+            let pays_fee = fee.is_some_and(|fee| fee != Amount::ZERO);
+            let ephemeral_dust = usize::from(tx.version == Version::Three && !pays_fee);
+            if let Some(index) = (0..tx.output.len())
+                .filter(|&i| network.is_dust(&tx.output[i]))
+                .nth(ephemeral_dust)
+            {
+                return Err(BitcoinError::DustOutput {
+                    index,
+                    threshold: network.dust_threshold(&tx.output[index].script_pubkey),
+                });
+            }
+        }
+
+        Ok(tx)
+    }
+
     pub const fn version(mut self, version: Version) -> Self {
         self.version = Some(version);
         self
@@ -58,11 +133,59 @@ impl BitcoinTransactionBuilder {
         self.outputs = Some(outputs);
         self
     }
+
+    /// UTXOs spent by the transaction, checked against the inputs and outputs when building.
+    ///
+    /// The same UTXOs are then passed to [`BitcoinTransaction::ecdsa_signature_hash`],
+    /// [`BitcoinTransaction::fee`] or [`BitcoinTransaction::verify_utxos`].
+    pub fn utxos(mut self, utxos: Vec<Utxo>) -> Self {
+        self.utxos = Some(utxos);
+        self
+    }
+
+    /// Network the transaction is built for, used to apply its policy rules.
+    pub const fn network(mut self, network: Network) -> Self {
+        self.network = Some(network);
+        self
+    }
+
+    /// Sorts the inputs and outputs as specified by BIP-69 when building, so that every signer
+    /// derives the same transaction and sighashes from the same inputs and outputs.
+    pub const fn bip69(mut self, enabled: bool) -> Self {
+        self.bip69 = enabled;
+        self
+    }
+
+    /// Sets the lock time from the current chain tip to discourage fee sniping, like the
+    /// Bitcoin Core wallet, replacing any lock time set with [`Self::lock_time`].
+    ///
+    /// The random choices are drawn from `seed`, such as the NEAR block random seed, so that
+    /// signers sharing it build the same transaction. Final inputs are made non-final, as the
+    /// lock time would be ignored otherwise.
+    pub const fn anti_fee_sniping(mut self, tip_height: u32, seed: [u8; 32]) -> Self {
+        self.anti_fee_sniping = Some((tip_height, seed));
+        self
+    }
+}
+
+/// Mirrors `DiscourageFeeSniping` of Bitcoin Core: the lock time is the tip height, moved back
+/// by up to 99 blocks one time in ten so that transactions delayed for privacy do not stand out.
+fn anti_fee_sniping_lock_time(tip_height: u32, seed: &[u8; 32]) -> Result<LockTime, BitcoinError> {
+    let roll = u32::from_le_bytes(seed[..4].try_into().expect("4 byte slice"));
+    let delay = u32::from_le_bytes(seed[4..8].try_into().expect("4 byte slice")) % 100;
+
+    let height = if roll % 10 == 0 {
+        tip_height.saturating_sub(delay)
+    } else {
+        tip_height
+    };
+    LockTime::from_height(height).map_err(BitcoinError::InvalidLockTime)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::bitcoin::types::ScriptBuf;
 
     #[test]
     fn test_build() {
@@ -91,4 +214,313 @@ mod tests {
             .outputs(vec![])
             .build();
     }
+
+    #[test]
+    fn test_try_build_with_network_rejects_dust_outputs() {
+        let p2wpkh = ScriptBuf::from_hex("0014751e76e8199196d454941c45d1b3a323f1433bd6").unwrap();
+        let builder = BitcoinTransactionBuilder::new()
+            .version(Version::Two)
+            .lock_time(LockTime::from_height(0).unwrap())
+            .inputs(vec![])
+            .network(Network::Mainnet);
+
+        let result = builder
+            .clone()
+            .outputs(vec![TxOut {
+                value: Amount::from_sat(293),
+                script_pubkey: p2wpkh.clone(),
+            }])
+            .try_build();
+        assert_eq!(
+            result,
+            Err(BitcoinError::DustOutput {
+                index: 0,
+                threshold: Amount::from_sat(294)
+            })
+        );
+
+        let result = builder
+            .outputs(vec![TxOut {
+                value: Amount::from_sat(294),
+                script_pubkey: p2wpkh,
+            }])
+            .try_build();
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_try_build_with_ephemeral_dust() {
+        use crate::bitcoin::types::{Hash, OutPoint, TransactionType, Txid};
+
+        let p2wpkh = ScriptBuf::from_hex("0014751e76e8199196d454941c45d1b3a323f1433bd6").unwrap();
+        let output = |sats| TxOut {
+            value: Amount::from_sat(sats),
+            script_pubkey: p2wpkh.clone(),
+        };
+        let utxo = Utxo::new(
+            OutPoint::new(Txid(Hash([0x11; 32])), 0),
+            output(10_000),
+            TransactionType::P2WPKH,
+        );
+        let builder = BitcoinTransactionBuilder::new()
+            .version(Version::Three)
+            .lock_time(LockTime::from_height(0).unwrap())
+            .network(Network::Mainnet);
+
+        // Without UTXOs the fee is unknown, and a single dust output is allowed
+        let result = builder
+            .clone()
+            .inputs(vec![])
+            .outputs(vec![output(10_000), output(0)])
+            .try_build();
+        assert!(result.is_ok());
+
+        let result = builder
+            .clone()
+            .inputs(vec![])
+            .outputs(vec![output(0), output(0)])
+            .try_build();
+        assert_eq!(
+            result,
+            Err(BitcoinError::DustOutput {
+                index: 1,
+                threshold: Amount::from_sat(294)
+            })
+        );
+
+        let result = builder
+            .clone()
+            .utxos(vec![utxo.clone()])
+            .outputs(vec![output(10_000), output(0)])
+            .try_build();
+        assert!(result.is_ok());
+
+        // A transaction paying a fee may not carry ephemeral dust
+        let result = builder
+            .utxos(vec![utxo])
+            .outputs(vec![output(9_000), output(0)])
+            .try_build();
+        assert_eq!(
+            result,
+            Err(BitcoinError::DustOutput {
+                index: 1,
+                threshold: Amount::from_sat(294)
+            })
+        );
+
+        let result = BitcoinTransactionBuilder::new()
+            .version(Version::Two)
+            .lock_time(LockTime::from_height(0).unwrap())
+            .inputs(vec![])
+            .outputs(vec![output(10_000), output(0)])
+            .network(Network::Mainnet)
+            .try_build();
+        assert_eq!(
+            result,
+            Err(BitcoinError::DustOutput {
+                index: 1,
+                threshold: Amount::from_sat(294)
+            })
+        );
+    }
+
+    #[test]
+    fn test_try_build_with_bip69_ordering() {
+        use crate::bitcoin::types::{Hash, OutPoint, Txid, Witness};
+
+        let input = |txid: &str, vout| TxIn {
+            previous_output: OutPoint::new(Txid(Hash::from_hex(txid).unwrap()), vout),
+            script_sig: ScriptBuf::default(),
+            sequence: Sequence::MAX,
+            witness: Witness::new(),
+        };
+        let output = |sats, script: &str| TxOut {
+            value: Amount::from_sat(sats),
+            script_pubkey: ScriptBuf::from_hex(script).unwrap(),
+        };
+        let a = "0e53ec5dfb2cb8a71fec32dc9a634a35b7e24799295ddd5278217822e0b31f57";
+        let b = "26aa6e6d8b9e49bb0630aac301db6757c02e3619feb4ee0eea81eb1672947024";
+        let c = "7d037ceb2ee0dc03e82f17be7935d238b35d1deabf953a892a4507bfbeeb3ba4";
+
+        let tx = BitcoinTransactionBuilder::new()
+            .version(Version::Two)
+            .lock_time(LockTime::from_height(0).unwrap())
+            .inputs(vec![input(c, 0), input(a, 1), input(b, 7), input(a, 0)])
+            .outputs(vec![
+                output(2_000, "76a9144a5fba237213a062f6f57978f796390bdcf8d01588ac"),
+                output(1_000, "76a9145be32612930b8323add2212a4ec03c1562084f8488ac"),
+                output(2_000, "76a914000000000000000000000000000000000000000088ac"),
+            ])
+            .bip69(true)
+            .build();
+
+        let inputs: Vec<_> = tx
+            .input
+            .iter()
+            .map(|i| (i.previous_output.txid.to_string(), i.previous_output.vout))
+            .collect();
+        assert_eq!(
+            inputs,
+            [(a.into(), 0), (a.into(), 1), (b.into(), 7), (c.into(), 0)]
+        );
+
+        let outputs: Vec<_> = tx
+            .output
+            .iter()
+            .map(|o| (o.value.to_sat(), hex::encode(o.script_pubkey.as_bytes())))
+            .collect();
+        assert_eq!(
+            outputs,
+            [
+                (
+                    1_000,
+                    "76a9145be32612930b8323add2212a4ec03c1562084f8488ac".into()
+                ),
+                (
+                    2_000,
+                    "76a914000000000000000000000000000000000000000088ac".into()
+                ),
+                (
+                    2_000,
+                    "76a9144a5fba237213a062f6f57978f796390bdcf8d01588ac".into()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_try_build_with_anti_fee_sniping() {
+        use crate::bitcoin::types::{OutPoint, Witness};
+
+        let builder = BitcoinTransactionBuilder::new()
+            .version(Version::Two)
+            .lock_time(LockTime::from_height(1).unwrap())
+            .inputs(vec![TxIn {
+                previous_output: OutPoint::null(),
+                script_sig: ScriptBuf::default(),
+                sequence: Sequence::MAX,
+                witness: Witness::new(),
+            }])
+            .outputs(vec![]);
+
+        // A roll that is not a multiple of ten keeps the tip height.
+        let mut seed = [0u8; 32];
+        seed[0] = 3;
+        seed[4] = 42;
+        let tx = builder.clone().anti_fee_sniping(850_000, seed).build();
+        assert_eq!(tx.lock_time, LockTime::from_height(850_000).unwrap());
+        assert_eq!(tx.input[0].sequence, Sequence::ENABLE_LOCKTIME_NO_RBF);
+
+        // Otherwise the lock time moves back by the delay drawn from the seed.
+        seed[0] = 20;
+        let tx = builder.clone().anti_fee_sniping(850_000, seed).build();
+        assert_eq!(tx.lock_time, LockTime::from_height(849_958).unwrap());
+        assert_eq!(tx, builder.clone().anti_fee_sniping(850_000, seed).build());
+
+        let tx = builder.clone().anti_fee_sniping(10, seed).build();
+        assert_eq!(tx.lock_time, LockTime::from_height(0).unwrap());
+
+        assert!(matches!(
+            builder.anti_fee_sniping(500_000_000, [1; 32]).try_build(),
+            Err(BitcoinError::InvalidLockTime(_))
+        ));
+    }
+
+    #[test]
+    fn test_try_build_missing_field() {
+        let result = BitcoinTransactionBuilder::new()
+            .version(Version::One)
+            .inputs(vec![])
+            .outputs(vec![])
+            .try_build();
+
+        assert_eq!(result, Err(BitcoinError::MissingField("lock time")));
+    }
+
+    #[test]
+    fn test_try_build_with_utxos() {
+        use crate::bitcoin::types::{Hash, OutPoint, TransactionType, Txid};
+
+        let p2wpkh = ScriptBuf::from_hex("0014751e76e8199196d454941c45d1b3a323f1433bd6").unwrap();
+        let utxo = |byte, sats| {
+            Utxo::new(
+                OutPoint::new(Txid(Hash([byte; 32])), 0),
+                TxOut {
+                    value: Amount::from_sat(sats),
+                    script_pubkey: p2wpkh.clone(),
+                },
+                TransactionType::P2WPKH,
+            )
+        };
+        let utxos = vec![utxo(0x22, 20_000), utxo(0x11, 10_000)];
+        let builder = BitcoinTransactionBuilder::new()
+            .version(Version::Two)
+            .lock_time(LockTime::from_height(0).unwrap())
+            .utxos(utxos.clone())
+            .bip69(true);
+
+        let tx = builder
+            .clone()
+            .outputs(vec![TxOut {
+                value: Amount::from_sat(29_000),
+                script_pubkey: p2wpkh.clone(),
+            }])
+            .try_build()
+            .unwrap();
+        assert_eq!(tx.input[0].previous_output, utxos[1].outpoint);
+        assert_eq!(tx.input[1].previous_output, utxos[0].outpoint);
+        assert_eq!(tx.fee(&utxos), Ok(Amount::from_sat(1_000)));
+
+        let result = builder
+            .clone()
+            .outputs(vec![TxOut {
+                value: Amount::from_sat(31_000),
+                script_pubkey: p2wpkh.clone(),
+            }])
+            .try_build();
+        assert_eq!(
+            result,
+            Err(BitcoinError::InsufficientFunds {
+                inputs: Amount::from_sat(30_000),
+                outputs: Amount::from_sat(31_000)
+            })
+        );
+
+        let result = builder
+            .inputs(vec![utxo(0x33, 0).to_txin(Sequence::MAX)])
+            .outputs(vec![])
+            .try_build();
+        assert_eq!(result, Err(BitcoinError::MissingUtxo { index: 0 }));
+    }
+
+    #[test]
+    fn test_try_build_with_overflowing_utxos() {
+        use crate::bitcoin::types::{Hash, OutPoint, TransactionType, Txid};
+
+        let p2wpkh = ScriptBuf::from_hex("0014751e76e8199196d454941c45d1b3a323f1433bd6").unwrap();
+        let utxo = |byte| {
+            Utxo::new(
+                OutPoint::new(Txid(Hash([byte; 32])), 0),
+                TxOut {
+                    value: Amount::from_sat(u64::MAX / 2 + 1),
+                    script_pubkey: p2wpkh.clone(),
+                },
+                TransactionType::P2WPKH,
+            )
+        };
+        let utxos = vec![utxo(0x11), utxo(0x22)];
+        let builder = BitcoinTransactionBuilder::new()
+            .version(Version::Two)
+            .lock_time(LockTime::from_height(0).unwrap())
+            .utxos(utxos.clone());
+
+        let result = builder.clone().outputs(vec![]).try_build();
+        assert_eq!(result, Err(BitcoinError::AmountOverflow));
+
+        let result = builder
+            .utxos(vec![utxos[0].clone()])
+            .outputs(vec![utxos[0].txout.clone(), utxos[1].txout.clone()])
+            .try_build();
+        assert_eq!(result, Err(BitcoinError::AmountOverflow));
+    }
 }
@@ -0,0 +1,405 @@
+//! The verbose transaction JSON of Bitcoin Core (`decoderawtransaction`, `getrawtransaction`).
+//!
+//! These types mirror the JSON returned by a node, so it can be deserialized directly and
+//! converted into a [`BitcoinTransaction`]. The `asm` and `desc` fields are accepted but not
+//! produced.
+use near_sdk::serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::{
+    address::Address,
+    bitcoin_transaction::BitcoinTransaction,
+    error::BitcoinError,
+    network::Network,
+    types::{
+        Amount, Denomination, Hash, LockTime, OutPoint, ScriptBuf, Sequence, TxIn, TxOut, Txid,
+        Version, Witness,
+    },
+};
+
+/// A transaction in the verbose JSON format of Bitcoin Core.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BitcoindTransaction {
+    /// The transaction id.
+    #[serde(default)]
+    pub txid: String,
+    /// The witness transaction id.
+    #[serde(default)]
+    pub hash: String,
+    /// The transaction version.
+    pub version: i32,
+    /// The serialized size in bytes.
+    #[serde(default)]
+    pub size: u64,
+    /// The virtual size in vbytes.
+    #[serde(default)]
+    pub vsize: u64,
+    /// The weight in weight units.
+    #[serde(default)]
+    pub weight: u64,
+    /// The lock time.
+    pub locktime: u32,
+    /// The inputs.
+    pub vin: Vec<BitcoindTxIn>,
+    /// The outputs.
+    pub vout: Vec<BitcoindTxOut>,
+}
+
+/// An input in the verbose JSON format of Bitcoin Core.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BitcoindTxIn {
+    /// The script sig of a coinbase input, in hex. Coinbase inputs have no outpoint.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coinbase: Option<String>,
+    /// The id of the transaction of the spent output.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub txid: Option<String>,
+    /// The index of the spent output.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vout: Option<u32>,
+    /// The script sig.
+    #[serde(rename = "scriptSig", default, skip_serializing_if = "Option::is_none")]
+    pub script_sig: Option<BitcoindScript>,
+    /// The witness items, in hex.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub txinwitness: Vec<String>,
+    /// The sequence number.
+    pub sequence: u32,
+}
+
+/// An output in the verbose JSON format of Bitcoin Core.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BitcoindTxOut {
+    /// The value, in BTC.
+    #[serde(with = "value_in_btc")]
+    pub value: Amount,
+    /// The index of the output.
+    #[serde(default)]
+    pub n: u32,
+    /// The output script.
+    #[serde(rename = "scriptPubKey")]
+    pub script_pubkey: BitcoindScript,
+}
+
+/// A script in the verbose JSON format of Bitcoin Core.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BitcoindScript {
+    /// The script, in hex.
+    pub hex: String,
+    /// The address of an output script, if it is a standard one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    /// The type of an output script, such as `witness_v0_keyhash`.
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub script_type: Option<String>,
+}
+
+impl BitcoindTransaction {
+    /// Converts a transaction to the verbose JSON format, rendering addresses for `network`.
+    pub fn from_transaction(tx: &BitcoinTransaction, network: Network) -> Self {
+        let vin = tx
+            .input
+            .iter()
+            .map(|input| {
+                let witness = input.witness.iter().map(hex::encode).collect();
+                if input.previous_output.is_null() {
+                    BitcoindTxIn {
+                        coinbase: Some(hex::encode(input.script_sig.as_bytes())),
+                        txid: None,
+                        vout: None,
+                        script_sig: None,
+                        txinwitness: witness,
+                        sequence: input.sequence.0,
+                    }
+                } else {
+                    BitcoindTxIn {
+                        coinbase: None,
+                        txid: Some(input.previous_output.txid.to_string()),
+                        vout: Some(input.previous_output.vout),
+                        script_sig: Some(BitcoindScript {
+                            hex: hex::encode(input.script_sig.as_bytes()),
+                            address: None,
+                            script_type: None,
+                        }),
+                        txinwitness: witness,
+                        sequence: input.sequence.0,
+                    }
+                }
+            })
+            .collect();
+
+        let vout = tx
+            .output
+            .iter()
+            .zip(0..)
+            .map(|(output, n)| BitcoindTxOut {
+                value: output.value,
+                n,
+                script_pubkey: BitcoindScript {
+                    hex: hex::encode(output.script_pubkey.as_bytes()),
+                    address: Address::from_script(&output.script_pubkey, network)
+                        .ok()
+                        .map(|address| address.to_string()),
+                    script_type: Some(script_type(&output.script_pubkey).to_string()),
+                },
+            })
+            .collect();
+
+        Self {
+            txid: tx.compute_txid().to_string(),
+            hash: tx.compute_wtxid().to_string(),
+            version: tx.version as i32,
+            size: tx.serialize().len() as u64,
+            vsize: tx.vsize(),
+            weight: tx.weight(),
+            locktime: tx.lock_time.to_u32(),
+            vin,
+            vout,
+        }
+    }
+}
+
+impl TryFrom<BitcoindTransaction> for BitcoinTransaction {
+    type Error = BitcoinError;
+
+    fn try_from(tx: BitcoindTransaction) -> Result<Self, Self::Error> {
+        let version = match tx.version {
+            0 => Version::Zero,
+            1 => Version::One,
+            2 => Version::Two,
+            3 => Version::Three,
+            version => {
+                return Err(BitcoinError::Encoding(format!(
+                    "Unsupported transaction version {version}"
+                )))
+            }
+        };
+
+        let input = tx
+            .vin
+            .into_iter()
+            .map(|input| {
+                let (previous_output, script_sig) = match input.coinbase {
+                    Some(coinbase) => (OutPoint::null(), coinbase),
+                    None => {
+                        let txid = input.txid.ok_or(BitcoinError::MissingField("vin txid"))?;
+                        let vout = input.vout.ok_or(BitcoinError::MissingField("vin vout"))?;
+                        let script_sig = input.script_sig.map(|s| s.hex).unwrap_or_default();
+                        (OutPoint::new(Txid(parse_hash(&txid)?), vout), script_sig)
+                    }
+                };
+                let witness = input
+                    .txinwitness
+                    .iter()
+                    .map(|item| parse_hex(item))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                Ok(TxIn {
+                    previous_output,
+                    script_sig: ScriptBuf::from_bytes(parse_hex(&script_sig)?),
+                    sequence: Sequence(input.sequence),
+                    witness: Witness::from_slice(&witness),
+                })
+            })
+            .collect::<Result<_, BitcoinError>>()?;
+
+        let output = tx
+            .vout
+            .into_iter()
+            .map(|output| {
+                Ok(TxOut {
+                    value: output.value,
+                    script_pubkey: ScriptBuf::from_bytes(parse_hex(&output.script_pubkey.hex)?),
+                })
+            })
+            .collect::<Result<_, BitcoinError>>()?;
+
+        Ok(Self {
+            version,
+            lock_time: LockTime::from_consensus(tx.locktime),
+            input,
+            output,
+        })
+    }
+}
+
+/// The output script type names used by Bitcoin Core.
+fn script_type(script: &ScriptBuf) -> &'static str {
+    if script.is_p2a() {
+        "anchor"
+    } else if script.is_p2pkh() {
+        "pubkeyhash"
+    } else if script.is_p2sh() {
+        "scripthash"
+    } else if script.is_p2wpkh() {
+        "witness_v0_keyhash"
+    } else if script.is_p2wsh() {
+        "witness_v0_scripthash"
+    } else if script.is_p2tr() {
+        "witness_v1_taproot"
+    } else if script.is_witness_program() {
+        "witness_unknown"
+    } else if script.is_op_return() {
+        "nulldata"
+    } else {
+        "nonstandard"
+    }
+}
+
+fn parse_hex(s: &str) -> Result<Vec<u8>, BitcoinError> {
+    hex::decode(s).map_err(|e| BitcoinError::Encoding(format!("Invalid hex {s}: {e}")))
+}
+
+fn parse_hash(s: &str) -> Result<Hash, BitcoinError> {
+    let bytes: [u8; 32] = parse_hex(s)?
+        .try_into()
+        .map_err(|_| BitcoinError::Encoding(format!("Invalid hash length: {s}")))?;
+    Ok(Hash(bytes))
+}
+
+/// Serializes an [`Amount`] as a JSON number of BTC, like Bitcoin Core.
+mod value_in_btc {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(amount: &Amount, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(amount.to_sat() as f64 / 100_000_000.0)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Amount, D::Error> {
+        // Every amount with 8 decimals has a distinct f64, whose shortest representation
+        // is the amount itself.
+        let btc = f64::deserialize(deserializer)?;
+        Amount::from_str_in(&btc.to_string(), Denomination::Bitcoin)
+            .map_err(near_sdk::serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A P2WPKH spend as decoded by `bitcoind decoderawtransaction`, trimmed of asm and desc.
+    const BITCOIND_JSON: &str = r#"{
+        "txid": "c586389e5e4b3acb9d6c8be1c19ae8ab2795397633176f5a6442a261bbdefc3a",
+        "hash": "b759d39a8596b70b3a46700b83e1edb247e17ba58df305421864fe7a9ac142ea",
+        "version": 2,
+        "size": 222,
+        "vsize": 141,
+        "weight": 561,
+        "locktime": 0,
+        "vin": [
+            {
+                "txid": "ac4994014aa36b7f53375658ef595b3cb2891e1735fe5b441686f5e53338e76a",
+                "vout": 1,
+                "scriptSig": { "asm": "", "hex": "" },
+                "txinwitness": [
+                    "304402203b3b0af8fbf3f6c31cf6c52ad2c5b8f3cd2ac6ed3c2f2e1b2d1a1a8c31b6d4e50220157b13a5df3e23a4b8f7ec1c3f32ad4b7bd5d6b2e5a8f7d7e0b0a1f9b3c8d7e601",
+                    "02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5"
+                ],
+                "sequence": 4294967293
+            }
+        ],
+        "vout": [
+            {
+                "value": 0.015,
+                "n": 0,
+                "scriptPubKey": {
+                    "asm": "0 751e76e8199196d454941c45d1b3a323f1433bd6",
+                    "hex": "0014751e76e8199196d454941c45d1b3a323f1433bd6",
+                    "address": "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+                    "type": "witness_v0_keyhash"
+                }
+            },
+            {
+                "value": 20999999.99999999,
+                "n": 1,
+                "scriptPubKey": {
+                    "asm": "OP_RETURN 68656c6c6f",
+                    "hex": "6a0568656c6c6f",
+                    "type": "nulldata"
+                }
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn test_from_bitcoind_json_against_rust_bitcoin() {
+        let tx = BitcoinTransaction::from_bitcoind_json(BITCOIND_JSON).unwrap();
+        let rust_bitcoin_tx: bitcoin::Transaction =
+            bitcoin::consensus::deserialize(&tx.serialize()).unwrap();
+
+        assert_eq!(tx.version, Version::Two);
+        assert_eq!(tx.input[0].sequence, Sequence(0xffff_fffd));
+        assert_eq!(tx.input[0].witness.len(), 2);
+        assert_eq!(tx.output[0].value, Amount::from_sat(1_500_000));
+        assert_eq!(tx.output[1].value, Amount::from_sat(2_099_999_999_999_999));
+        assert_eq!(
+            rust_bitcoin_tx.input[0].previous_output.txid.to_string(),
+            "ac4994014aa36b7f53375658ef595b3cb2891e1735fe5b441686f5e53338e76a"
+        );
+        assert_eq!(
+            tx.compute_txid().to_string(),
+            rust_bitcoin_tx.compute_txid().to_string()
+        );
+        assert_eq!(
+            tx.compute_wtxid().to_string(),
+            rust_bitcoin_tx.compute_wtxid().to_string()
+        );
+        assert_eq!(tx.weight(), rust_bitcoin_tx.weight().to_wu());
+        assert_eq!(tx.vsize(), rust_bitcoin_tx.vsize() as u64);
+    }
+
+    #[test]
+    fn test_to_bitcoind_json_roundtrip() {
+        let tx = BitcoinTransaction::from_bitcoind_json(BITCOIND_JSON).unwrap();
+        let json = tx.to_bitcoind_json(Network::Mainnet);
+        let value: near_sdk::serde_json::Value = near_sdk::serde_json::from_str(&json).unwrap();
+
+        assert_eq!(value["txid"], tx.compute_txid().to_string());
+        assert_eq!(value["vin"][0]["scriptSig"]["hex"], "");
+        assert_eq!(value["vout"][0]["value"], 0.015);
+        assert_eq!(
+            value["vout"][0]["scriptPubKey"]["address"],
+            "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+        );
+        assert_eq!(value["vout"][1]["scriptPubKey"]["type"], "nulldata");
+        assert!(value["vout"][1]["scriptPubKey"].get("address").is_none());
+        assert_eq!(BitcoinTransaction::from_bitcoind_json(&json).unwrap(), tx);
+    }
+
+    #[test]
+    fn test_coinbase_input() {
+        let json = r#"{
+            "version": 1,
+            "locktime": 0,
+            "vin": [{ "coinbase": "04ffff001d0104", "sequence": 4294967295 }],
+            "vout": [{ "value": 50.0, "scriptPubKey": { "hex": "51" } }]
+        }"#;
+
+        let tx = BitcoinTransaction::from_bitcoind_json(json).unwrap();
+
+        assert!(tx.input[0].previous_output.is_null());
+        assert_eq!(
+            tx.input[0].script_sig.as_bytes(),
+            [0x04, 0xff, 0xff, 0x00, 0x1d, 0x01, 0x04]
+        );
+        assert_eq!(tx.output[0].value, Amount::from_int_btc(50));
+
+        let roundtrip: near_sdk::serde_json::Value =
+            near_sdk::serde_json::from_str(&tx.to_bitcoind_json(Network::Mainnet)).unwrap();
+        assert_eq!(roundtrip["vin"][0]["coinbase"], "04ffff001d0104");
+        assert!(roundtrip["vin"][0].get("txid").is_none());
+    }
+
+    #[test]
+    fn test_from_bitcoind_json_rejects_invalid_values() {
+        let json = BITCOIND_JSON.replace("0.015", "0.000000001");
+        assert!(BitcoinTransaction::from_bitcoind_json(&json).is_err());
+
+        let json = BITCOIND_JSON.replace("\"version\": 2", "\"version\": 7");
+        assert!(BitcoinTransaction::from_bitcoind_json(&json).is_err());
+    }
+}
@@ -0,0 +1,733 @@
+//! Output script descriptors (BIP-380) with concrete public keys.
+//!
+//! Supports `pkh()`, `wpkh()`, `sh(wpkh())`, `sh(multi())`, `wsh(multi())`, their
+//! `sortedmulti()` variants and key path only `tr()`. Extended keys are not supported.
+use std::{fmt, str::FromStr};
+
+use super::{
+    address::{bech32, Address},
+    error::BitcoinError,
+    hashes::{hash160, sha256},
+    network::Network,
+    taproot::tweak_internal_key,
+    types::{
+        opcodes::{OP_1, OP_CHECKMULTISIG},
+        ScriptBuf, TransactionType,
+    },
+};
+
+/// Characters allowed in a descriptor, in the order used by the checksum.
+const INPUT_CHARSET: &str =
+    "0123456789()[],'/*abcdefgh@:$%{}IJKLMNOPQRSTUVWXYZ&+-.;<=>?!^_|~ijklmnopqrstuvwxyzABCDEFGH`#\"\\ ";
+
+/// Maximum number of keys in a `multi()` inside `sh()`.
+const MAX_PUBKEYS_PER_P2SH_MULTISIG: usize = 16;
+/// Maximum number of keys in a `multi()` inside `wsh()`.
+const MAX_PUBKEYS_PER_MULTISIG: usize = 20;
+/// Maximum size of a P2SH redeem script.
+const MAX_SCRIPT_ELEMENT_SIZE: usize = 520;
+/// Bit set on hardened derivation steps.
+const HARDENED: u32 = 0x8000_0000;
+
+/// Where a key comes from: the fingerprint of the master key and the derivation path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyOrigin {
+    /// The first four bytes of the HASH160 of the master public key.
+    pub fingerprint: [u8; 4],
+    /// The derivation steps from the master key, with hardened steps offset by 2^31.
+    pub derivation_path: Vec<u32>,
+}
+
+/// A concrete public key of a descriptor, with its optional origin.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DescriptorPublicKey {
+    /// The origin of the key, written as `[fingerprint/path]` in front of it.
+    pub origin: Option<KeyOrigin>,
+    /// The serialized key: 33 bytes compressed, 65 bytes uncompressed or 32 bytes x-only.
+    pub key: Vec<u8>,
+}
+
+impl DescriptorPublicKey {
+    /// The key in its x-only form, as used by Taproot.
+    ///
+    /// Fails for uncompressed keys and keys of any other length.
+    fn x_only(&self) -> Result<[u8; 32], BitcoinError> {
+        let x = match self.key.len() {
+            32 => &self.key[..],
+            33 => &self.key[1..],
+            _ => return Err(BitcoinError::InvalidTaprootKey),
+        };
+        x.try_into().map_err(|_| BitcoinError::InvalidTaprootKey)
+    }
+
+    fn is_compressed(&self) -> bool {
+        self.key.len() == 33
+    }
+}
+
+/// A `multi()` or `sortedmulti()` expression.
+///
+/// Built with [`Multi::new`], which keeps the threshold and the number of keys in the range
+/// the script can encode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Multi {
+    threshold: usize,
+    keys: Vec<DescriptorPublicKey>,
+    sorted: bool,
+}
+
+impl Multi {
+    /// Creates a `multi()`, or a `sortedmulti()` if `sorted` is set.
+    ///
+    /// Fails unless there are between 1 and 20 keys and the threshold is between 1 and the
+    /// number of keys.
+    pub fn new(
+        threshold: usize,
+        keys: Vec<DescriptorPublicKey>,
+        sorted: bool,
+    ) -> Result<Self, BitcoinError> {
+        if keys.is_empty() || keys.len() > MAX_PUBKEYS_PER_MULTISIG {
+            return Err(invalid(format!(
+                "multisig must have between 1 and {MAX_PUBKEYS_PER_MULTISIG} keys"
+            )));
+        }
+        if threshold == 0 || threshold > keys.len() {
+            return Err(invalid(format!(
+                "threshold {threshold} must be between 1 and {}",
+                keys.len()
+            )));
+        }
+
+        Ok(Self {
+            threshold,
+            keys,
+            sorted,
+        })
+    }
+
+    /// Number of signatures required.
+    pub const fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    /// The public keys, in the order given by the descriptor.
+    pub fn keys(&self) -> &[DescriptorPublicKey] {
+        &self.keys
+    }
+
+    /// Whether the keys are sorted in the script (`sortedmulti()`).
+    pub const fn is_sorted(&self) -> bool {
+        self.sorted
+    }
+
+    /// The bare multisig script `<k> <keys> <n> OP_CHECKMULTISIG`.
+    pub fn script(&self) -> ScriptBuf {
+        let mut keys: Vec<&[u8]> = self.keys.iter().map(|k| k.key.as_slice()).collect();
+        if self.sorted {
+            keys.sort_unstable();
+        }
+
+        let mut script = ScriptBuf::default();
+        push_small_int(&mut script, self.threshold);
+        for key in keys {
+            script.push_slice(key);
+        }
+        push_small_int(&mut script, self.keys.len());
+        script.push_opcode(OP_CHECKMULTISIG);
+        script
+    }
+}
+
+/// An output script descriptor.
+///
+/// ###### Example:
+///
+/// ```rust
+/// let descriptor: Descriptor = "wpkh(0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798)"
+///     .parse()
+///     .unwrap();
+///
+/// let script_pubkey = descriptor.script_pubkey().unwrap();
+/// let script_code = descriptor.script_code().unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Descriptor {
+    /// `pkh(KEY)`: pay to public key hash.
+    Pkh(DescriptorPublicKey),
+    /// `wpkh(KEY)`: pay to witness public key hash.
+    Wpkh(DescriptorPublicKey),
+    /// `sh(wpkh(KEY))`: pay to witness public key hash nested in P2SH.
+    ShWpkh(DescriptorPublicKey),
+    /// `sh(multi(...))`: bare multisig nested in P2SH.
+    Sh(Multi),
+    /// `wsh(multi(...))`: pay to witness script hash of a multisig script.
+    Wsh(Multi),
+    /// `tr(KEY)`: Taproot output spendable through the key path only.
+    Tr(DescriptorPublicKey),
+}
+
+impl Descriptor {
+    /// Parses a descriptor, validating its checksum when present.
+    pub fn parse(s: &str) -> Result<Self, BitcoinError> {
+        let descriptor = match s.split_once('#') {
+            Some((descriptor, checksum)) => {
+                let expected = descriptor_checksum(descriptor)?;
+                if checksum != expected {
+                    return Err(invalid(format!(
+                        "checksum mismatch: expected {expected}, got {checksum}"
+                    )));
+                }
+                descriptor
+            }
+            None => s,
+        };
+
+        let (name, args) = split_function(descriptor)?;
+        match name {
+            "pkh" => {
+                let key = parse_key(args)?;
+                if key.key.len() == 32 {
+                    return Err(invalid("x-only keys are only allowed in tr()"));
+                }
+                Ok(Self::Pkh(key))
+            }
+            "wpkh" => Ok(Self::Wpkh(parse_compressed_key(args)?)),
+            "sh" => match split_function(args)? {
+                ("wpkh", key) => Ok(Self::ShWpkh(parse_compressed_key(key)?)),
+                ("multi", multi) | ("sortedmulti", multi) => {
+                    let multi = parse_multi(args, multi, MAX_PUBKEYS_PER_P2SH_MULTISIG)?;
+                    if multi.keys.iter().any(|k| k.key.len() == 32) {
+                        return Err(invalid("x-only keys are only allowed in tr()"));
+                    }
+                    if multi.script().len() > MAX_SCRIPT_ELEMENT_SIZE {
+                        return Err(invalid("redeem script exceeds 520 bytes"));
+                    }
+                    Ok(Self::Sh(multi))
+                }
+                (name, _) => Err(invalid(format!("unsupported sh({name}())"))),
+            },
+            "wsh" => match split_function(args)? {
+                ("multi", multi) | ("sortedmulti", multi) => {
+                    let multi = parse_multi(args, multi, MAX_PUBKEYS_PER_MULTISIG)?;
+                    if multi.keys.iter().any(|k| !k.is_compressed()) {
+                        return Err(invalid("wsh() requires compressed keys"));
+                    }
+                    Ok(Self::Wsh(multi))
+                }
+                (name, _) => Err(invalid(format!("unsupported wsh({name}())"))),
+            },
+            "tr" => {
+                if args.contains(',') {
+                    return Err(invalid("tr() script trees are not supported"));
+                }
+                let key = parse_key(args)?;
+                if key.key.len() == 65 {
+                    return Err(invalid("tr() requires an x-only or compressed key"));
+                }
+                Ok(Self::Tr(key))
+            }
+            name => Err(invalid(format!("unsupported descriptor {name}()"))),
+        }
+    }
+
+    /// The output script locked by the descriptor.
+    ///
+    /// Fails for a `tr()` descriptor whose key is not a valid Taproot internal key, which
+    /// parsing rules out but a descriptor built by hand may hold.
+    pub fn script_pubkey(&self) -> Result<ScriptBuf, BitcoinError> {
+        Ok(match self {
+            Self::Pkh(key) => ScriptBuf::new_p2pkh(&hash160(&key.key)),
+            Self::Wpkh(key) => ScriptBuf::new_p2wpkh(&hash160(&key.key)),
+            Self::ShWpkh(key) => {
+                let redeem_script = ScriptBuf::new_p2wpkh(&hash160(&key.key));
+                ScriptBuf::new_p2sh(&hash160(redeem_script.as_bytes()))
+            }
+            Self::Sh(multi) => ScriptBuf::new_p2sh(&hash160(multi.script().as_bytes())),
+            Self::Wsh(multi) => ScriptBuf::new_p2wsh(&sha256(multi.script().as_bytes())),
+            Self::Tr(key) => ScriptBuf::new_p2tr(&taproot_output_key(key)?),
+        })
+    }
+
+    /// The address of the output script on the given network.
+    pub fn address(&self, network: Network) -> Result<Address, BitcoinError> {
+        Address::from_script(&self.script_pubkey()?, network)
+    }
+
+    /// The redeem script pushed by the script sig of `sh()` descriptors.
+    pub fn redeem_script(&self) -> Option<ScriptBuf> {
+        match self {
+            Self::ShWpkh(key) => Some(ScriptBuf::new_p2wpkh(&hash160(&key.key))),
+            Self::Sh(multi) => Some(multi.script()),
+            _ => None,
+        }
+    }
+
+    /// The witness script revealed in the witness of `wsh()` descriptors.
+    pub fn witness_script(&self) -> Option<ScriptBuf> {
+        match self {
+            Self::Wsh(multi) => Some(multi.script()),
+            _ => None,
+        }
+    }
+
+    /// The script code signed over by ECDSA signatures, to pass to
+    /// [`BitcoinTransaction::build_for_signing_segwit`](super::BitcoinTransaction::build_for_signing_segwit)
+    /// or [`BitcoinTransaction::legacy_signature_hash`](super::BitcoinTransaction::legacy_signature_hash).
+    ///
+    /// Returns [`None`] for `tr()`, whose signatures do not commit to a script code.
+    pub fn script_code(&self) -> Option<ScriptBuf> {
+        match self {
+            Self::Pkh(key) => Some(ScriptBuf::new_p2pkh(&hash160(&key.key))),
+            Self::Wpkh(key) | Self::ShWpkh(key) => Some(ScriptBuf::new_p2pkh(&hash160(&key.key))),
+            Self::Sh(multi) | Self::Wsh(multi) => Some(multi.script()),
+            Self::Tr(_) => None,
+        }
+    }
+
+    /// The transaction type to attach the script sig or witness with.
+    ///
+    /// `sh(wpkh())` spends are reported as [`TransactionType::P2WPKH`]: the signature goes in
+    /// the witness, and the script sig only pushes the [`Self::redeem_script`].
+    pub const fn transaction_type(&self) -> TransactionType {
+        match self {
+            Self::Pkh(_) => TransactionType::P2PKH,
+            Self::Wpkh(_) | Self::ShWpkh(_) => TransactionType::P2WPKH,
+            Self::Sh(_) => TransactionType::P2SH,
+            Self::Wsh(_) => TransactionType::P2WSH,
+            Self::Tr(_) => TransactionType::P2TR,
+        }
+    }
+
+    /// Whether the spend signs with the SegWit (BIP-143 or BIP-341) signature hash.
+    pub const fn is_witness(&self) -> bool {
+        !matches!(self, Self::Pkh(_) | Self::Sh(_))
+    }
+
+    /// The public keys of the descriptor, in the order they appear.
+    pub fn public_keys(&self) -> Vec<&DescriptorPublicKey> {
+        match self {
+            Self::Pkh(key) | Self::Wpkh(key) | Self::ShWpkh(key) | Self::Tr(key) => vec![key],
+            Self::Sh(multi) | Self::Wsh(multi) => multi.keys.iter().collect(),
+        }
+    }
+
+    /// Writes the descriptor without its checksum.
+    fn fmt_without_checksum(&self) -> String {
+        let multi = |f: &str, multi: &Multi| {
+            let name = if multi.sorted { "sortedmulti" } else { "multi" };
+            let keys: Vec<String> = multi.keys.iter().map(ToString::to_string).collect();
+            format!("{f}({name}({},{}))", multi.threshold, keys.join(","))
+        };
+
+        match self {
+            Self::Pkh(key) => format!("pkh({key})"),
+            Self::Wpkh(key) => format!("wpkh({key})"),
+            Self::ShWpkh(key) => format!("sh(wpkh({key}))"),
+            Self::Sh(m) => multi("sh", m),
+            Self::Wsh(m) => multi("wsh", m),
+            Self::Tr(key) => format!("tr({key})"),
+        }
+    }
+}
+
+impl fmt::Display for Descriptor {
+    /// Writes the descriptor followed by its checksum.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let descriptor = self.fmt_without_checksum();
+        let checksum = descriptor_checksum(&descriptor).map_err(|_| fmt::Error)?;
+        write!(f, "{descriptor}#{checksum}")
+    }
+}
+
+impl FromStr for Descriptor {
+    type Err = BitcoinError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for DescriptorPublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(origin) = &self.origin {
+            write!(f, "[{}", hex::encode(origin.fingerprint))?;
+            for step in &origin.derivation_path {
+                if step & HARDENED != 0 {
+                    write!(f, "/{}h", step & !HARDENED)?;
+                } else {
+                    write!(f, "/{step}")?;
+                }
+            }
+            write!(f, "]")?;
+        }
+        write!(f, "{}", hex::encode(&self.key))
+    }
+}
+
+/// Computes the 8 character BIP-380 checksum of a descriptor.
+pub fn descriptor_checksum(descriptor: &str) -> Result<String, BitcoinError> {
+    fn poly_mod(mut c: u64, val: u64) -> u64 {
+        let c0 = c >> 35;
+        c = ((c & 0x7_ffff_ffff) << 5) ^ val;
+        for (bit, generator) in [
+            0xf5_dee5_1989,
+            0xa9_fdca_3312,
+            0x1b_ab10_e32d,
+            0x37_06b1_677a,
+            0x64_4d62_6ffd,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            if c0 & (1 << bit) != 0 {
+                c ^= generator;
+            }
+        }
+        c
+    }
+
+    let mut c = 1;
+    let mut class = 0;
+    let mut class_count = 0;
+    for ch in descriptor.chars() {
+        let pos = INPUT_CHARSET
+            .chars()
+            .position(|x| x == ch)
+            .ok_or_else(|| invalid(format!("invalid character {ch:?}")))? as u64;
+        c = poly_mod(c, pos & 31);
+        class = class * 3 + (pos >> 5);
+        class_count += 1;
+        if class_count == 3 {
+            c = poly_mod(c, class);
+            class = 0;
+            class_count = 0;
+        }
+    }
+    if class_count > 0 {
+        c = poly_mod(c, class);
+    }
+    for _ in 0..8 {
+        c = poly_mod(c, 0);
+    }
+    c ^= 1;
+
+    Ok((0..8)
+        .map(|j| bech32::CHARSET[((c >> (5 * (7 - j))) & 31) as usize] as char)
+        .collect())
+}
+
+/// The tweaked x-only output key of a key path only `tr()` descriptor.
+fn taproot_output_key(key: &DescriptorPublicKey) -> Result<[u8; 32], BitcoinError> {
+    let (output_key, _) = tweak_internal_key(&key.x_only()?, None)?;
+    Ok(output_key)
+}
+
+fn invalid(reason: impl Into<String>) -> BitcoinError {
+    BitcoinError::InvalidDescriptor(reason.into())
+}
+
+/// Splits `name(args)` into its name and arguments.
+fn split_function(s: &str) -> Result<(&str, &str), BitcoinError> {
+    let (name, rest) = s
+        .split_once('(')
+        .ok_or_else(|| invalid(format!("expected a function in {s}")))?;
+    let args = rest
+        .strip_suffix(')')
+        .ok_or_else(|| invalid(format!("missing closing parenthesis in {s}")))?;
+    Ok((name, args))
+}
+
+/// Parses `threshold,KEY,KEY,...` of a `multi()` or `sortedmulti()` expression.
+fn parse_multi(expression: &str, args: &str, max_keys: usize) -> Result<Multi, BitcoinError> {
+    let mut args = args.split(',');
+    let threshold: usize = args
+        .next()
+        .and_then(|t| t.parse().ok())
+        .ok_or_else(|| invalid(format!("invalid threshold in {expression}")))?;
+    let keys = args.map(parse_key).collect::<Result<Vec<_>, _>>()?;
+
+    if keys.is_empty() || keys.len() > max_keys {
+        return Err(invalid(format!(
+            "multisig must have between 1 and {max_keys} keys"
+        )));
+    }
+
+    Multi::new(threshold, keys, expression.starts_with("sortedmulti("))
+}
+
+fn parse_compressed_key(s: &str) -> Result<DescriptorPublicKey, BitcoinError> {
+    let key = parse_key(s)?;
+    if !key.is_compressed() {
+        return Err(invalid("SegWit v0 requires a compressed key"));
+    }
+    Ok(key)
+}
+
+/// Parses a hex public key with an optional `[fingerprint/path]` origin.
+fn parse_key(s: &str) -> Result<DescriptorPublicKey, BitcoinError> {
+    let (origin, key) = match s.strip_prefix('[') {
+        Some(rest) => {
+            let (origin, key) = rest
+                .split_once(']')
+                .ok_or_else(|| invalid(format!("unterminated key origin in {s}")))?;
+            (Some(parse_key_origin(origin)?), key)
+        }
+        None => (None, s),
+    };
+
+    let key = hex::decode(key).map_err(|_| {
+        invalid(format!(
+            "{key} is not a hex public key, extended keys are not supported"
+        ))
+    })?;
+    let valid = match key.len() {
+        32 => k256::schnorr::VerifyingKey::from_bytes(&key).is_ok(),
+        33 | 65 => k256::PublicKey::from_sec1_bytes(&key).is_ok(),
+        _ => false,
+    };
+    if !valid {
+        return Err(invalid(format!("invalid public key {}", hex::encode(&key))));
+    }
+
+    Ok(DescriptorPublicKey { origin, key })
+}
+
+fn parse_key_origin(s: &str) -> Result<KeyOrigin, BitcoinError> {
+    let mut parts = s.split('/');
+    let fingerprint = parts
+        .next()
+        .and_then(|f| hex::decode(f).ok())
+        .and_then(|f| f.try_into().ok())
+        .ok_or_else(|| invalid(format!("invalid key origin fingerprint in [{s}]")))?;
+    let derivation_path = parts
+        .map(|step| {
+            let (index, hardened) = step
+                .strip_suffix(['h', 'H', '\''])
+                .map_or((step, 0), |index| (index, HARDENED));
+            index
+                .parse::<u32>()
+                .ok()
+                .filter(|index| index & HARDENED == 0)
+                .map(|index| index | hardened)
+                .ok_or_else(|| invalid(format!("invalid derivation step {step}")))
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(KeyOrigin {
+        fingerprint,
+        derivation_path,
+    })
+}
+
+/// Pushes a number from 1 to 20, as used for multisig thresholds and key counts.
+///
+/// [`Multi::new`] keeps both in that range.
+fn push_small_int(script: &mut ScriptBuf, n: usize) {
+    match n {
+        1..=16 => script.push_opcode(OP_1 + n as u8 - 1),
+        _ => script.push_slice(&[n as u8]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY_1: &str = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+    const KEY_2: &str = "02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5";
+    const KEY_3: &str = "02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9";
+
+    fn rust_bitcoin_key(key: &str) -> bitcoin::PublicKey {
+        key.parse().unwrap()
+    }
+
+    fn rust_bitcoin_multisig(threshold: i64, keys: &[&str]) -> bitcoin::ScriptBuf {
+        let mut builder = bitcoin::script::Builder::new().push_int(threshold);
+        for key in keys {
+            builder = builder.push_key(&rust_bitcoin_key(key));
+        }
+        builder
+            .push_int(keys.len() as i64)
+            .push_opcode(bitcoin::opcodes::all::OP_CHECKMULTISIG)
+            .into_script()
+    }
+
+    #[test]
+    fn test_descriptor_checksum() {
+        // Test vectors from BIP-380
+        assert_eq!(descriptor_checksum("raw(deadbeef)").unwrap(), "89f8spxm");
+        assert_eq!(
+            descriptor_checksum("addr(mkmZxiEcEd8ZqjQWVZuC6so5dFMKEFpN2j)").unwrap(),
+            "02wpgw69"
+        );
+        assert!(descriptor_checksum("raw(deadbeef)é").is_err());
+    }
+
+    #[test]
+    fn test_script_pubkey_against_rust_bitcoin() {
+        use bitcoin::secp256k1::Secp256k1;
+
+        let key = rust_bitcoin_key(KEY_1);
+        let compressed = bitcoin::CompressedPublicKey(key.inner);
+        let multisig = rust_bitcoin_multisig(2, &[KEY_1, KEY_2, KEY_3]);
+
+        let cases = [
+            (
+                format!("pkh({KEY_1})"),
+                bitcoin::ScriptBuf::new_p2pkh(&key.pubkey_hash()),
+            ),
+            (
+                format!("wpkh({KEY_1})"),
+                bitcoin::ScriptBuf::new_p2wpkh(&compressed.wpubkey_hash()),
+            ),
+            (
+                format!("sh(wpkh({KEY_1}))"),
+                bitcoin::ScriptBuf::new_p2sh(
+                    &bitcoin::ScriptBuf::new_p2wpkh(&compressed.wpubkey_hash()).script_hash(),
+                ),
+            ),
+            (
+                format!("sh(multi(2,{KEY_1},{KEY_2},{KEY_3}))"),
+                bitcoin::ScriptBuf::new_p2sh(&multisig.script_hash()),
+            ),
+            (
+                format!("wsh(multi(2,{KEY_1},{KEY_2},{KEY_3}))"),
+                bitcoin::ScriptBuf::new_p2wsh(&multisig.wscript_hash()),
+            ),
+            (
+                // Sorted by serialized key, which is the order of `multisig`
+                format!("wsh(sortedmulti(2,{KEY_3},{KEY_1},{KEY_2}))"),
+                bitcoin::ScriptBuf::new_p2wsh(&multisig.wscript_hash()),
+            ),
+            (
+                format!("tr({})", &KEY_1[2..]),
+                bitcoin::ScriptBuf::new_p2tr(
+                    &Secp256k1::verification_only(),
+                    key.inner.x_only_public_key().0,
+                    None,
+                ),
+            ),
+        ];
+
+        for (descriptor, expected) in cases {
+            let descriptor = Descriptor::parse(&descriptor).unwrap();
+            assert_eq!(
+                descriptor.script_pubkey().unwrap().as_bytes(),
+                expected.as_bytes()
+            );
+        }
+    }
+
+    #[test]
+    fn test_spend_metadata() {
+        let wpkh = Descriptor::parse(&format!("sh(wpkh({KEY_1}))")).unwrap();
+        let key_hash = hash160(&hex::decode(KEY_1).unwrap());
+
+        assert_eq!(wpkh.redeem_script(), Some(ScriptBuf::new_p2wpkh(&key_hash)));
+        assert_eq!(wpkh.script_code(), Some(ScriptBuf::new_p2pkh(&key_hash)));
+        assert_eq!(wpkh.transaction_type(), TransactionType::P2WPKH);
+        assert!(wpkh.is_witness());
+
+        let wsh = Descriptor::parse(&format!("wsh(multi(1,{KEY_1},{KEY_2}))")).unwrap();
+        let witness_script = rust_bitcoin_multisig(1, &[KEY_1, KEY_2]);
+
+        assert_eq!(
+            wsh.witness_script().unwrap().as_bytes(),
+            witness_script.as_bytes()
+        );
+        assert_eq!(wsh.script_code(), wsh.witness_script());
+        assert_eq!(wsh.public_keys().len(), 2);
+
+        // First receiving address of the BIP-86 test vectors
+        let tr = Descriptor::parse(
+            "tr(cc8a4bc64d897bddc5fbc2f670f7a8ba0b386779106cf1223c6fc5d7cd6fc115)",
+        )
+        .unwrap();
+        assert_eq!(tr.script_code(), None);
+        assert_eq!(
+            tr.address(Network::Mainnet).unwrap().to_string(),
+            "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr"
+        );
+    }
+
+    #[test]
+    fn test_checksum_and_key_origin_roundtrip() {
+        let descriptor = format!("wpkh([d34db33f/84'/0'/0']{KEY_1})");
+        let parsed = Descriptor::parse(&descriptor).unwrap();
+        let Descriptor::Wpkh(key) = &parsed else {
+            panic!("expected wpkh()");
+        };
+
+        assert_eq!(
+            key.origin,
+            Some(KeyOrigin {
+                fingerprint: [0xd3, 0x4d, 0xb3, 0x3f],
+                derivation_path: vec![84 | HARDENED, HARDENED, HARDENED],
+            })
+        );
+
+        let with_checksum = parsed.to_string();
+        assert!(with_checksum.starts_with(&format!("wpkh([d34db33f/84h/0h/0h]{KEY_1})#")));
+        assert_eq!(Descriptor::parse(&with_checksum).unwrap(), parsed);
+
+        let tampered = with_checksum.replace("/84h/", "/85h/");
+        assert!(matches!(
+            Descriptor::parse(&tampered),
+            Err(BitcoinError::InvalidDescriptor(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_descriptors() {
+        let uncompressed = "0479be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8";
+        let xpub = "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8";
+
+        assert!(Descriptor::parse(&format!("pkh({uncompressed})")).is_ok());
+
+        for descriptor in [
+            format!("wpkh({uncompressed})"),
+            format!("wsh(multi(3,{KEY_1},{KEY_2}))"),
+            format!("wsh(multi(0,{KEY_1}))"),
+            format!("wpkh({xpub})"),
+            format!("tr({KEY_1},pk({KEY_2}))"),
+            format!("combo({KEY_1})"),
+            format!("wpkh({KEY_1}"),
+        ] {
+            assert!(Descriptor::parse(&descriptor).is_err(), "{descriptor}");
+        }
+    }
+
+    #[test]
+    fn test_hand_built_descriptors() {
+        let key = |hex_key: &str| DescriptorPublicKey {
+            origin: None,
+            key: hex::decode(hex_key).unwrap(),
+        };
+
+        // Keys that parsing would reject fail instead of panicking
+        let empty = Descriptor::Tr(key(""));
+        assert_eq!(empty.script_pubkey(), Err(BitcoinError::InvalidTaprootKey));
+        assert_eq!(
+            empty.address(Network::Mainnet),
+            Err(BitcoinError::InvalidTaprootKey)
+        );
+        let off_curve = Descriptor::Tr(key(&"00".repeat(32)));
+        assert_eq!(
+            off_curve.script_pubkey(),
+            Err(BitcoinError::InvalidTaprootKey)
+        );
+
+        // Thresholds and key counts must fit the script
+        assert!(Multi::new(0, vec![key(KEY_1)], false).is_err());
+        assert!(Multi::new(2, vec![key(KEY_1)], false).is_err());
+        assert!(Multi::new(1, vec![], false).is_err());
+        assert!(Multi::new(1, vec![key(KEY_1); 21], false).is_err());
+
+        let keys = [KEY_1; 20];
+        let multi = Multi::new(17, keys.iter().map(|k| key(k)).collect(), false).unwrap();
+        assert_eq!(
+            multi.script().as_bytes(),
+            rust_bitcoin_multisig(17, &keys).as_bytes()
+        );
+    }
+}
@@ -25,6 +25,7 @@ impl_array!(32);
 impl_int_encodable!(u8, read_u8, emit_u8);
 impl_int_encodable!(u16, read_u16, emit_u16);
 impl_int_encodable!(u32, read_u32, emit_u32);
+impl_int_encodable!(i32, read_i32, emit_i32);
 impl_int_encodable!(u64, read_u64, emit_u64);
 
 // Encodable implementation for `Vec<u8>`
@@ -8,6 +8,8 @@ pub trait WriteExt: Write {
     fn emit_u64(&mut self, v: u64) -> Result<(), std::io::Error>;
     /// Outputs a 32-bit unsigned integer.
     fn emit_u32(&mut self, v: u32) -> Result<(), std::io::Error>;
+    /// Outputs a 32-bit signed integer.
+    fn emit_i32(&mut self, v: i32) -> Result<(), std::io::Error>;
     /// Outputs a 16-bit unsigned integer.
     fn emit_u16(&mut self, v: u16) -> Result<(), std::io::Error>;
     /// Outputs an 8-bit unsigned integer.
@@ -22,6 +24,8 @@ pub trait ReadExt: Read {
     fn read_u64(&mut self) -> Result<u64, std::io::Error>;
     /// Reads a 32-bit unsigned integer.
     fn read_u32(&mut self) -> Result<u32, std::io::Error>;
+    /// Reads a 32-bit signed integer.
+    fn read_i32(&mut self) -> Result<i32, std::io::Error>;
     /// Reads a 16-bit unsigned integer.
     fn read_u16(&mut self) -> Result<u16, std::io::Error>;
     /// Reads an 8-bit unsigned integer.
@@ -33,6 +37,7 @@ pub trait ReadExt: Read {
 impl<W: Write + ?Sized> WriteExt for W {
     encoder_fn!(emit_u64, u64);
     encoder_fn!(emit_u32, u32);
+    encoder_fn!(emit_i32, i32);
     encoder_fn!(emit_u16, u16);
 
     fn emit_u8(&mut self, v: u8) -> Result<(), std::io::Error> {
@@ -47,6 +52,7 @@ impl<W: Write + ?Sized> WriteExt for W {
 impl<R: Read + ?Sized> ReadExt for R {
     decoder_fn!(read_u64, u64, 8);
     decoder_fn!(read_u32, u32, 4);
+    decoder_fn!(read_i32, i32, 4);
     decoder_fn!(read_u16, u16, 2);
 
     fn read_u8(&mut self) -> Result<u8, std::io::Error> {
@@ -0,0 +1,200 @@
+//! Error type returned by the fallible Bitcoin transaction APIs.
+use std::fmt;
+
+use super::{
+    network::Network,
+    types::{Amount, Version},
+};
+
+/// Errors that can occur while preparing or assembling a Bitcoin transaction.
+///
+/// Inside a NEAR contract a panic aborts the whole receipt, so every panicking
+/// method of [`BitcoinTransaction`](super::BitcoinTransaction) has a `try_` counterpart
+/// returning this error instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BitcoinError {
+    /// A script sig was provided for a SegWit transaction type.
+    ScriptSigForSegwit,
+    /// A witness was provided for a non-SegWit transaction type.
+    WitnessForLegacy,
+    /// The transaction version is not valid for the requested operation.
+    InvalidVersion {
+        /// The version that the operation requires.
+        expected: Version,
+        /// The version of the transaction.
+        actual: Version,
+    },
+    /// SegWit signing was requested for a version 1 transaction.
+    ///
+    /// Versions 2 and 3 are accepted, and version 0 for BIP-322 virtual transactions.
+    InvalidSegwitVersion(Version),
+    /// The input index does not point to an input of the transaction.
+    InputIndexOutOfRange {
+        /// The requested input index.
+        index: usize,
+        /// The number of inputs in the transaction.
+        len: usize,
+    },
+    /// The transaction could not be encoded.
+    Encoding(String),
+    /// The address could not be parsed or is not valid on the expected network.
+    InvalidAddress(String),
+    /// The output script does not correspond to a standard address type.
+    UnsupportedScript,
+    /// A required field was not set on the builder.
+    MissingField(&'static str),
+    /// An output carries less value than the dust threshold of the network.
+    DustOutput {
+        /// The index of the dust output.
+        index: usize,
+        /// The dust threshold for the output script.
+        threshold: Amount,
+    },
+    /// The number of spent outputs does not match the number of inputs.
+    SpentOutputsMismatch {
+        /// The number of inputs in the transaction.
+        expected: usize,
+        /// The number of spent outputs provided.
+        actual: usize,
+    },
+    /// The signature hash type is not supported.
+    UnsupportedSighashType(u32),
+    /// The fork value of a FORKID signature hash does not fit in 24 bits.
+    InvalidForkValue(u32),
+    /// The script sig or witness of an input does not satisfy the output it spends.
+    InvalidSpend {
+        /// The index of the input.
+        index: usize,
+        /// Why the spend is invalid.
+        reason: &'static str,
+    },
+    /// The signature of an input does not verify against its signature hash and public key.
+    InvalidSignature {
+        /// The index of the input.
+        index: usize,
+    },
+    /// The key is not a valid Taproot internal key, or tweaking it failed.
+    InvalidTaprootKey,
+    /// The output descriptor could not be parsed.
+    InvalidDescriptor(String),
+    /// The block header target is invalid or not met by its hash.
+    InvalidProofOfWork,
+    /// A header does not extend the chain of headers before it.
+    InvalidHeaderChain {
+        /// The height of the offending header.
+        height: u32,
+        /// Why the header does not extend the chain.
+        reason: &'static str,
+    },
+    /// The lock time is not a valid block height or timestamp.
+    InvalidLockTime(String),
+    /// The operation is not supported on the network.
+    UnsupportedNetwork(Network),
+    /// A BIP-322 message signature is malformed.
+    InvalidMessageSignature(String),
+    /// A transaction of a package breaks the TRUC (BIP-431) policy.
+    TrucViolation {
+        /// The index of the offending transaction in the package.
+        index: usize,
+        /// Which rule the transaction breaks.
+        reason: &'static str,
+    },
+    /// The leaves of a Taproot script tree do not form a valid tree.
+    InvalidTapTree(&'static str),
+    /// No UTXO was provided for the outpoint spent by an input.
+    MissingUtxo {
+        /// The index of the input.
+        index: usize,
+    },
+    /// The outputs of the transaction spend more than its inputs.
+    InsufficientFunds {
+        /// The total value of the spent outputs.
+        inputs: Amount,
+        /// The total value of the outputs.
+        outputs: Amount,
+    },
+    /// The total value of the spent outputs or of the outputs overflows.
+    AmountOverflow,
+}
+
+impl fmt::Display for BitcoinError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ScriptSigForSegwit => {
+                write!(f, "Use build_with_witness for SegWit transactions")
+            }
+            Self::WitnessForLegacy => {
+                write!(f, "Use build_with_script_sig for non-SegWit transactions")
+            }
+            Self::InvalidVersion { expected, actual } => write!(
+                f,
+                "Invalid transaction version: expected {expected}, got {actual}"
+            ),
+            Self::InvalidSegwitVersion(version) => write!(
+                f,
+                "SegWit signing requires a version 0 (BIP-322), 2 or 3 transaction, got version {version}"
+            ),
+            Self::InputIndexOutOfRange { index, len } => write!(
+                f,
+                "Input index {index} out of range for transaction with {len} inputs"
+            ),
+            Self::Encoding(e) => write!(f, "Failed to encode transaction: {e}"),
+            Self::InvalidAddress(e) => write!(f, "Invalid address: {e}"),
+            Self::UnsupportedScript => write!(f, "Unsupported output script"),
+            Self::MissingField(field) => write!(f, "Missing {field}"),
+            Self::DustOutput { index, threshold } => write!(
+                f,
+                "Output {index} is below the dust threshold of {} sat",
+                threshold.to_sat()
+            ),
+            Self::SpentOutputsMismatch { expected, actual } => write!(
+                f,
+                "Expected {expected} spent outputs, one per input, got {actual}"
+            ),
+            Self::UnsupportedSighashType(sighash_type) => {
+                write!(f, "Unsupported sighash type: {sighash_type:#04x}")
+            }
+            Self::InvalidForkValue(fork_value) => {
+                write!(f, "Fork value {fork_value:#x} does not fit in 24 bits")
+            }
+            Self::InvalidSpend { index, reason } => {
+                write!(f, "Invalid spend for input {index}: {reason}")
+            }
+            Self::InvalidSignature { index } => write!(f, "Invalid signature for input {index}"),
+            Self::InvalidTaprootKey => write!(f, "Invalid Taproot key"),
+            Self::InvalidDescriptor(e) => write!(f, "Invalid descriptor: {e}"),
+            Self::InvalidProofOfWork => write!(f, "Invalid proof of work"),
+            Self::InvalidHeaderChain { height, reason } => {
+                write!(f, "Invalid header at height {height}: {reason}")
+            }
+            Self::InvalidLockTime(e) => write!(f, "Invalid lock time: {e}"),
+            Self::UnsupportedNetwork(network) => write!(f, "Unsupported network: {network}"),
+            Self::InvalidMessageSignature(e) => write!(f, "Invalid message signature: {e}"),
+            Self::TrucViolation { index, reason } => {
+                write!(f, "TRUC policy violation by transaction {index}: {reason}")
+            }
+            Self::InvalidTapTree(reason) => write!(f, "Invalid Taproot script tree: {reason}"),
+            Self::MissingUtxo { index } => {
+                write!(
+                    f,
+                    "No UTXO provided for the outpoint spent by input {index}"
+                )
+            }
+            Self::InsufficientFunds { inputs, outputs } => write!(
+                f,
+                "Outputs spend {} sat but inputs only provide {} sat",
+                outputs.to_sat(),
+                inputs.to_sat()
+            ),
+            Self::AmountOverflow => write!(f, "Total amount overflows"),
+        }
+    }
+}
+
+impl std::error::Error for BitcoinError {}
+
+impl From<std::io::Error> for BitcoinError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Encoding(e.to_string())
+    }
+}
@@ -0,0 +1,55 @@
+//! Hash functions used across Bitcoin scripts, addresses and signature hashes.
+use ripemd::Ripemd160;
+use sha2::{Digest, Sha256};
+
+/// Computes SHA-256 of the given data.
+pub fn sha256(data: &[u8]) -> [u8; 32] {
+    Sha256::digest(data).into()
+}
+
+/// Computes sha256d (double SHA-256) of the given data.
+pub fn sha256d(data: &[u8]) -> [u8; 32] {
+    sha256(&sha256(data))
+}
+
+/// Computes HASH160 (RIPEMD-160 of SHA-256) of the given data.
+///
+/// This is the hash committed to by P2PKH, P2SH and P2WPKH outputs.
+pub fn hash160(data: &[u8]) -> [u8; 20] {
+    Ripemd160::digest(sha256(data)).into()
+}
+
+/// Computes the BIP-340 tagged hash `SHA256(SHA256(tag) || SHA256(tag) || data)`.
+pub fn tagged_hash(tag: &str, data: &[u8]) -> [u8; 32] {
+    let tag_hash = sha256(tag.as_bytes());
+
+    Sha256::new()
+        .chain_update(tag_hash)
+        .chain_update(tag_hash)
+        .chain_update(data)
+        .finalize()
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash160_against_rust_bitcoin() {
+        use bitcoin::hashes::{hash160, Hash};
+
+        let data = b"omni-transaction";
+
+        assert_eq!(hash160(data), hash160::Hash::hash(data).to_byte_array());
+    }
+
+    #[test]
+    fn test_sha256d_against_rust_bitcoin() {
+        use bitcoin::hashes::{sha256d, Hash};
+
+        let data = b"omni-transaction";
+
+        assert_eq!(sha256d(data), sha256d::Hash::hash(data).to_byte_array());
+    }
+}
@@ -0,0 +1,310 @@
+//! Message signing: the generic [BIP-322] format and the legacy "Bitcoin Signed Message" one.
+//!
+//! A BIP-322 signature proves control of an output script by spending a virtual `to_spend`
+//! transaction committing to the message. Sign input 0 of [`to_sign`] with the usual sighash
+//! methods, spending the single output of [`to_spend`], then attach the witness and encode it
+//! with [`encode_simple`], or the script sig and witness and encode it with [`encode_full`].
+//!
+//! [BIP-322]: https://github.com/bitcoin/bips/blob/master/bip-0322.mediawiki
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+use super::{
+    bitcoin_transaction::BitcoinTransaction,
+    encoding::{utils::VarInt, Decodable, Encodable},
+    error::BitcoinError,
+    hashes::{sha256d, tagged_hash},
+    types::{
+        opcodes::{OP_0, OP_RETURN},
+        Amount, LockTime, OutPoint, ScriptBuf, Sequence, TxIn, TxOut, Version, Witness,
+    },
+};
+
+/// The prefix of the messages hashed by [`signed_message_hash`].
+pub const SIGNED_MESSAGE_PREFIX: &str = "Bitcoin Signed Message:\n";
+
+/// The hash signed by the legacy `signmessage` RPC of Bitcoin Core, used with P2PKH addresses.
+pub fn signed_message_hash(message: &[u8]) -> [u8; 32] {
+    let mut data = Vec::new();
+    let _ = VarInt(SIGNED_MESSAGE_PREFIX.len() as u64).encode(&mut data);
+    data.extend_from_slice(SIGNED_MESSAGE_PREFIX.as_bytes());
+    let _ = VarInt(message.len() as u64).encode(&mut data);
+    data.extend_from_slice(message);
+    sha256d(&data)
+}
+
+/// The BIP-322 message hash, committed to by the script sig of [`to_spend`].
+pub fn message_hash(message: &[u8]) -> [u8; 32] {
+    tagged_hash("BIP0322-signed-message", message)
+}
+
+/// Builds the virtual `to_spend` transaction, whose single output is locked by `script_pubkey`.
+pub fn to_spend(script_pubkey: &ScriptBuf, message: &[u8]) -> BitcoinTransaction {
+    let mut script_sig = ScriptBuf::default();
+    script_sig.push_opcode(OP_0);
+    script_sig.push_slice(&message_hash(message));
+
+    BitcoinTransaction {
+        version: Version::Zero,
+        lock_time: LockTime::from_consensus(0),
+        input: vec![TxIn {
+            previous_output: OutPoint::null(),
+            script_sig,
+            sequence: Sequence::ZERO,
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut {
+            value: Amount::ZERO,
+            script_pubkey: script_pubkey.clone(),
+        }],
+    }
+}
+
+/// Builds the unsigned virtual `to_sign` transaction, spending the output of `to_spend`.
+pub fn to_sign(to_spend: &BitcoinTransaction) -> BitcoinTransaction {
+    BitcoinTransaction {
+        version: Version::Zero,
+        lock_time: LockTime::from_consensus(0),
+        input: vec![TxIn {
+            previous_output: OutPoint::new(to_spend.compute_txid(), 0),
+            script_sig: ScriptBuf::default(),
+            sequence: Sequence::ZERO,
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut {
+            value: Amount::ZERO,
+            script_pubkey: ScriptBuf::from_bytes(vec![OP_RETURN]),
+        }],
+    }
+}
+
+/// Encodes a "simple" signature: the base64 of the consensus encoded witness of `to_sign`.
+pub fn encode_simple(witness: &Witness) -> String {
+    let mut data = Vec::new();
+    let _ = witness.encode(&mut data);
+    STANDARD.encode(data)
+}
+
+/// Encodes a "full" signature: the base64 of the consensus encoded signed `to_sign`.
+pub fn encode_full(to_sign: &BitcoinTransaction) -> String {
+    STANDARD.encode(to_sign.serialize())
+}
+
+/// Verifies a "simple" signature of `message` by the owner of `script_pubkey`.
+///
+/// Only the SegWit and Taproot key path spends supported by [`BitcoinTransaction::verify`]
+/// can be verified.
+pub fn verify_simple(
+    script_pubkey: &ScriptBuf,
+    message: &[u8],
+    signature: &str,
+) -> Result<(), BitcoinError> {
+    let witness: Witness = decode_base64(signature)?;
+    let to_spend = to_spend(script_pubkey, message);
+    let mut to_sign = to_sign(&to_spend);
+    to_sign.input[0].witness = witness;
+
+    to_sign.verify(&to_spend.output)
+}
+
+/// Verifies a "full" signature of `message` by the owner of `script_pubkey`.
+///
+/// Inputs after the first one prove control of further funds; the outputs they spend are
+/// passed in `additional_spent_outputs`, in the same order.
+pub fn verify_full(
+    script_pubkey: &ScriptBuf,
+    message: &[u8],
+    signature: &str,
+    additional_spent_outputs: &[TxOut],
+) -> Result<(), BitcoinError> {
+    let signed: BitcoinTransaction = decode_base64(signature)?;
+    let to_spend = to_spend(script_pubkey, message);
+    let expected = to_sign(&to_spend);
+
+    if signed.input.first().map(|input| input.previous_output)
+        != Some(expected.input[0].previous_output)
+    {
+        return Err(BitcoinError::InvalidMessageSignature(
+            "first input does not spend to_spend".to_string(),
+        ));
+    }
+    if signed.output != expected.output {
+        return Err(BitcoinError::InvalidMessageSignature(
+            "to_sign must have a single empty OP_RETURN output".to_string(),
+        ));
+    }
+
+    let spent_outputs = [&to_spend.output[..], additional_spent_outputs].concat();
+    signed.verify(&spent_outputs)
+}
+
+fn decode_base64<T: Decodable>(signature: &str) -> Result<T, BitcoinError> {
+    let invalid = |e: String| BitcoinError::InvalidMessageSignature(e);
+    let data = STANDARD
+        .decode(signature)
+        .map_err(|e| invalid(e.to_string()))?;
+
+    let mut reader = &data[..];
+    let decoded = T::decode(&mut reader).map_err(|e| invalid(e.to_string()))?;
+    if !reader.is_empty() {
+        return Err(invalid("trailing data".to_string()));
+    }
+    Ok(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitcoin::{address::Address, network::Network, types::EcdsaSighashType};
+    use k256::ecdsa::{signature::hazmat::PrehashSigner, Signature, SigningKey};
+
+    // The test vectors of BIP-322.
+    const P2WPKH_ADDRESS: &str = "bc1q9vza2e8x573nczrlzms0wvx3gsqjx7vavgkx0l";
+    const P2TR_ADDRESS: &str = "bc1ppv609nr0vr25u07u95waq5lucwfm6tde4nydujnu8npg4q75mr5sxq8lt3";
+
+    fn script_pubkey(address: &str) -> ScriptBuf {
+        Address::parse(address, Network::Mainnet)
+            .unwrap()
+            .script_pubkey()
+    }
+
+    #[test]
+    fn test_message_hash() {
+        assert_eq!(
+            hex::encode(message_hash(b"")),
+            "c90c269c4f8fcbe6880f72a721ddfbf1914268a794cbb21cfafee13770ae19f1"
+        );
+        assert_eq!(
+            hex::encode(message_hash(b"Hello World")),
+            "f0eb03b1a75ac6d9847f55c624a99169b5dccba2a31f5b23bea77ba270de0a7a"
+        );
+    }
+
+    #[test]
+    fn test_virtual_transactions() {
+        let script_pubkey = script_pubkey(P2WPKH_ADDRESS);
+
+        for (message, to_spend_txid, to_sign_txid) in [
+            (
+                &b""[..],
+                "c5680aa69bb8d860bf82d4e9cd3504b55dde018de765a91bb566283c545a99a7",
+                "1e9654e951a5ba44c8604c4de6c67fd78a27e81dcadcfe1edf638ba3aaebaed6",
+            ),
+            (
+                &b"Hello World"[..],
+                "b79d196740ad5217771c1098fc4a4b51e0535c32236c71f1ea4d61a2d603352b",
+                "88737ae86f2077145f93cc4b153ae9a1cb8d56afa511988c149c5c8c9d93bddf",
+            ),
+        ] {
+            let to_spend = to_spend(&script_pubkey, message);
+            let to_sign = to_sign(&to_spend);
+
+            assert_eq!(to_spend.compute_txid().to_string(), to_spend_txid);
+            assert_eq!(to_sign.compute_txid().to_string(), to_sign_txid);
+        }
+    }
+
+    #[test]
+    fn test_verify_simple_vectors() {
+        let p2wpkh = script_pubkey(P2WPKH_ADDRESS);
+        assert_eq!(
+            verify_simple(
+                &p2wpkh,
+                b"",
+                "AkcwRAIgM2gBAQqvZX15ZiysmKmQpDrG83avLIT492QBzLnQIxYCIBaTpOaD20qRlEylyxFSeEA2ba9YOixpX8z46TSDtS40ASECx/EgAxlkQpQ9hYjgGu6EBCPMVPwVIVJqO4XCsMvViHI="
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            verify_simple(
+                &p2wpkh,
+                b"Hello World",
+                "AkcwRAIgZRfIY3p7/DoVTty6YZbWS71bc5Vct9p9Fia83eRmw2QCICK/ENGfwLtptFluMGs2KsqoNSk89pO7F29zJLUx9a/sASECx/EgAxlkQpQ9hYjgGu6EBCPMVPwVIVJqO4XCsMvViHI="
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            verify_simple(
+                &p2wpkh,
+                b"Hello World!",
+                "AkcwRAIgZRfIY3p7/DoVTty6YZbWS71bc5Vct9p9Fia83eRmw2QCICK/ENGfwLtptFluMGs2KsqoNSk89pO7F29zJLUx9a/sASECx/EgAxlkQpQ9hYjgGu6EBCPMVPwVIVJqO4XCsMvViHI="
+            ),
+            Err(BitcoinError::InvalidSignature { index: 0 })
+        );
+
+        assert_eq!(
+            verify_simple(
+                &script_pubkey(P2TR_ADDRESS),
+                b"Hello World",
+                "AUHd69PrJQEv+oKTfZ8l+WROBHuy9HKrbFCJu7U1iK2iiEy1vMU5EfMtjc+VSHM7aU0SDbak5IUZRVno2P5mjSafAQ=="
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_sign_and_verify_full_p2pkh() {
+        let signing_key = SigningKey::from_slice(&[0x42; 32]).unwrap();
+        let public_key = signing_key.verifying_key().to_sec1_bytes();
+        let address = Address::p2pkh(&public_key, Network::Mainnet);
+        let message = b"proof of reserves";
+
+        let to_spend = to_spend(&address.script_pubkey(), message);
+        let mut to_sign = to_sign(&to_spend);
+        let sighash = to_sign
+            .legacy_signature_hash(0, &address.script_pubkey(), EcdsaSighashType::All)
+            .unwrap();
+        let signature: Signature = signing_key.sign_prehash(&sighash).unwrap();
+
+        let mut script_sig = ScriptBuf::default();
+        script_sig.push_slice(&[signature.to_der().as_bytes(), &[0x01]].concat());
+        script_sig.push_slice(&public_key);
+        to_sign.input[0].script_sig = script_sig;
+        let signature = encode_full(&to_sign);
+
+        assert_eq!(
+            verify_full(&address.script_pubkey(), message, &signature, &[]),
+            Ok(())
+        );
+        assert_eq!(
+            verify_full(&address.script_pubkey(), b"other", &signature, &[]),
+            Err(BitcoinError::InvalidMessageSignature(
+                "first input does not spend to_spend".to_string()
+            ))
+        );
+        // A P2PKH spend has no witness, so it can't be encoded in the simple format.
+        assert!(verify_simple(
+            &address.script_pubkey(),
+            message,
+            &encode_simple(&to_sign.input[0].witness)
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_signed_message_hash_against_rust_bitcoin() {
+        for message in ["", "Hello World", &"a".repeat(300)] {
+            assert_eq!(
+                signed_message_hash(message.as_bytes()),
+                bitcoin::hashes::Hash::to_byte_array(bitcoin::sign_message::signed_msg_hash(
+                    message
+                ))
+            );
+        }
+    }
+
+    #[test]
+    fn test_decode_rejects_malformed_signatures() {
+        let p2wpkh = script_pubkey(P2WPKH_ADDRESS);
+
+        assert!(matches!(
+            verify_simple(&p2wpkh, b"", "not base64!"),
+            Err(BitcoinError::InvalidMessageSignature(_))
+        ));
+        assert_eq!(
+            verify_simple(&p2wpkh, b"", &STANDARD.encode([0x00, 0x00])),
+            Err(BitcoinError::InvalidMessageSignature(
+                "trailing data".to_string()
+            ))
+        );
+    }
+}
@@ -1,12 +1,29 @@
 //! Transaction builder, encoders, types and utilities for Bitcoin.
+pub mod address;
 mod bitcoin_transaction;
 mod bitcoin_transaction_builder;
+pub mod bitcoind;
 mod constants;
-mod encoding;
+pub mod descriptor;
+pub(crate) mod encoding;
+mod error;
+pub mod hashes;
+pub mod message;
+mod network;
+pub mod spv;
+pub mod taproot;
+pub mod truc;
 pub mod types;
 pub mod utils;
+mod verify;
 
 /// Bitcoin transaction
 pub use bitcoin_transaction::BitcoinTransaction;
 /// Bitcoin transaction builder
 pub use bitcoin_transaction_builder::BitcoinTransactionBuilder;
+/// Bitcoin error
+pub use error::BitcoinError;
+/// Bitcoin network
+pub use network::Network;
+/// Builder for TRUC transactions spending a pay-to-anchor output
+pub use truc::AnchorChildBuilder;
@@ -0,0 +1,501 @@
+//! Network specific parameters for Bitcoin and the chains sharing its transaction format.
+use std::{fmt, str::FromStr};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+
+use super::types::{Amount, Hash, ScriptBuf, Target, TxOut};
+
+/// The default dust relay fee of Bitcoin Core, in satoshis per 1000 virtual bytes.
+pub const DUST_RELAY_TX_FEE: u64 = 3_000;
+
+/// The default dust relay fee of Litecoin Core, in litoshis per 1000 virtual bytes.
+pub const LITECOIN_DUST_RELAY_TX_FEE: u64 = 30_000;
+
+/// The default dust limit of Dogecoin Core, 0.01 DOGE, which applies to every output script.
+pub const DOGECOIN_DUST_LIMIT: u64 = 1_000_000;
+
+/// The default minimum relay fee of Bitcoin Core since 29.1, in satoshis per 1000 virtual bytes.
+pub const MIN_RELAY_TX_FEE: u64 = 100;
+
+/// The default minimum relay fee of Litecoin Core, in litoshis per 1000 virtual bytes.
+pub const LITECOIN_MIN_RELAY_TX_FEE: u64 = 10_000;
+
+/// The default minimum relay fee of Dogecoin Core, 0.001 DOGE, in koinus per 1000 bytes.
+pub const DOGECOIN_MIN_RELAY_TX_FEE: u64 = 100_000;
+
+/// The network a transaction or an address belongs to.
+///
+/// Each network carries its own address prefixes, bech32 human readable part,
+/// relay fees and genesis block, so a single contract can serve several deployments.
+/// Litecoin and Dogecoin share the transaction format of Bitcoin, so the same transactions can
+/// be built and signed for them, with their own addresses, dust limits and minimum fees.
+#[derive(
+    Debug,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
+    JsonSchema,
+)]
+#[serde(crate = "near_sdk::serde", rename_all = "lowercase")]
+pub enum Network {
+    /// Bitcoin mainnet.
+    Mainnet,
+    /// Bitcoin testnet3.
+    Testnet,
+    /// Bitcoin testnet4 (BIP-94).
+    Testnet4,
+    /// Bitcoin signet (BIP-325) default network.
+    Signet,
+    /// Bitcoin regression test network.
+    Regtest,
+    /// Litecoin mainnet.
+    Litecoin,
+    /// Litecoin testnet4.
+    #[serde(rename = "litecoin-testnet")]
+    LitecoinTestnet,
+    /// Dogecoin mainnet, which has no SegWit.
+    Dogecoin,
+    /// Dogecoin testnet, which has no SegWit.
+    #[serde(rename = "dogecoin-testnet")]
+    DogecoinTestnet,
+}
+
+impl Network {
+    /// Version byte of base58 encoded P2PKH addresses.
+    pub const fn p2pkh_prefix(&self) -> u8 {
+        match self {
+            Self::Mainnet => 0x00,
+            Self::Testnet | Self::Testnet4 | Self::Signet | Self::Regtest => 0x6f,
+            Self::Litecoin => 0x30,
+            Self::LitecoinTestnet => 0x6f,
+            Self::Dogecoin => 0x1e,
+            Self::DogecoinTestnet => 0x71,
+        }
+    }
+
+    /// Version byte of base58 encoded P2SH addresses.
+    pub const fn p2sh_prefix(&self) -> u8 {
+        match self {
+            Self::Mainnet => 0x05,
+            Self::Testnet | Self::Testnet4 | Self::Signet | Self::Regtest => 0xc4,
+            Self::Litecoin => 0x32,
+            Self::LitecoinTestnet => 0x3a,
+            Self::Dogecoin => 0x16,
+            Self::DogecoinTestnet => 0xc4,
+        }
+    }
+
+    /// Human readable part of bech32 encoded SegWit addresses (BIP-173).
+    ///
+    /// Empty on networks without SegWit, see [`Self::supports_segwit`].
+    pub const fn bech32_hrp(&self) -> &'static str {
+        match self {
+            Self::Mainnet => "bc",
+            Self::Testnet | Self::Testnet4 | Self::Signet => "tb",
+            Self::Regtest => "bcrt",
+            Self::Litecoin => "ltc",
+            Self::LitecoinTestnet => "tltc",
+            Self::Dogecoin | Self::DogecoinTestnet => "",
+        }
+    }
+
+    /// Whether the network has activated SegWit, and so accepts witness outputs and inputs.
+    pub const fn supports_segwit(&self) -> bool {
+        !matches!(self, Self::Dogecoin | Self::DogecoinTestnet)
+    }
+
+    /// Whether the network is one of the Bitcoin networks, whose proof of work is the sha256d
+    /// block hash. Litecoin and Dogecoin use scrypt instead.
+    pub const fn is_bitcoin(&self) -> bool {
+        matches!(
+            self,
+            Self::Mainnet | Self::Testnet | Self::Testnet4 | Self::Signet | Self::Regtest
+        )
+    }
+
+    /// Default dust relay fee, in satoshis per 1000 virtual bytes.
+    ///
+    /// Zero on Dogecoin, whose dust limit does not depend on a fee rate.
+    pub const fn dust_relay_fee(&self) -> u64 {
+        match self {
+            Self::Litecoin | Self::LitecoinTestnet => LITECOIN_DUST_RELAY_TX_FEE,
+            Self::Dogecoin | Self::DogecoinTestnet => 0,
+            _ => DUST_RELAY_TX_FEE,
+        }
+    }
+
+    /// Default minimum relay fee, in satoshis per 1000 virtual bytes.
+    ///
+    /// Transactions paying a lower fee rate are not relayed by default nodes of the network.
+    pub const fn min_relay_fee(&self) -> u64 {
+        match self {
+            Self::Litecoin | Self::LitecoinTestnet => LITECOIN_MIN_RELAY_TX_FEE,
+            Self::Dogecoin | Self::DogecoinTestnet => DOGECOIN_MIN_RELAY_TX_FEE,
+            _ => MIN_RELAY_TX_FEE,
+        }
+    }
+
+    /// Hash of the genesis block, in the usual (reversed) hex display order.
+    pub fn genesis_hash(&self) -> Hash {
+        let genesis_hash = match self {
+            Self::Mainnet => "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f",
+            Self::Testnet => "000000000933ea01ad0ee984209779baaec3ced90fa3f408719526f8d77f4943",
+            Self::Testnet4 => "00000000da84f2bafbbc53dee25a72ae507ff4914b867c565be350b0da8bf043",
+            Self::Signet => "00000008819873e925422c1ff0f99f7cc9bbb232af63a077a480a3633bee1ef6",
+            Self::Regtest => "0f9188f13cb7b2c71f2a335e3a4fc328bf5beb436012afca590b1a11466e2206",
+            Self::Litecoin => "12a765e31ffd4059bada1e25190f6e98c99d9714d334efa41a195a7e7e04bfe2",
+            Self::LitecoinTestnet => {
+                "4966625a4b2851d9fdee139e56211a0d88575f59ed816ff5e6a63deb4e3e29a0"
+            }
+            Self::Dogecoin => "1a91e3dace36e2be3bf030a65679fe821aa1d6ef92e7c9902eb318182c355691",
+            Self::DogecoinTestnet => {
+                "bb0a78264637406b6360aad926284d544d7049f45189db5664f3c4d07350559e"
+            }
+        };
+        Hash::from_hex(genesis_hash).expect("genesis hash is valid hex")
+    }
+
+    /// The easiest proof of work target allowed on the network.
+    ///
+    /// The targets of Litecoin and Dogecoin apply to the scrypt hash of their headers.
+    pub fn pow_limit(&self) -> Target {
+        let bits = match self {
+            Self::Mainnet | Self::Testnet | Self::Testnet4 => 0x1d00ffff,
+            Self::Signet => 0x1e0377ae,
+            Self::Regtest => 0x207fffff,
+            Self::Litecoin | Self::LitecoinTestnet | Self::Dogecoin | Self::DogecoinTestnet => {
+                0x1e0fffff
+            }
+        };
+        Target::from_compact(bits).expect("valid compact target")
+    }
+
+    /// Whether a block may use the [`Self::pow_limit`] target when it is more than twice the
+    /// target spacing after the previous block.
+    pub const fn allows_min_difficulty_blocks(&self) -> bool {
+        matches!(self, Self::Testnet | Self::Testnet4 | Self::Regtest)
+    }
+
+    /// Whether the target stays the same at every difficulty adjustment.
+    pub const fn no_pow_retargeting(&self) -> bool {
+        matches!(self, Self::Regtest)
+    }
+
+    /// Whether difficulty adjustments start from the first block of the period (BIP-94), so
+    /// that minimum difficulty blocks cannot lower the difficulty of the next one.
+    pub const fn enforces_bip94(&self) -> bool {
+        matches!(self, Self::Testnet4)
+    }
+
+    /// Minimum value an output locked by `script_pubkey` must carry to not be considered dust.
+    ///
+    /// Mirrors `GetDustThreshold` of Bitcoin Core: the output is dust if spending it would cost
+    /// more than its value at the dust relay fee. Unspendable `OP_RETURN` outputs are never dust.
+    /// Dogecoin instead applies the fixed [`DOGECOIN_DUST_LIMIT`] to every other output.
+    pub fn dust_threshold(&self, script_pubkey: &ScriptBuf) -> Amount {
+        if script_pubkey.is_op_return() {
+            return Amount::ZERO;
+        }
+        if matches!(self, Self::Dogecoin | Self::DogecoinTestnet) {
+            return Amount::from_sat(DOGECOIN_DUST_LIMIT);
+        }
+
+        // Serialized size of the output itself: value, script length and script.
+        let output_size = (Amount::SIZE + 1 + script_pubkey.len()) as u64;
+
+        // Size of the input spending it: outpoint, script sig length, sequence and a
+        // typical 107 byte script sig, with the witness part discounted for SegWit.
+        let input_size = if script_pubkey.is_witness_program() {
+            32 + 4 + 1 + (107 / 4) + 4
+        } else {
+            32 + 4 + 1 + 107 + 4
+        };
+
+        Amount::from_sat((output_size + input_size) * self.dust_relay_fee() / 1000)
+    }
+
+    /// Checks whether the output is below the dust threshold of the network.
+    pub fn is_dust(&self, output: &TxOut) -> bool {
+        output.value.to_sat() < self.dust_threshold(&output.script_pubkey).to_sat()
+    }
+}
+
+impl fmt::Display for Network {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Mainnet => "mainnet",
+            Self::Testnet => "testnet",
+            Self::Testnet4 => "testnet4",
+            Self::Signet => "signet",
+            Self::Regtest => "regtest",
+            Self::Litecoin => "litecoin",
+            Self::LitecoinTestnet => "litecoin-testnet",
+            Self::Dogecoin => "dogecoin",
+            Self::DogecoinTestnet => "dogecoin-testnet",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl FromStr for Network {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "mainnet" | "bitcoin" => Ok(Self::Mainnet),
+            "testnet" | "testnet3" => Ok(Self::Testnet),
+            "testnet4" => Ok(Self::Testnet4),
+            "signet" => Ok(Self::Signet),
+            "regtest" => Ok(Self::Regtest),
+            "litecoin" => Ok(Self::Litecoin),
+            "litecoin-testnet" => Ok(Self::LitecoinTestnet),
+            "dogecoin" => Ok(Self::Dogecoin),
+            "dogecoin-testnet" => Ok(Self::DogecoinTestnet),
+            _ => Err(format!("Unknown network: {s}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitcoin::{
+        address::Address,
+        error::BitcoinError,
+        types::{
+            opcodes::{OP_CHECKSIG, OP_RETURN},
+            BlockHeader, LockTime, OutPoint, Sequence, TxIn, Version, Witness,
+        },
+        BitcoinTransaction,
+    };
+
+    #[test]
+    fn test_genesis_hash_against_rust_bitcoin() {
+        use bitcoin::blockdata::constants::genesis_block;
+
+        for (network, rust_bitcoin_network) in [
+            (Network::Mainnet, bitcoin::Network::Bitcoin),
+            (Network::Testnet, bitcoin::Network::Testnet),
+            (Network::Testnet4, bitcoin::Network::Testnet4),
+            (Network::Signet, bitcoin::Network::Signet),
+            (Network::Regtest, bitcoin::Network::Regtest),
+        ] {
+            assert_eq!(
+                network.genesis_hash().to_string(),
+                genesis_block(rust_bitcoin_network).block_hash().to_string()
+            );
+        }
+    }
+
+    #[test]
+    fn test_dust_threshold_against_rust_bitcoin() {
+        let scripts = [
+            "76a914cb8a3018cf279311b148cb8d13728bd8cbe95bda88ac",
+            "a914748284390f9e263a4b766a75d0633c50426eb87587",
+            "0014751e76e8199196d454941c45d1b3a323f1433bd6",
+            "00201863143c14c5166804bd19203356da136c985678cd4d27a1b8c6329604903262",
+            "512079be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+        ];
+
+        for script in scripts {
+            let omni_script = ScriptBuf::from_hex(script).unwrap();
+            let rust_bitcoin_script = bitcoin::ScriptBuf::from_hex(script).unwrap();
+
+            assert_eq!(
+                Network::Mainnet.dust_threshold(&omni_script).to_sat(),
+                rust_bitcoin_script.minimal_non_dust().to_sat()
+            );
+        }
+    }
+
+    #[test]
+    fn test_dust_threshold_for_op_return_is_zero() {
+        let script = ScriptBuf::from_hex("6a0b68656c6c6f20776f726c64").unwrap();
+
+        assert_eq!(Network::Regtest.dust_threshold(&script), Amount::ZERO);
+    }
+
+    /// Builds the genesis block of a chain paying the coinbase to the genesis public key of
+    /// Bitcoin, which Litecoin and Dogecoin reuse.
+    fn genesis_block(
+        coinbase_script_sig: &str,
+        value: Amount,
+        time: u32,
+        nonce: u32,
+    ) -> (BitcoinTransaction, BlockHeader) {
+        let mut script_pubkey = ScriptBuf::default();
+        script_pubkey.push_slice(&hex::decode("040184710fa689ad5023690c80f3a49c8f13f8d45b8c857fbcbc8bc4a8e4d3eb4b10f4d4604fa08dce601aaf0f470216fe1b51850b4acf21b179c45070ac7b03a9").unwrap());
+        script_pubkey.push_opcode(OP_CHECKSIG);
+
+        let coinbase = BitcoinTransaction {
+            version: Version::One,
+            lock_time: LockTime::from_height(0).unwrap(),
+            input: vec![TxIn {
+                previous_output: OutPoint::null(),
+                script_sig: ScriptBuf::from_hex(coinbase_script_sig).unwrap(),
+                sequence: Sequence::MAX,
+                witness: Witness::new(),
+            }],
+            output: vec![TxOut {
+                value,
+                script_pubkey,
+            }],
+        };
+        let header = BlockHeader {
+            version: 1,
+            prev_blockhash: Hash::all_zeros(),
+            merkle_root: coinbase.compute_txid().0,
+            time,
+            bits: 0x1e0ffff0,
+            nonce,
+        };
+        (coinbase, header)
+    }
+
+    #[test]
+    fn test_litecoin_mainnet_genesis_block() {
+        let (coinbase, header) = genesis_block(
+            "04ffff001d0104404e592054696d65732030352f4f63742f32303131205374657665204a6f62732c204170706c65e280997320566973696f6e6172792c2044696573206174203536",
+            Amount::from_int_btc(50),
+            1317972665,
+            2084524493,
+        );
+
+        assert_eq!(
+            coinbase.compute_txid().to_string(),
+            "97ddfbbae6be97fd6cdf3e7ca13232a3afff2353e29badfab7f73011edd4ced9"
+        );
+        assert_eq!(header.block_hash(), Network::Litecoin.genesis_hash());
+        assert_eq!(
+            header.validate_pow(Network::Litecoin),
+            Err(BitcoinError::UnsupportedNetwork(Network::Litecoin))
+        );
+    }
+
+    #[test]
+    fn test_dogecoin_mainnet_genesis_block() {
+        let (coinbase, header) = genesis_block(
+            "04ffff001d0104084e696e746f6e646f",
+            Amount::from_int_btc(88),
+            1386325540,
+            99943,
+        );
+
+        assert_eq!(
+            coinbase.compute_txid().to_string(),
+            "5b2a3f53f605d62c53e62932dac6925e3d74afa5a4b459745c36d42d0ed26a69"
+        );
+        assert_eq!(header.block_hash(), Network::Dogecoin.genesis_hash());
+    }
+
+    #[test]
+    fn test_litecoin_and_dogecoin_addresses() {
+        let public_key =
+            hex::decode("0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798")
+                .unwrap();
+
+        // The key hashes to 751e76e8...; on Bitcoin its P2PKH address is
+        // 1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH.
+        for (network, expected_p2pkh, expected_p2sh) in [
+            (
+                Network::Litecoin,
+                "LVuDpNCSSj6pQ7t9Pv6d6sUkLKoqDEVUnJ",
+                "MSdeRd4AsX3rRtX2Xvxp2JfaGmFHUYVk9A",
+            ),
+            (
+                Network::Dogecoin,
+                "DFpN6QqFfUm3gKNaxN6tNcab1FArL9cZLE",
+                "ABAkrai6zU5KXkcbrBdtSo3Yee2saibFuM",
+            ),
+            (
+                Network::DogecoinTestnet,
+                "nesRpRaAbTDmZHwmzBkLd2AtF7Z9L9z5S2",
+                "2NByiBUaEXrhmqAsg7BbLpcQSAQs1EDwt5w",
+            ),
+        ] {
+            let p2pkh = Address::p2pkh(&public_key, network);
+            let p2sh = Address::p2sh(&p2pkh.script_pubkey(), network);
+
+            assert_eq!(p2pkh.to_string(), expected_p2pkh);
+            assert_eq!(p2sh.to_string(), expected_p2sh);
+            assert_eq!(Address::parse(&p2pkh.to_string(), network).unwrap(), p2pkh);
+            // Legacy addresses of other chains are rejected.
+            assert!(Address::parse(&p2pkh.to_string(), Network::Mainnet).is_err());
+        }
+
+        let p2wpkh = Address::p2wpkh(&public_key, Network::Litecoin).unwrap();
+        assert_eq!(
+            p2wpkh.to_string(),
+            "ltc1qw508d6qejxtdg4y5r3zarvary0c5xw7kgmn4n9"
+        );
+        assert_eq!(
+            Address::parse(&p2wpkh.to_string(), Network::Litecoin).unwrap(),
+            p2wpkh
+        );
+
+        assert_eq!(
+            Address::p2wpkh(&public_key, Network::Dogecoin),
+            Err(BitcoinError::UnsupportedNetwork(Network::Dogecoin))
+        );
+        assert_eq!(
+            Address::from_script(&p2wpkh.script_pubkey(), Network::Dogecoin),
+            Err(BitcoinError::UnsupportedScript)
+        );
+    }
+
+    #[test]
+    fn test_litecoin_and_dogecoin_dust_thresholds() {
+        let p2pkh = ScriptBuf::new_p2pkh(&[0x11; 20]);
+        let p2wpkh = ScriptBuf::new_p2wpkh(&[0x11; 20]);
+
+        // Ten times the Bitcoin thresholds, from the higher dust relay fee of Litecoin.
+        assert_eq!(Network::Litecoin.dust_threshold(&p2pkh).to_sat(), 5_460);
+        assert_eq!(Network::Litecoin.dust_threshold(&p2wpkh).to_sat(), 2_940);
+
+        assert_eq!(
+            Network::Dogecoin.dust_threshold(&p2pkh).to_sat(),
+            DOGECOIN_DUST_LIMIT
+        );
+        assert_eq!(
+            Network::Dogecoin.dust_threshold(&ScriptBuf::from_bytes(vec![OP_RETURN])),
+            Amount::ZERO
+        );
+    }
+
+    #[test]
+    fn test_min_relay_fee() {
+        assert_eq!(Network::Mainnet.min_relay_fee(), MIN_RELAY_TX_FEE);
+        assert_eq!(Network::Signet.min_relay_fee(), MIN_RELAY_TX_FEE);
+        assert_eq!(Network::Litecoin.min_relay_fee(), LITECOIN_MIN_RELAY_TX_FEE);
+        assert_eq!(
+            Network::DogecoinTestnet.min_relay_fee(),
+            DOGECOIN_MIN_RELAY_TX_FEE
+        );
+    }
+
+    #[test]
+    fn test_network_serde_and_from_str() {
+        let json = serde_json::to_string(&Network::Testnet4).unwrap();
+        assert_eq!(json, r#""testnet4""#);
+        assert_eq!(
+            serde_json::from_str::<Network>(&json).unwrap(),
+            Network::Testnet4
+        );
+        assert_eq!("signet".parse::<Network>().unwrap(), Network::Signet);
+        assert!("unknown".parse::<Network>().is_err());
+
+        for network in [Network::LitecoinTestnet, Network::Dogecoin] {
+            let json = serde_json::to_string(&network).unwrap();
+            assert_eq!(json, format!(r#""{network}""#));
+            assert_eq!(network.to_string().parse::<Network>().unwrap(), network);
+        }
+    }
+}
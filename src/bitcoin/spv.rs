@@ -0,0 +1,462 @@
+//! Simplified payment verification (SPV): header chain validation and merkle inclusion proofs.
+//!
+//! A contract that trusts a checkpoint header can use these to check that a chain of headers
+//! extends it with valid proof of work, and that a transaction is included in one of its blocks.
+//! The consensus rules checked are those of the Bitcoin networks; headers of chains using a
+//! different proof of work, such as Litecoin and Dogecoin, are rejected.
+use super::{
+    error::BitcoinError,
+    hashes::sha256d,
+    network::Network,
+    types::{BlockHeader, Hash, Target, Txid},
+};
+
+/// Number of blocks between difficulty adjustments.
+pub const DIFFICULTY_ADJUSTMENT_INTERVAL: u32 = 2016;
+/// Expected time between blocks, in seconds.
+pub const POW_TARGET_SPACING: u32 = 10 * 60;
+/// Expected duration of a difficulty period, in seconds.
+pub const POW_TARGET_TIMESPAN: u32 = 14 * 24 * 60 * 60;
+
+/// Validates that `headers` form a chain starting at `anchor_height` with valid proof of work.
+///
+/// The first header is the trusted anchor, usually a checkpoint. Every following header must
+/// reference the previous one, carry the difficulty required by the network, and meet it.
+/// Returns the hash of the last header.
+///
+/// Difficulty adjustments can only be checked when the first header of the ending period is
+/// part of `headers`, so the anchor should be at a multiple of [`DIFFICULTY_ADJUSTMENT_INTERVAL`]
+/// when the chain crosses a boundary. Timestamps are not checked against the median time past.
+pub fn validate_header_chain(
+    network: Network,
+    anchor_height: u32,
+    headers: &[BlockHeader],
+) -> Result<Hash, BitcoinError> {
+    let (anchor, _) = headers
+        .split_first()
+        .ok_or(BitcoinError::InvalidHeaderChain {
+            height: anchor_height,
+            reason: "empty header chain",
+        })?;
+    let mut tip = anchor.validate_pow(network)?;
+
+    for i in 1..headers.len() {
+        let height = header_height(anchor_height, i)?;
+        let header = &headers[i];
+        if header.prev_blockhash != tip {
+            return Err(BitcoinError::InvalidHeaderChain {
+                height,
+                reason: "header does not reference the previous block",
+            });
+        }
+        if header.bits != next_work_required(network, anchor_height, &headers[..i], header)? {
+            return Err(BitcoinError::InvalidHeaderChain {
+                height,
+                reason: "unexpected difficulty target",
+            });
+        }
+        tip = header
+            .validate_pow(network)
+            .map_err(|_| BitcoinError::InvalidHeaderChain {
+                height,
+                reason: "block hash does not meet the target",
+            })?;
+    }
+
+    Ok(tip)
+}
+
+/// Computes the compact target of the first block of a new difficulty period, from the first
+/// and the last block of the period that ends.
+pub fn next_retarget_bits(
+    network: Network,
+    period_first: &BlockHeader,
+    period_last: &BlockHeader,
+) -> u32 {
+    if network.no_pow_retargeting() {
+        return period_last.bits;
+    }
+
+    let target_timespan = i64::from(POW_TARGET_TIMESPAN);
+    let timespan = (i64::from(period_last.time) - i64::from(period_first.time))
+        .clamp(target_timespan / 4, target_timespan * 4) as u64;
+
+    // BIP-94 starts from the first block, which cannot be a minimum difficulty block.
+    let base_bits = if network.enforces_bip94() {
+        period_first.bits
+    } else {
+        period_last.bits
+    };
+
+    let pow_limit = network.pow_limit();
+    Target::from_compact(base_bits)
+        .and_then(|target| target.checked_mul(timespan))
+        .map(|target| target.div(u64::from(POW_TARGET_TIMESPAN)))
+        .filter(|target| *target <= pow_limit)
+        .unwrap_or(pow_limit)
+        .to_compact_lossy()
+}
+
+/// The compact target required for `header`, the child of the last of `previous` headers.
+///
+/// Mirrors `GetNextWorkRequired` of Bitcoin Core, looking back no further than the anchor.
+fn next_work_required(
+    network: Network,
+    anchor_height: u32,
+    previous: &[BlockHeader],
+    header: &BlockHeader,
+) -> Result<u32, BitcoinError> {
+    let last_index = previous.len() - 1;
+    let last = &previous[last_index];
+    let height = header_height(anchor_height, previous.len())?;
+
+    if height % DIFFICULTY_ADJUSTMENT_INTERVAL != 0 {
+        if !network.allows_min_difficulty_blocks() {
+            return Ok(last.bits);
+        }
+
+        let pow_limit_bits = network.pow_limit().to_compact_lossy();
+        if header.time > last.time.saturating_add(2 * POW_TARGET_SPACING) {
+            return Ok(pow_limit_bits);
+        }
+
+        // Otherwise use the target of the last block that was not mined at minimum difficulty.
+        let mut i = last_index;
+        while i > 0
+            && header_height(anchor_height, i)? % DIFFICULTY_ADJUSTMENT_INTERVAL != 0
+            && previous[i].bits == pow_limit_bits
+        {
+            i -= 1;
+        }
+        return Ok(previous[i].bits);
+    }
+
+    let first_index = (previous.len() as u32)
+        .checked_sub(DIFFICULTY_ADJUSTMENT_INTERVAL)
+        .ok_or(BitcoinError::InvalidHeaderChain {
+            height,
+            reason: "the first header of the difficulty period is missing",
+        })?;
+
+    Ok(next_retarget_bits(
+        network,
+        &previous[first_index as usize],
+        last,
+    ))
+}
+
+/// The height of the header `offset` blocks after the anchor.
+fn header_height(anchor_height: u32, offset: usize) -> Result<u32, BitcoinError> {
+    u32::try_from(offset)
+        .ok()
+        .and_then(|offset| anchor_height.checked_add(offset))
+        .ok_or(BitcoinError::InvalidHeaderChain {
+            height: u32::MAX,
+            reason: "header height overflows",
+        })
+}
+
+/// Computes the merkle root of the transaction ids of a block.
+///
+/// Returns [`None`] if `txids` is empty.
+pub fn merkle_root(txids: &[Txid]) -> Option<Hash> {
+    let mut level: Vec<[u8; 32]> = txids
+        .iter()
+        .map(|txid| txid.0.to_internal_bytes())
+        .collect();
+    if level.is_empty() {
+        return None;
+    }
+    while level.len() > 1 {
+        level = next_level(&level);
+    }
+    Some(Hash::from_internal_bytes(level[0]))
+}
+
+/// Computes the merkle branch proving that the transaction at `index` is part of a block.
+///
+/// Returns [`None`] if `index` is out of range.
+pub fn merkle_branch(txids: &[Txid], index: usize) -> Option<Vec<Hash>> {
+    if index >= txids.len() {
+        return None;
+    }
+
+    let mut level: Vec<[u8; 32]> = txids
+        .iter()
+        .map(|txid| txid.0.to_internal_bytes())
+        .collect();
+    let mut index = index;
+    let mut branch = Vec::new();
+    while level.len() > 1 {
+        // The last node is paired with itself on levels with an odd number of nodes.
+        let sibling = level.get(index ^ 1).unwrap_or(&level[index]);
+        branch.push(Hash::from_internal_bytes(*sibling));
+        level = next_level(&level);
+        index /= 2;
+    }
+    Some(branch)
+}
+
+/// Verifies that `txid` is the leaf at `index` of the merkle tree committed to by `merkle_root`.
+///
+/// A 64 byte transaction is indistinguishable from an inner node of the tree, so callers must
+/// also check that the proven transaction is not 64 bytes long.
+pub fn verify_merkle_proof(txid: &Txid, merkle_root: &Hash, branch: &[Hash], index: u32) -> bool {
+    if branch.len() < 32 && index >> branch.len() != 0 {
+        return false;
+    }
+
+    let mut node = txid.0.to_internal_bytes();
+    for (level, sibling) in branch.iter().enumerate() {
+        let sibling = sibling.to_internal_bytes();
+        node = if (index >> level) & 1 == 1 {
+            hash_nodes(&sibling, &node)
+        } else {
+            hash_nodes(&node, &sibling)
+        };
+    }
+
+    node == merkle_root.to_internal_bytes()
+}
+
+fn next_level(level: &[[u8; 32]]) -> Vec<[u8; 32]> {
+    level
+        .chunks(2)
+        .map(|pair| hash_nodes(&pair[0], pair.get(1).unwrap_or(&pair[0])))
+        .collect()
+}
+
+fn hash_nodes(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut data = [0u8; 64];
+    data[..32].copy_from_slice(left);
+    data[32..].copy_from_slice(right);
+    sha256d(&data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::hashes::Hash as _;
+
+    // Header of mainnet block 1
+    const BLOCK_1: &str = "010000006fe28c0ab6f1b372c1a6a246ae63f74f931e8365e15a089c68d6190000000000982051fd1e4ba744bbbe680e1fee14677ba1a3c3540bf7b1cdb606e857233e0e61bc6649ffff001d01e36299";
+
+    fn genesis_header(network: bitcoin::Network) -> BlockHeader {
+        let genesis = bitcoin::blockdata::constants::genesis_block(network).header;
+        BlockHeader::deserialize(&bitcoin::consensus::serialize(&genesis)).unwrap()
+    }
+
+    /// Mines a regtest header on top of `prev`, which takes a couple of tries at most.
+    fn mine_regtest_header(prev: &BlockHeader, time: u32) -> BlockHeader {
+        let mut header = BlockHeader {
+            version: 0x2000_0000,
+            prev_blockhash: prev.block_hash(),
+            merkle_root: Hash([0x42; 32]),
+            time,
+            bits: 0x207fffff,
+            nonce: 0,
+        };
+        while header.validate_pow(Network::Regtest).is_err() {
+            header.nonce += 1;
+        }
+        header
+    }
+
+    fn txids(n: u8) -> Vec<Txid> {
+        (0..n).map(|i| Txid(Hash([i; 32]))).collect()
+    }
+
+    #[test]
+    fn test_validate_mainnet_header_chain() {
+        let genesis = genesis_header(bitcoin::Network::Bitcoin);
+        let block_1 = BlockHeader::deserialize(&hex::decode(BLOCK_1).unwrap()).unwrap();
+
+        assert_eq!(
+            validate_header_chain(Network::Mainnet, 0, &[genesis, block_1]),
+            Ok(block_1.block_hash())
+        );
+    }
+
+    #[test]
+    fn test_validate_regtest_header_chain() {
+        let mut headers = vec![genesis_header(bitcoin::Network::Regtest)];
+        for i in 1..=5 {
+            let prev = headers.last().unwrap();
+            headers.push(mine_regtest_header(prev, prev.time + 600 * i));
+        }
+
+        assert_eq!(
+            validate_header_chain(Network::Regtest, 0, &headers),
+            Ok(headers[5].block_hash())
+        );
+
+        let mut broken = headers.clone();
+        broken.remove(3);
+        assert_eq!(
+            validate_header_chain(Network::Regtest, 0, &broken),
+            Err(BitcoinError::InvalidHeaderChain {
+                height: 3,
+                reason: "header does not reference the previous block"
+            })
+        );
+
+        let mut harder = headers.clone();
+        harder[1].bits = 0x1f7fffff;
+        harder[1].prev_blockhash = headers[0].block_hash();
+        assert_eq!(
+            validate_header_chain(Network::Regtest, 0, &harder[..2]),
+            Err(BitcoinError::InvalidHeaderChain {
+                height: 1,
+                reason: "unexpected difficulty target"
+            })
+        );
+
+        assert_eq!(
+            validate_header_chain(Network::Regtest, u32::MAX, &headers[..2]),
+            Err(BitcoinError::InvalidHeaderChain {
+                height: u32::MAX,
+                reason: "header height overflows"
+            })
+        );
+    }
+
+    #[test]
+    fn test_next_retarget_bits_against_rust_bitcoin() {
+        use bitcoin::pow::CompactTarget;
+
+        for (bits, timespan) in [
+            (0x1d00ffff, POW_TARGET_TIMESPAN * 5),
+            (0x1b0404cb, POW_TARGET_TIMESPAN / 2),
+            (0x17034219, POW_TARGET_TIMESPAN / 10),
+            (0x17034219, POW_TARGET_TIMESPAN + 12_345),
+        ] {
+            let first = BlockHeader {
+                version: 1,
+                prev_blockhash: Hash::all_zeros(),
+                merkle_root: Hash::all_zeros(),
+                time: 1_600_000_000,
+                bits,
+                nonce: 0,
+            };
+            let last = BlockHeader {
+                time: first.time + timespan,
+                ..first
+            };
+            let expected = CompactTarget::from_next_work_required(
+                CompactTarget::from_consensus(bits),
+                u64::from(timespan),
+                bitcoin::Network::Bitcoin,
+            );
+
+            assert_eq!(
+                next_retarget_bits(Network::Mainnet, &first, &last),
+                expected.to_consensus()
+            );
+        }
+    }
+
+    #[test]
+    fn test_next_work_required_with_min_difficulty_blocks() {
+        let pow_limit_bits = 0x1d00ffff;
+        let header = |time, bits| BlockHeader {
+            version: 1,
+            prev_blockhash: Hash::all_zeros(),
+            merkle_root: Hash::all_zeros(),
+            time,
+            bits,
+            nonce: 0,
+        };
+        let previous = [
+            header(1_000, 0x1c0fffff),
+            header(1_600, 0x1c0fffff),
+            header(4_000, pow_limit_bits),
+        ];
+
+        // More than 20 minutes after the previous block, the minimum difficulty is allowed.
+        assert_eq!(
+            next_work_required(Network::Testnet, 1, &previous, &header(5_300, 0)),
+            Ok(pow_limit_bits)
+        );
+        // Otherwise the difficulty of the last regular block applies.
+        assert_eq!(
+            next_work_required(Network::Testnet, 1, &previous, &header(4_100, 0)),
+            Ok(0x1c0fffff)
+        );
+        assert_eq!(
+            next_work_required(Network::Mainnet, 1, &previous, &header(5_300, 0)),
+            Ok(pow_limit_bits)
+        );
+        assert_eq!(
+            next_work_required(Network::Mainnet, 1, &previous[..2], &header(5_300, 0)),
+            Ok(0x1c0fffff)
+        );
+        // Headers close to the maximum height or time do not overflow.
+        assert_eq!(
+            next_work_required(Network::Testnet, u32::MAX - 1, &previous, &header(5_300, 0)),
+            Err(BitcoinError::InvalidHeaderChain {
+                height: u32::MAX,
+                reason: "header height overflows"
+            })
+        );
+        let late = [previous[1], header(u32::MAX - 100, pow_limit_bits)];
+        assert_eq!(
+            next_work_required(Network::Testnet, 1, &late, &header(u32::MAX, 0)),
+            Ok(0x1c0fffff)
+        );
+        // Crossing a difficulty adjustment needs the first header of the period.
+        assert_eq!(
+            next_work_required(Network::Mainnet, 2013, &previous, &header(5_300, 0)),
+            Err(BitcoinError::InvalidHeaderChain {
+                height: 2016,
+                reason: "the first header of the difficulty period is missing"
+            })
+        );
+    }
+
+    #[test]
+    fn test_merkle_root_against_rust_bitcoin() {
+        for n in 1..=7 {
+            let txids = txids(n);
+            let expected = bitcoin::merkle_tree::calculate_root(
+                txids
+                    .iter()
+                    .map(|txid| bitcoin::Txid::from_byte_array(txid.0.to_internal_bytes())),
+            )
+            .unwrap();
+
+            assert_eq!(
+                merkle_root(&txids).unwrap().to_string(),
+                expected.to_string()
+            );
+        }
+        assert_eq!(merkle_root(&[]), None);
+    }
+
+    #[test]
+    fn test_merkle_branch_and_proof() {
+        for n in 1..=7 {
+            let txids = txids(n);
+            let root = merkle_root(&txids).unwrap();
+
+            for (index, txid) in txids.iter().enumerate() {
+                let branch = merkle_branch(&txids, index).unwrap();
+                assert!(verify_merkle_proof(txid, &root, &branch, index as u32));
+
+                if n > 1 {
+                    let other = (index + 1) % txids.len();
+                    assert!(!verify_merkle_proof(txid, &root, &branch, other as u32));
+                }
+            }
+            assert_eq!(merkle_branch(&txids, n as usize), None);
+        }
+
+        let txids = txids(4);
+        let branch = merkle_branch(&txids, 1).unwrap();
+        assert!(!verify_merkle_proof(
+            &txids[1],
+            &merkle_root(&txids).unwrap(),
+            &branch,
+            1 + 4
+        ));
+    }
+}
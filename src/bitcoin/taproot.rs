@@ -0,0 +1,522 @@
+//! Taproot (BIP-341) output key derivation and script trees.
+use k256::elliptic_curve::{sec1::ToEncodedPoint, PrimeField};
+use k256::{schnorr::VerifyingKey, ProjectivePoint, PublicKey, Scalar};
+
+use super::{
+    address::Address, encoding::Encodable, error::BitcoinError, hashes::tagged_hash,
+    network::Network, types::ScriptBuf,
+};
+
+/// Leaf version of Tapscript leaves (BIP-342).
+pub const TAPROOT_LEAF_TAPSCRIPT: u8 = 0xc0;
+
+/// Maximum depth of a leaf in a Taproot script tree, bounding the size of control blocks.
+pub const TAPROOT_CONTROL_MAX_NODE_COUNT: usize = 128;
+
+/// Returns the x-only form of a public key, dropping the parity of its Y coordinate.
+///
+/// Accepts compressed (33 bytes), uncompressed (65 bytes) and already x-only (32 bytes) keys,
+/// such as the keys derived by the NEAR MPC signer.
+pub fn x_only_public_key(public_key: &[u8]) -> Result<[u8; 32], BitcoinError> {
+    if let Ok(x_only) = <[u8; 32]>::try_from(public_key) {
+        VerifyingKey::from_bytes(&x_only).map_err(|_| BitcoinError::InvalidTaprootKey)?;
+        return Ok(x_only);
+    }
+    let encoded = PublicKey::from_sec1_bytes(public_key)
+        .map_err(|_| BitcoinError::InvalidTaprootKey)?
+        .to_encoded_point(true);
+    Ok((*encoded.x().ok_or(BitcoinError::InvalidTaprootKey)?).into())
+}
+
+/// Computes the hash `hash_TapLeaf(leaf_version || compact_size(script) || script)` of a leaf.
+pub fn tap_leaf_hash(script: &ScriptBuf, leaf_version: u8) -> [u8; 32] {
+    let mut data = vec![leaf_version];
+    let _ = script.encode(&mut data);
+    tagged_hash("TapLeaf", &data)
+}
+
+/// Computes the hash of a branch from the hashes of its children, sorted lexicographically.
+pub fn tap_branch_hash(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    let mut data = left.to_vec();
+    data.extend_from_slice(right);
+    tagged_hash("TapBranch", &data)
+}
+
+/// Computes the tweak `t = hash_TapTweak(P || merkle_root)` committed to by a Taproot output key.
+pub fn tap_tweak_hash(internal_key: &[u8; 32], merkle_root: Option<&[u8; 32]>) -> [u8; 32] {
+    let mut data = internal_key.to_vec();
+    if let Some(merkle_root) = merkle_root {
+        data.extend_from_slice(merkle_root);
+    }
+    tagged_hash("TapTweak", &data)
+}
+
+/// Derives the Taproot output key `Q = P + t*G` from an x-only internal key.
+///
+/// Returns the x-only output key and whether its Y coordinate is odd, which is needed for
+/// script path control blocks. A `merkle_root` of [`None`] commits to a key path only output.
+pub fn tweak_internal_key(
+    internal_key: &[u8; 32],
+    merkle_root: Option<&[u8; 32]>,
+) -> Result<([u8; 32], bool), BitcoinError> {
+    let internal_point = VerifyingKey::from_bytes(internal_key)
+        .map_err(|_| BitcoinError::InvalidTaprootKey)?
+        .as_affine()
+        .to_owned();
+    let tweak = Option::<Scalar>::from(Scalar::from_repr(
+        tap_tweak_hash(internal_key, merkle_root).into(),
+    ))
+    .ok_or(BitcoinError::InvalidTaprootKey)?;
+
+    let output_point =
+        (ProjectivePoint::from(internal_point) + ProjectivePoint::GENERATOR * tweak).to_affine();
+    let encoded = output_point.to_encoded_point(true);
+    let output_key: [u8; 32] = (*encoded.x().ok_or(BitcoinError::InvalidTaprootKey)?).into();
+    let odd = encoded.as_bytes()[0] == 0x03;
+
+    Ok((output_key, odd))
+}
+
+/// A leaf of a Taproot script tree, with the hashes needed to prove its inclusion.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct LeafInfo {
+    script: ScriptBuf,
+    leaf_version: u8,
+    merkle_branch: Vec<[u8; 32]>,
+}
+
+/// A node of a Taproot script tree under construction, with the leaves below it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct NodeInfo {
+    hash: [u8; 32],
+    leaves: Vec<LeafInfo>,
+}
+
+impl NodeInfo {
+    fn combine(a: Self, b: Self) -> Self {
+        let hash = tap_branch_hash(&a.hash, &b.hash);
+        let mut leaves = Vec::with_capacity(a.leaves.len() + b.leaves.len());
+        for mut leaf in a.leaves {
+            leaf.merkle_branch.push(b.hash);
+            leaves.push(leaf);
+        }
+        for mut leaf in b.leaves {
+            leaf.merkle_branch.push(a.hash);
+            leaves.push(leaf);
+        }
+        Self { hash, leaves }
+    }
+}
+
+/// Builds a Taproot script tree from its leaves, given in depth-first order with their depth.
+///
+/// ###### Example:
+///
+/// ```rust
+/// // A tree with `a` at depth 1, and `b` and `c` at depth 2.
+/// let spend_info = TapTreeBuilder::new()
+///     .add_leaf(1, a)?
+///     .add_leaf(2, b.clone())?
+///     .add_leaf(2, c)?
+///     .finalize(&internal_key)?;
+///
+/// let address = spend_info.address(Network::Mainnet)?;
+/// let control_block = spend_info.control_block(&b, TAPROOT_LEAF_TAPSCRIPT).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct TapTreeBuilder {
+    branch: Vec<Option<NodeInfo>>,
+}
+
+impl Default for TapTreeBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TapTreeBuilder {
+    pub const fn new() -> Self {
+        Self { branch: Vec::new() }
+    }
+
+    /// Adds a Tapscript leaf at the given depth.
+    pub fn add_leaf(self, depth: u8, script: ScriptBuf) -> Result<Self, BitcoinError> {
+        self.add_leaf_with_version(depth, script, TAPROOT_LEAF_TAPSCRIPT)
+    }
+
+    /// Adds a leaf with a custom leaf version at the given depth.
+    pub fn add_leaf_with_version(
+        self,
+        depth: u8,
+        script: ScriptBuf,
+        leaf_version: u8,
+    ) -> Result<Self, BitcoinError> {
+        // Odd versions would collide with the parity bit of the control block, and 0x50 with
+        // the annex prefix.
+        if leaf_version & 0x01 != 0 || leaf_version == 0x50 {
+            return Err(BitcoinError::InvalidTapTree("invalid leaf version"));
+        }
+        let node = NodeInfo {
+            hash: tap_leaf_hash(&script, leaf_version),
+            leaves: vec![LeafInfo {
+                script,
+                leaf_version,
+                merkle_branch: Vec::new(),
+            }],
+        };
+        self.insert(node, depth)
+    }
+
+    /// Whether the leaves added so far form a complete tree.
+    pub fn is_finalizable(&self) -> bool {
+        matches!(self.branch[..], [] | [Some(_)])
+    }
+
+    /// Completes the tree and derives the output key committing to it.
+    ///
+    /// A builder without leaves gives a key path only output.
+    pub fn finalize(self, internal_key: &[u8; 32]) -> Result<TaprootSpendInfo, BitcoinError> {
+        let root = match self.branch.as_slice() {
+            [] => None,
+            [Some(root)] => Some(root.clone()),
+            _ => return Err(BitcoinError::InvalidTapTree("incomplete tree")),
+        };
+        let merkle_root = root.as_ref().map(|root| root.hash);
+        let (output_key, output_key_parity) =
+            tweak_internal_key(internal_key, merkle_root.as_ref())?;
+
+        Ok(TaprootSpendInfo {
+            internal_key: *internal_key,
+            merkle_root,
+            output_key,
+            output_key_parity,
+            leaves: root.map(|root| root.leaves).unwrap_or_default(),
+        })
+    }
+
+    /// Inserts a node at `depth`, merging it with its completed siblings, as in rust-bitcoin.
+    fn insert(mut self, mut node: NodeInfo, mut depth: u8) -> Result<Self, BitcoinError> {
+        let mut depth_index = usize::from(depth);
+        if depth_index > TAPROOT_CONTROL_MAX_NODE_COUNT {
+            return Err(BitcoinError::InvalidTapTree("leaf is too deep"));
+        }
+        // A node shallower than an unfinished branch is not in depth-first order.
+        if depth_index + 1 < self.branch.len() {
+            return Err(BitcoinError::InvalidTapTree(
+                "leaves are not in depth-first order",
+            ));
+        }
+
+        while self.branch.len() == depth_index + 1 {
+            let Some(sibling) = self.branch.pop().flatten() else {
+                self.branch.push(None);
+                break;
+            };
+            if depth == 0 {
+                return Err(BitcoinError::InvalidTapTree("tree is already complete"));
+            }
+            node = NodeInfo::combine(sibling, node);
+            depth -= 1;
+            depth_index -= 1;
+        }
+
+        if self.branch.len() < depth_index + 1 {
+            self.branch.resize(depth_index + 1, None);
+        }
+        self.branch[depth_index] = Some(node);
+        Ok(self)
+    }
+}
+
+/// The keys and script tree of a Taproot output, needed to receive to it and to spend it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaprootSpendInfo {
+    internal_key: [u8; 32],
+    merkle_root: Option<[u8; 32]>,
+    output_key: [u8; 32],
+    output_key_parity: bool,
+    leaves: Vec<LeafInfo>,
+}
+
+impl TaprootSpendInfo {
+    /// Spend info of an output without script path.
+    pub fn new_key_spend(internal_key: &[u8; 32]) -> Result<Self, BitcoinError> {
+        TapTreeBuilder::new().finalize(internal_key)
+    }
+
+    /// The x-only internal key.
+    pub const fn internal_key(&self) -> &[u8; 32] {
+        &self.internal_key
+    }
+
+    /// The root of the script tree, or [`None`] for key path only outputs.
+    pub const fn merkle_root(&self) -> Option<&[u8; 32]> {
+        self.merkle_root.as_ref()
+    }
+
+    /// The tweaked x-only output key, which is the witness program of the output.
+    pub const fn output_key(&self) -> &[u8; 32] {
+        &self.output_key
+    }
+
+    /// Whether the Y coordinate of the output key is odd.
+    pub const fn output_key_parity(&self) -> bool {
+        self.output_key_parity
+    }
+
+    /// The P2TR output script paying to the output key.
+    pub fn script_pubkey(&self) -> ScriptBuf {
+        ScriptBuf::new_p2tr(&self.output_key)
+    }
+
+    /// The P2TR address paying to the output key, failing on networks without SegWit.
+    pub fn address(&self, network: Network) -> Result<Address, BitcoinError> {
+        Address::p2tr_tweaked(self.output_key, network)
+    }
+
+    /// The control block proving that a leaf is part of the tree, to be pushed last on the
+    /// witness of a script path spend.
+    ///
+    /// Returns [`None`] if the tree has no such leaf. When the same leaf appears several
+    /// times, the control block of its shallowest occurrence is returned.
+    pub fn control_block(&self, script: &ScriptBuf, leaf_version: u8) -> Option<ControlBlock> {
+        self.leaves
+            .iter()
+            .filter(|leaf| &leaf.script == script && leaf.leaf_version == leaf_version)
+            .min_by_key(|leaf| leaf.merkle_branch.len())
+            .map(|leaf| ControlBlock {
+                leaf_version,
+                output_key_parity: self.output_key_parity,
+                internal_key: self.internal_key,
+                merkle_branch: leaf.merkle_branch.clone(),
+            })
+    }
+
+    /// The leaf hashes of the tree, in depth-first order.
+    pub fn leaf_hashes(&self) -> Vec<[u8; 32]> {
+        self.leaves
+            .iter()
+            .map(|leaf| tap_leaf_hash(&leaf.script, leaf.leaf_version))
+            .collect()
+    }
+}
+
+/// The proof that a leaf is committed to by a Taproot output key (BIP-341).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ControlBlock {
+    /// The version of the leaf.
+    pub leaf_version: u8,
+    /// Whether the Y coordinate of the output key is odd.
+    pub output_key_parity: bool,
+    /// The x-only internal key.
+    pub internal_key: [u8; 32],
+    /// The hashes of the siblings on the path from the leaf to the root.
+    pub merkle_branch: Vec<[u8; 32]>,
+}
+
+impl ControlBlock {
+    /// Encodes the control block as it appears in the witness.
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut buffer = vec![self.leaf_version | u8::from(self.output_key_parity)];
+        buffer.extend_from_slice(&self.internal_key);
+        for hash in &self.merkle_branch {
+            buffer.extend_from_slice(hash);
+        }
+        buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tweak_internal_key_against_rust_bitcoin() {
+        use bitcoin::key::{TapTweak, XOnlyPublicKey};
+        use bitcoin::secp256k1::Secp256k1;
+
+        let secp = Secp256k1::verification_only();
+        let internal_key: [u8; 32] =
+            hex::decode("79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798")
+                .unwrap()
+                .try_into()
+                .unwrap();
+        let merkle_root = [0x42; 32];
+
+        let rust_bitcoin_internal_key = XOnlyPublicKey::from_slice(&internal_key).unwrap();
+        for root in [None, Some(merkle_root)] {
+            let (expected, parity) = rust_bitcoin_internal_key
+                .tap_tweak(&secp, root.map(bitcoin::TapNodeHash::assume_hidden));
+            let (output_key, odd) = tweak_internal_key(&internal_key, root.as_ref()).unwrap();
+
+            assert_eq!(output_key, expected.to_x_only_public_key().serialize());
+            assert_eq!(odd, parity == bitcoin::secp256k1::Parity::Odd);
+        }
+    }
+
+    #[test]
+    fn test_tweak_internal_key_rejects_invalid_key() {
+        assert_eq!(
+            tweak_internal_key(&[0xff; 32], None),
+            Err(BitcoinError::InvalidTaprootKey)
+        );
+    }
+
+    fn internal_key() -> [u8; 32] {
+        hex::decode("79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798")
+            .unwrap()
+            .try_into()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_x_only_public_key() {
+        let compressed =
+            hex::decode("0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798")
+                .unwrap();
+        let uncompressed = hex::decode(
+            "0479be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798\
+             483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8",
+        )
+        .unwrap();
+
+        for key in [&compressed[..], &uncompressed, &internal_key()] {
+            assert_eq!(x_only_public_key(key), Ok(internal_key()));
+        }
+        assert_eq!(
+            x_only_public_key(&[0x02; 20]),
+            Err(BitcoinError::InvalidTaprootKey)
+        );
+    }
+
+    #[test]
+    fn test_tap_tree_against_rust_bitcoin() {
+        use bitcoin::hashes::Hash as _;
+        use bitcoin::key::XOnlyPublicKey;
+        use bitcoin::secp256k1::Secp256k1;
+        use bitcoin::taproot::{LeafVersion, TapLeafHash, TaprootBuilder};
+
+        let scripts: Vec<ScriptBuf> = ["51", "52", "53", "5487"]
+            .iter()
+            .map(|script| ScriptBuf::from_hex(script).unwrap())
+            .collect();
+        let depths = [1, 2, 3, 3];
+
+        let mut builder = TapTreeBuilder::new();
+        let mut rust_bitcoin_builder = TaprootBuilder::new();
+        for (depth, script) in depths.into_iter().zip(&scripts) {
+            assert!(!builder.is_finalizable() || depth == 1);
+            builder = builder.add_leaf(depth, script.clone()).unwrap();
+            rust_bitcoin_builder = rust_bitcoin_builder
+                .add_leaf(
+                    depth,
+                    bitcoin::ScriptBuf::from_bytes(script.as_bytes().to_vec()),
+                )
+                .unwrap();
+        }
+        let spend_info = builder.finalize(&internal_key()).unwrap();
+
+        let secp = Secp256k1::verification_only();
+        let expected = rust_bitcoin_builder
+            .finalize(&secp, XOnlyPublicKey::from_slice(&internal_key()).unwrap())
+            .unwrap();
+
+        assert_eq!(
+            spend_info.merkle_root().copied(),
+            expected.merkle_root().map(|root| root.to_byte_array())
+        );
+        assert_eq!(
+            spend_info.output_key(),
+            &expected.output_key().to_x_only_public_key().serialize()
+        );
+        assert_eq!(
+            spend_info.output_key_parity(),
+            expected.output_key_parity() == bitcoin::secp256k1::Parity::Odd
+        );
+        assert_eq!(
+            spend_info.address(Network::Mainnet).unwrap().to_string(),
+            bitcoin::Address::p2tr_tweaked(expected.output_key(), bitcoin::Network::Bitcoin)
+                .to_string()
+        );
+
+        for (script, leaf_hash) in scripts.iter().zip(spend_info.leaf_hashes()) {
+            let rust_bitcoin_script = bitcoin::ScriptBuf::from_bytes(script.as_bytes().to_vec());
+            assert_eq!(
+                leaf_hash,
+                TapLeafHash::from_script(&rust_bitcoin_script, LeafVersion::TapScript)
+                    .to_byte_array()
+            );
+            assert_eq!(
+                spend_info
+                    .control_block(script, TAPROOT_LEAF_TAPSCRIPT)
+                    .unwrap()
+                    .serialize(),
+                expected
+                    .control_block(&(rust_bitcoin_script, LeafVersion::TapScript))
+                    .unwrap()
+                    .serialize()
+            );
+        }
+        assert_eq!(
+            spend_info.control_block(&ScriptBuf::from_hex("55").unwrap(), TAPROOT_LEAF_TAPSCRIPT),
+            None
+        );
+    }
+
+    #[test]
+    fn test_tap_tree_key_spend_only() {
+        let spend_info = TapTreeBuilder::new().finalize(&internal_key()).unwrap();
+        let (output_key, _) = tweak_internal_key(&internal_key(), None).unwrap();
+
+        assert_eq!(
+            spend_info,
+            TaprootSpendInfo::new_key_spend(&internal_key()).unwrap()
+        );
+        assert_eq!(spend_info.merkle_root(), None);
+        assert_eq!(spend_info.output_key(), &output_key);
+        assert!(spend_info.script_pubkey().is_p2tr());
+        assert_eq!(
+            spend_info.address(Network::Dogecoin),
+            Err(BitcoinError::UnsupportedNetwork(Network::Dogecoin))
+        );
+    }
+
+    #[test]
+    fn test_tap_tree_builder_rejects_invalid_trees() {
+        let leaf = || ScriptBuf::from_hex("51").unwrap();
+
+        let incomplete = TapTreeBuilder::new().add_leaf(1, leaf()).unwrap();
+        assert!(!incomplete.is_finalizable());
+        assert_eq!(
+            incomplete.clone().finalize(&internal_key()),
+            Err(BitcoinError::InvalidTapTree("incomplete tree"))
+        );
+
+        let complete = incomplete.clone().add_leaf(1, leaf()).unwrap();
+        assert!(complete.is_finalizable());
+        assert_eq!(
+            complete.add_leaf(0, leaf()).unwrap_err(),
+            BitcoinError::InvalidTapTree("tree is already complete")
+        );
+
+        assert_eq!(
+            incomplete
+                .add_leaf(3, leaf())
+                .unwrap()
+                .add_leaf(1, leaf())
+                .unwrap_err(),
+            BitcoinError::InvalidTapTree("leaves are not in depth-first order")
+        );
+        assert_eq!(
+            TapTreeBuilder::new().add_leaf(129, leaf()).unwrap_err(),
+            BitcoinError::InvalidTapTree("leaf is too deep")
+        );
+        assert_eq!(
+            TapTreeBuilder::new()
+                .add_leaf_with_version(0, leaf(), 0xc1)
+                .unwrap_err(),
+            BitcoinError::InvalidTapTree("invalid leaf version")
+        );
+    }
+}
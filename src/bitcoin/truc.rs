@@ -0,0 +1,396 @@
+//! Topologically restricted until confirmation (TRUC) transactions and pay-to-anchor outputs.
+//!
+//! Version 3 transactions opt into the [BIP-431] policy: they are always replaceable and may
+//! only form unconfirmed packages of one parent and one child, which makes fee bumping through
+//! a pay-to-anchor (P2A) output of the parent reliable.
+//!
+//! [BIP-431]: https://github.com/bitcoin/bips/blob/master/bip-0431.mediawiki
+use super::{
+    bitcoin_transaction::BitcoinTransaction,
+    error::BitcoinError,
+    types::{LockTime, OutPoint, ScriptBuf, Sequence, TxIn, TxOut, Version, Witness},
+};
+use crate::transaction_builder::TxBuilder;
+
+/// Maximum virtual size of a TRUC transaction.
+pub const TRUC_MAX_VSIZE: u64 = 10_000;
+
+/// Maximum virtual size of a TRUC transaction with an unconfirmed parent.
+pub const TRUC_CHILD_MAX_VSIZE: u64 = 1_000;
+
+/// Checks a package of unconfirmed transactions against the TRUC policy.
+///
+/// The package must hold every unconfirmed ancestor and descendant of its transactions, as
+/// relationships are only found between the transactions given. The rules are:
+///
+/// - TRUC transactions only spend unconfirmed TRUC transactions, and the other way around.
+/// - A TRUC transaction is at most [`TRUC_MAX_VSIZE`] virtual bytes.
+/// - A TRUC transaction has at most one unconfirmed ancestor and one unconfirmed descendant.
+/// - A TRUC transaction with an unconfirmed parent is at most [`TRUC_CHILD_MAX_VSIZE`]
+///   virtual bytes.
+pub fn validate_truc_package(package: &[BitcoinTransaction]) -> Result<(), BitcoinError> {
+    let txids: Vec<_> = package
+        .iter()
+        .map(BitcoinTransaction::compute_txid)
+        .collect();
+    let parents: Vec<Vec<usize>> = package
+        .iter()
+        .map(|tx| {
+            let mut parents: Vec<usize> = (0..package.len())
+                .filter(|&j| {
+                    tx.input
+                        .iter()
+                        .any(|input| input.previous_output.txid == txids[j])
+                })
+                .collect();
+            parents.dedup();
+            parents
+        })
+        .collect();
+
+    for (index, tx) in package.iter().enumerate() {
+        let violation = |reason| BitcoinError::TrucViolation { index, reason };
+        let is_truc = tx.version == Version::Three;
+
+        for &parent in &parents[index] {
+            match (is_truc, package[parent].version == Version::Three) {
+                (true, false) => {
+                    return Err(violation("spends an unconfirmed non-TRUC transaction"))
+                }
+                (false, true) => return Err(violation("spends an unconfirmed TRUC transaction")),
+                _ => {}
+            }
+        }
+        if !is_truc {
+            continue;
+        }
+
+        let vsize = tx.vsize();
+        if vsize > TRUC_MAX_VSIZE {
+            return Err(violation("exceeds the maximum TRUC size"));
+        }
+        match parents[index].as_slice() {
+            [] => {}
+            [parent] => {
+                if !parents[*parent].is_empty() {
+                    return Err(violation("has more than one unconfirmed ancestor"));
+                }
+                if vsize > TRUC_CHILD_MAX_VSIZE {
+                    return Err(violation("exceeds the maximum TRUC child size"));
+                }
+            }
+            _ => return Err(violation("has more than one unconfirmed ancestor")),
+        }
+        if parents.iter().filter(|p| p.contains(&index)).count() > 1 {
+            return Err(violation("has more than one unconfirmed child"));
+        }
+    }
+
+    Ok(())
+}
+
+/// Builds a version 3 child transaction spending the pay-to-anchor output of its parent.
+///
+/// The anchor is always the first input, with an empty script sig and witness, followed by the
+/// inputs paying the fee. The size limits of TRUC children depend on the witnesses, so check the
+/// signed package with [`validate_truc_package`].
+#[derive(Clone)]
+pub struct AnchorChildBuilder {
+    pub anchor: Option<OutPoint>,
+    pub lock_time: Option<LockTime>,
+    pub inputs: Option<Vec<TxIn>>,
+    pub outputs: Option<Vec<TxOut>>,
+}
+
+impl Default for AnchorChildBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TxBuilder<BitcoinTransaction> for AnchorChildBuilder {
+    fn build(&self) -> BitcoinTransaction {
+        self.try_build().unwrap_or_else(|e| panic!("{e}"))
+    }
+}
+
+impl AnchorChildBuilder {
+    pub const fn new() -> Self {
+        Self {
+            anchor: None,
+            lock_time: None,
+            inputs: None,
+            outputs: None,
+        }
+    }
+
+    /// Builds the child transaction, returning an error instead of panicking.
+    pub fn try_build(&self) -> Result<BitcoinTransaction, BitcoinError> {
+        let anchor = self
+            .anchor
+            .ok_or(BitcoinError::MissingField("anchor output"))?;
+        let inputs = self
+            .inputs
+            .as_ref()
+            .ok_or(BitcoinError::MissingField("inputs"))?;
+
+        let mut input = vec![TxIn {
+            previous_output: anchor,
+            script_sig: ScriptBuf::default(),
+            sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+            witness: Witness::new(),
+        }];
+        input.extend_from_slice(inputs);
+
+        Ok(BitcoinTransaction {
+            version: Version::Three,
+            lock_time: self
+                .lock_time
+                .ok_or(BitcoinError::MissingField("lock time"))?,
+            input,
+            output: self
+                .outputs
+                .clone()
+                .ok_or(BitcoinError::MissingField("outputs"))?,
+        })
+    }
+
+    /// Spends the first pay-to-anchor output of `parent`, if it has one.
+    pub fn parent(mut self, parent: &BitcoinTransaction) -> Self {
+        let txid = parent.compute_txid();
+        self.anchor = (0..)
+            .zip(&parent.output)
+            .find(|(_, output)| output.script_pubkey.is_p2a())
+            .map(|(vout, _)| OutPoint::new(txid, vout));
+        self
+    }
+
+    /// Spends the pay-to-anchor output at `anchor`.
+    pub const fn anchor(mut self, anchor: OutPoint) -> Self {
+        self.anchor = Some(anchor);
+        self
+    }
+
+    pub const fn lock_time(mut self, lock_time: LockTime) -> Self {
+        self.lock_time = Some(lock_time);
+        self
+    }
+
+    /// Inputs paying the fee, spent after the anchor.
+    pub fn inputs(mut self, inputs: Vec<TxIn>) -> Self {
+        self.inputs = Some(inputs);
+        self
+    }
+
+    pub fn outputs(mut self, outputs: Vec<TxOut>) -> Self {
+        self.outputs = Some(outputs);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitcoin::{
+        network::Network,
+        types::{opcodes::OP_RETURN, Amount, Txid},
+        BitcoinTransactionBuilder,
+    };
+
+    fn funding_input(byte: u8) -> TxIn {
+        TxIn {
+            previous_output: OutPoint::new(Txid(crate::bitcoin::types::Hash([byte; 32])), 0),
+            script_sig: ScriptBuf::default(),
+            sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+            witness: Witness::new(),
+        }
+    }
+
+    fn p2wpkh_output(sats: u64) -> TxOut {
+        TxOut {
+            value: Amount::from_sat(sats),
+            script_pubkey: ScriptBuf::new_p2wpkh(&[0x11; 20]),
+        }
+    }
+
+    fn anchor_output() -> TxOut {
+        TxOut {
+            value: Amount::ZERO,
+            script_pubkey: ScriptBuf::new_p2a(),
+        }
+    }
+
+    fn parent(version: Version) -> BitcoinTransaction {
+        BitcoinTransaction {
+            version,
+            lock_time: LockTime::from_height(0).unwrap(),
+            input: vec![funding_input(1)],
+            output: vec![p2wpkh_output(10_000), anchor_output()],
+        }
+    }
+
+    fn child(parent: &BitcoinTransaction, fee_input: u8) -> BitcoinTransaction {
+        AnchorChildBuilder::new()
+            .parent(parent)
+            .lock_time(LockTime::from_height(0).unwrap())
+            .inputs(vec![funding_input(fee_input)])
+            .outputs(vec![p2wpkh_output(5_000)])
+            .build()
+    }
+
+    #[test]
+    fn test_p2a_against_rust_bitcoin() {
+        let script = ScriptBuf::new_p2a();
+        let expected = bitcoin::ScriptBuf::new_p2a();
+
+        assert_eq!(script.as_bytes(), expected.as_bytes());
+        assert!(script.is_p2a());
+        assert!(!ScriptBuf::new_p2tr(&[0x11; 32]).is_p2a());
+        assert_eq!(
+            Network::Mainnet.dust_threshold(&script).to_sat(),
+            expected.minimal_non_dust().to_sat()
+        );
+    }
+
+    #[test]
+    fn test_anchor_child_builder() {
+        let parent = parent(Version::Three);
+        let child = child(&parent, 2);
+
+        assert_eq!(child.version, Version::Three);
+        assert_eq!(
+            child.input[0].previous_output,
+            OutPoint::new(parent.compute_txid(), 1)
+        );
+        assert!(child.input[0].script_sig.is_empty());
+        assert!(child.input[0].witness.is_empty());
+        assert_eq!(child.input[1], funding_input(2));
+        assert_eq!(validate_truc_package(&[parent, child]), Ok(()));
+    }
+
+    #[test]
+    fn test_anchor_child_builder_requires_anchor() {
+        let mut parent = parent(Version::Three);
+        parent.output.pop();
+
+        let result = AnchorChildBuilder::new()
+            .parent(&parent)
+            .lock_time(LockTime::from_height(0).unwrap())
+            .inputs(vec![])
+            .outputs(vec![])
+            .try_build();
+
+        assert_eq!(result, Err(BitcoinError::MissingField("anchor output")));
+    }
+
+    #[test]
+    fn test_builder_allows_ephemeral_dust_in_truc_transactions() {
+        let builder = BitcoinTransactionBuilder::new()
+            .lock_time(LockTime::from_height(0).unwrap())
+            .inputs(vec![funding_input(1)])
+            .outputs(vec![p2wpkh_output(10_000), anchor_output()])
+            .network(Network::Mainnet);
+
+        assert!(builder.clone().version(Version::Three).try_build().is_ok());
+        assert_eq!(
+            builder.clone().version(Version::Two).try_build(),
+            Err(BitcoinError::DustOutput {
+                index: 1,
+                threshold: Amount::from_sat(240)
+            })
+        );
+        assert_eq!(
+            builder
+                .outputs(vec![p2wpkh_output(1), anchor_output()])
+                .version(Version::Three)
+                .try_build(),
+            Err(BitcoinError::DustOutput {
+                index: 1,
+                threshold: Amount::from_sat(240)
+            })
+        );
+    }
+
+    #[test]
+    fn test_validate_truc_package_version_inheritance() {
+        let v3_parent = parent(Version::Three);
+        let mut v2_child = child(&v3_parent, 2);
+        v2_child.version = Version::Two;
+        assert_eq!(
+            validate_truc_package(&[v3_parent, v2_child]),
+            Err(BitcoinError::TrucViolation {
+                index: 1,
+                reason: "spends an unconfirmed TRUC transaction"
+            })
+        );
+
+        let v2_parent = parent(Version::Two);
+        let v3_child = child(&v2_parent, 2);
+        assert_eq!(
+            validate_truc_package(&[v2_parent, v3_child]),
+            Err(BitcoinError::TrucViolation {
+                index: 1,
+                reason: "spends an unconfirmed non-TRUC transaction"
+            })
+        );
+    }
+
+    #[test]
+    fn test_validate_truc_package_topology() {
+        let parent = parent(Version::Three);
+        let first_child = child(&parent, 2);
+        let second_child = child(&parent, 3);
+        assert_eq!(
+            validate_truc_package(&[parent.clone(), first_child.clone(), second_child]),
+            Err(BitcoinError::TrucViolation {
+                index: 0,
+                reason: "has more than one unconfirmed child"
+            })
+        );
+
+        let grandchild = AnchorChildBuilder::new()
+            .anchor(OutPoint::new(first_child.compute_txid(), 0))
+            .lock_time(LockTime::from_height(0).unwrap())
+            .inputs(vec![])
+            .outputs(vec![p2wpkh_output(1_000)])
+            .build();
+        assert_eq!(
+            validate_truc_package(&[parent, first_child, grandchild]),
+            Err(BitcoinError::TrucViolation {
+                index: 2,
+                reason: "has more than one unconfirmed ancestor"
+            })
+        );
+    }
+
+    #[test]
+    fn test_validate_truc_package_size_limits() {
+        let data_output = |len| TxOut {
+            value: Amount::ZERO,
+            script_pubkey: ScriptBuf::from_bytes([vec![OP_RETURN], vec![0; len]].concat()),
+        };
+
+        let parent = parent(Version::Three);
+        let mut large_child = child(&parent, 2);
+        large_child.output.push(data_output(1_000));
+        assert_eq!(
+            validate_truc_package(&[parent.clone(), large_child.clone()]),
+            Err(BitcoinError::TrucViolation {
+                index: 1,
+                reason: "exceeds the maximum TRUC child size"
+            })
+        );
+        // Without an unconfirmed parent, the same transaction is within the limits.
+        assert_eq!(validate_truc_package(&[large_child]), Ok(()));
+
+        let mut oversized = parent;
+        oversized.output.push(data_output(10_000));
+        assert_eq!(
+            validate_truc_package(&[oversized]),
+            Err(BitcoinError::TrucViolation {
+                index: 0,
+                reason: "exceeds the maximum TRUC size"
+            })
+        );
+    }
+}
@@ -0,0 +1,195 @@
+use std::io::{BufRead, Write};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+
+use super::{target::Target, tx_in::Hash};
+use crate::bitcoin::{
+    encoding::{Decodable, Encodable},
+    error::BitcoinError,
+    hashes::sha256d,
+    network::Network,
+};
+
+/// A Bitcoin block header, the 80 bytes committed to by the proof of work.
+///
+/// ### Bitcoin Core References
+///
+/// * [CBlockHeader definition](https://github.com/bitcoin/bitcoin/blob/345457b542b6a980ccfbc868af0970a6f91d1b82/src/primitives/block.h#L20)
+#[derive(
+    Debug,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
+    JsonSchema,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BlockHeader {
+    /// Block version, used to signal soft fork deployments (BIP-9).
+    pub version: i32,
+    /// Hash of the previous block header.
+    pub prev_blockhash: Hash,
+    /// Root of the merkle tree of the transaction ids of the block.
+    pub merkle_root: Hash,
+    /// Block timestamp, in seconds since the Unix epoch.
+    pub time: u32,
+    /// Proof of work target in compact form.
+    pub bits: u32,
+    /// Nonce varied by miners to meet the target.
+    pub nonce: u32,
+}
+
+impl BlockHeader {
+    /// The number of bytes of a serialized block header.
+    pub const SIZE: usize = 80;
+
+    /// Encode the block header into a vector of bytes
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut buffer = Vec::with_capacity(Self::SIZE);
+
+        let _ = self.encode(&mut buffer);
+
+        buffer
+    }
+
+    /// Decode a block header from its 80 byte serialization
+    pub fn deserialize(bytes: &[u8]) -> Result<Self, BitcoinError> {
+        if bytes.len() != Self::SIZE {
+            return Err(BitcoinError::Encoding(format!(
+                "Block header must be {} bytes, got {}",
+                Self::SIZE,
+                bytes.len()
+            )));
+        }
+        Ok(Self::decode(&mut &bytes[..])?)
+    }
+
+    /// The block hash, sha256d of the serialized header.
+    pub fn block_hash(&self) -> Hash {
+        Hash::from_internal_bytes(sha256d(&self.serialize()))
+    }
+
+    /// The proof of work target encoded by [`Self::bits`], if it is a valid positive target.
+    pub fn target(&self) -> Option<Target> {
+        Target::from_compact(self.bits).filter(|target| *target != Target::ZERO)
+    }
+
+    /// Checks that the target is valid on the network and that the block hash meets it.
+    ///
+    /// Returns the block hash on success. Only the sha256d proof of work of the Bitcoin
+    /// networks can be checked.
+    pub fn validate_pow(&self, network: Network) -> Result<Hash, BitcoinError> {
+        if !network.is_bitcoin() {
+            return Err(BitcoinError::UnsupportedNetwork(network));
+        }
+        let target = self
+            .target()
+            .filter(|target| *target <= network.pow_limit())
+            .ok_or(BitcoinError::InvalidProofOfWork)?;
+
+        let block_hash = self.block_hash();
+        if Target::from_le_bytes(block_hash.to_internal_bytes()) > target {
+            return Err(BitcoinError::InvalidProofOfWork);
+        }
+
+        Ok(block_hash)
+    }
+}
+
+impl Encodable for BlockHeader {
+    fn encode<W: Write + ?Sized>(&self, w: &mut W) -> Result<usize, std::io::Error> {
+        let mut len = 0;
+        len += self.version.encode(w)?;
+        len += self.prev_blockhash.encode(w)?;
+        len += self.merkle_root.encode(w)?;
+        len += self.time.encode(w)?;
+        len += self.bits.encode(w)?;
+        len += self.nonce.encode(w)?;
+        Ok(len)
+    }
+}
+
+impl Decodable for BlockHeader {
+    fn decode<R: BufRead + ?Sized>(r: &mut R) -> Result<Self, std::io::Error> {
+        Ok(Self {
+            version: Decodable::decode(r)?,
+            prev_blockhash: Decodable::decode(r)?,
+            merkle_root: Decodable::decode(r)?,
+            time: Decodable::decode(r)?,
+            bits: Decodable::decode(r)?,
+            nonce: Decodable::decode(r)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::blockdata::constants::genesis_block;
+
+    // Header of mainnet block 1
+    const BLOCK_1: &str = "010000006fe28c0ab6f1b372c1a6a246ae63f74f931e8365e15a089c68d6190000000000982051fd1e4ba744bbbe680e1fee14677ba1a3c3540bf7b1cdb606e857233e0e61bc6649ffff001d01e36299";
+
+    #[test]
+    fn test_encode_decode_against_rust_bitcoin() {
+        let genesis = genesis_block(bitcoin::Network::Bitcoin).header;
+        let serialized = bitcoin::consensus::serialize(&genesis);
+
+        let header = BlockHeader::deserialize(&serialized).unwrap();
+
+        assert_eq!(header.serialize(), serialized);
+        assert_eq!(
+            header.block_hash().to_string(),
+            genesis.block_hash().to_string()
+        );
+        assert_eq!(
+            header.merkle_root.to_string(),
+            genesis.merkle_root.to_string()
+        );
+        assert_eq!(header.block_hash(), Network::Mainnet.genesis_hash());
+    }
+
+    #[test]
+    fn test_validate_pow() {
+        let header = BlockHeader::deserialize(&hex::decode(BLOCK_1).unwrap()).unwrap();
+
+        assert_eq!(
+            header.validate_pow(Network::Mainnet).unwrap().to_string(),
+            "00000000839a8e6886ab5951d76f411475428afc90947ee320161bbf18eb6048"
+        );
+        assert_eq!(header.prev_blockhash, Network::Mainnet.genesis_hash());
+
+        let tampered = BlockHeader {
+            nonce: header.nonce + 1,
+            ..header
+        };
+        assert_eq!(
+            tampered.validate_pow(Network::Mainnet),
+            Err(BitcoinError::InvalidProofOfWork)
+        );
+
+        // A target above the network limit is rejected even if the hash meets it.
+        let easy = BlockHeader {
+            bits: 0x207fffff,
+            ..header
+        };
+        assert_eq!(
+            easy.validate_pow(Network::Mainnet),
+            Err(BitcoinError::InvalidProofOfWork)
+        );
+    }
+
+    #[test]
+    fn test_deserialize_rejects_wrong_length() {
+        assert!(matches!(
+            BlockHeader::deserialize(&[0u8; 79]),
+            Err(BitcoinError::Encoding(_))
+        ));
+    }
+}
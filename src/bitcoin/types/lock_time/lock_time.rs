@@ -49,6 +49,12 @@ impl LockTime {
         }
     }
 
+    /// Creates a lock time from its consensus encoding, a block height below 500 000 000
+    /// and a Unix timestamp otherwise.
+    pub const fn from_consensus(n: u32) -> Self {
+        Self(n)
+    }
+
     pub const fn is_block_height(&self) -> bool {
         Height::is_valid(self.0)
     }
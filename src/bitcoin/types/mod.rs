@@ -1,17 +1,23 @@
 //! Minimal required Bitcoin types, inspired by <https://github.com/rust-bitcoin/rust-bitcoin>
+mod block_header;
 mod lock_time;
+pub mod opcodes;
 mod script_buf;
 mod sighash;
+mod target;
 mod transaction_type;
 mod tx_in;
 mod tx_out;
+mod utxo;
 mod version;
 
+pub use self::block_header::BlockHeader;
 pub use self::lock_time::height::Height;
 pub use self::lock_time::time::Time;
 pub use self::lock_time::LockTime;
 pub use self::script_buf::ScriptBuf;
-pub use self::sighash::EcdsaSighashType;
+pub use self::sighash::{EcdsaSighashType, TapSighashType, SIGHASH_FORKID};
+pub use self::target::Target;
 pub use self::transaction_type::TransactionType;
 pub use self::tx_in::Hash;
 pub use self::tx_in::OutPoint;
@@ -19,6 +25,7 @@ pub use self::tx_in::Sequence;
 pub use self::tx_in::TxIn;
 pub use self::tx_in::Txid;
 pub use self::tx_in::Witness;
-pub use self::tx_out::Amount;
 pub use self::tx_out::TxOut;
+pub use self::tx_out::{Amount, Denomination, ParseAmountError, SignedAmount};
+pub use self::utxo::Utxo;
 pub use self::version::Version;
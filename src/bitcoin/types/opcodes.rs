@@ -0,0 +1,28 @@
+//! Script opcodes used by the standard output and input scripts.
+
+/// Push an empty array onto the stack, also the SegWit version 0 marker.
+pub const OP_0: u8 = 0x00;
+/// Read the next byte as N; push the next N bytes as an array onto the stack.
+pub const OP_PUSHDATA1: u8 = 0x4c;
+/// Read the next 2 bytes as N; push the next N bytes as an array onto the stack.
+pub const OP_PUSHDATA2: u8 = 0x4d;
+/// Read the next 4 bytes as N; push the next N bytes as an array onto the stack.
+pub const OP_PUSHDATA4: u8 = 0x4e;
+/// Push the number 1 onto the stack, also the SegWit version 1 (Taproot) marker.
+pub const OP_1: u8 = 0x51;
+/// Push the number 16 onto the stack.
+pub const OP_16: u8 = 0x60;
+/// Mark the output as unspendable, used to carry data.
+pub const OP_RETURN: u8 = 0x6a;
+/// Duplicate the top stack item.
+pub const OP_DUP: u8 = 0x76;
+/// Push 1 if the top two items are equal, 0 otherwise.
+pub const OP_EQUAL: u8 = 0x87;
+/// Same as `OP_EQUAL`, but run `OP_VERIFY` afterwards.
+pub const OP_EQUALVERIFY: u8 = 0x88;
+/// Pop the top stack item and push its HASH160.
+pub const OP_HASH160: u8 = 0xa9;
+/// Pop a public key and signature and push 1 if the signature is valid.
+pub const OP_CHECKSIG: u8 = 0xac;
+/// Pop a threshold, public keys and signatures and push 1 if enough signatures are valid.
+pub const OP_CHECKMULTISIG: u8 = 0xae;
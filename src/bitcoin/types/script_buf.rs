@@ -6,6 +6,14 @@ use schemars::JsonSchema;
 
 use crate::bitcoin::encoding::{encode::Encodable, Decodable};
 
+use super::opcodes::{
+    OP_0, OP_1, OP_16, OP_CHECKSIG, OP_DUP, OP_EQUAL, OP_EQUALVERIFY, OP_HASH160, OP_PUSHDATA1,
+    OP_PUSHDATA2, OP_PUSHDATA4, OP_RETURN,
+};
+
+/// The pay-to-anchor output script: a witness version 1 program of the two bytes `0x4e73`.
+const P2A_SCRIPT: [u8; 4] = [OP_1, 0x02, 0x4e, 0x73];
+
 #[derive(Debug, Default, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize, JsonSchema)]
 pub struct ScriptBuf(pub Vec<u8>);
 
@@ -28,6 +36,164 @@ impl ScriptBuf {
         script.extend_from_slice(&self.0);
         Self(script)
     }
+
+    /// Generates a P2PKH output script from a public key hash.
+    pub fn new_p2pkh(pubkey_hash: &[u8; 20]) -> Self {
+        let mut script = Self(vec![OP_DUP, OP_HASH160]);
+        script.push_slice(pubkey_hash);
+        script.push_opcode(OP_EQUALVERIFY);
+        script.push_opcode(OP_CHECKSIG);
+        script
+    }
+
+    /// Generates a P2SH output script from a script hash.
+    pub fn new_p2sh(script_hash: &[u8; 20]) -> Self {
+        let mut script = Self(vec![OP_HASH160]);
+        script.push_slice(script_hash);
+        script.push_opcode(OP_EQUAL);
+        script
+    }
+
+    /// Generates a P2WPKH output script from a public key hash.
+    pub fn new_p2wpkh(pubkey_hash: &[u8; 20]) -> Self {
+        Self::new_witness_program(0, pubkey_hash)
+    }
+
+    /// Generates a P2WSH output script from a witness script hash.
+    pub fn new_p2wsh(script_hash: &[u8; 32]) -> Self {
+        Self::new_witness_program(0, script_hash)
+    }
+
+    /// Generates a P2TR output script from an x-only output key.
+    pub fn new_p2tr(output_key: &[u8; 32]) -> Self {
+        Self::new_witness_program(1, output_key)
+    }
+
+    /// Generates the pay-to-anchor (P2A) output script, `OP_1 <0x4e73>`, spendable by anyone
+    /// with an empty witness to bump the fee of its transaction.
+    pub fn new_p2a() -> Self {
+        Self(P2A_SCRIPT.to_vec())
+    }
+
+    /// Generates a witness program output script: the version opcode followed by a single push.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the version is greater than 16.
+    pub fn new_witness_program(version: u8, program: &[u8]) -> Self {
+        assert!(version <= 16, "Invalid witness version: {version}");
+        let version_opcode = if version == 0 {
+            OP_0
+        } else {
+            OP_1 + version - 1
+        };
+        let mut script = Self(vec![version_opcode]);
+        script.push_slice(program);
+        script
+    }
+
+    /// Returns the script as a byte slice.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Returns the length of the script in bytes.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if the script is empty.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Appends a single opcode to the script.
+    pub fn push_opcode(&mut self, opcode: u8) {
+        self.0.push(opcode);
+    }
+
+    /// Appends a data push to the script, using the shortest push opcode for its length.
+    pub fn push_slice(&mut self, data: &[u8]) {
+        let len = data.len();
+        if len < OP_PUSHDATA1 as usize {
+            self.0.push(len as u8);
+        } else if len <= u8::MAX as usize {
+            self.0.push(OP_PUSHDATA1);
+            self.0.push(len as u8);
+        } else if len <= u16::MAX as usize {
+            self.0.push(OP_PUSHDATA2);
+            self.0.extend_from_slice(&(len as u16).to_le_bytes());
+        } else {
+            self.0.push(OP_PUSHDATA4);
+            self.0.extend_from_slice(&(len as u32).to_le_bytes());
+        }
+        self.0.extend_from_slice(data);
+    }
+
+    /// Checks whether the script is a P2PKH output script.
+    pub fn is_p2pkh(&self) -> bool {
+        self.0.len() == 25
+            && self.0[0] == OP_DUP
+            && self.0[1] == OP_HASH160
+            && self.0[2] == 20
+            && self.0[23] == OP_EQUALVERIFY
+            && self.0[24] == OP_CHECKSIG
+    }
+
+    /// Checks whether the script is a P2SH output script.
+    pub fn is_p2sh(&self) -> bool {
+        self.0.len() == 23 && self.0[0] == OP_HASH160 && self.0[1] == 20 && self.0[22] == OP_EQUAL
+    }
+
+    /// Checks whether the script is a P2WPKH output script.
+    pub fn is_p2wpkh(&self) -> bool {
+        self.witness_version() == Some(0) && self.0.len() == 22
+    }
+
+    /// Checks whether the script is a P2WSH output script.
+    pub fn is_p2wsh(&self) -> bool {
+        self.witness_version() == Some(0) && self.0.len() == 34
+    }
+
+    /// Checks whether the script is a P2TR output script.
+    pub fn is_p2tr(&self) -> bool {
+        self.witness_version() == Some(1) && self.0.len() == 34
+    }
+
+    /// Checks whether the script is a pay-to-anchor (P2A) output script.
+    pub fn is_p2a(&self) -> bool {
+        self.0 == P2A_SCRIPT
+    }
+
+    /// Checks whether the script is a provably unspendable `OP_RETURN` output script.
+    pub fn is_op_return(&self) -> bool {
+        self.0.first() == Some(&OP_RETURN)
+    }
+
+    /// Checks whether the script is a witness program (BIP-141).
+    pub fn is_witness_program(&self) -> bool {
+        self.witness_version().is_some()
+    }
+
+    /// Returns the witness version if the script is a witness program.
+    ///
+    /// A witness program is a version opcode followed by a single 2 to 40 byte push.
+    pub fn witness_version(&self) -> Option<u8> {
+        let len = self.0.len();
+        if !(4..=42).contains(&len) || self.0[1] as usize != len - 2 {
+            return None;
+        }
+        match self.0[0] {
+            OP_0 => Some(0),
+            op @ OP_1..=OP_16 => Some(op - OP_1 + 1),
+            _ => None,
+        }
+    }
+
+    /// Returns the witness program if the script is a witness program.
+    pub fn witness_program(&self) -> Option<&[u8]> {
+        self.witness_version().map(|_| &self.0[2..])
+    }
 }
 
 pub trait FromHex: Sized {
@@ -1,6 +1,10 @@
 use borsh::{BorshDeserialize, BorshSerialize};
 use near_sdk::serde::{Deserialize, Serialize};
 
+/// Flag set in the hash type of Bitcoin Cash and eCash signatures, which replay protects them
+/// from Bitcoin.
+pub const SIGHASH_FORKID: u32 = 0x40;
+
 #[derive(
     Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize,
 )]
@@ -9,3 +13,15 @@ pub enum EcdsaSighashType {
     /// 0x1: Sign all outputs.
     All = 0x01,
 }
+
+/// Hash type of a Taproot (BIP-341) signature.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize,
+)]
+#[borsh(use_discriminant = true)]
+pub enum TapSighashType {
+    /// 0x0: Sign all outputs, committed to by a 64 byte signature without hash type byte.
+    Default = 0x00,
+    /// 0x1: Sign all outputs.
+    All = 0x01,
+}
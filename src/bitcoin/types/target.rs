@@ -0,0 +1,184 @@
+//! 256-bit proof of work targets and their compact `nBits` encoding.
+use std::cmp::Ordering;
+
+/// A proof of work target: a block hash, read as a little endian number, must not exceed it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Target([u64; 4]);
+
+impl Target {
+    /// The zero target, which no block hash can meet.
+    pub const ZERO: Self = Self([0; 4]);
+
+    /// Decodes a target from its compact `nBits` representation.
+    ///
+    /// Returns [`None`] if the encoded value is negative or does not fit in 256 bits.
+    pub fn from_compact(bits: u32) -> Option<Self> {
+        let exponent = bits >> 24;
+        let mantissa = bits & 0x007f_ffff;
+        let negative = mantissa != 0 && bits & 0x0080_0000 != 0;
+        let overflow = mantissa != 0
+            && (exponent > 34
+                || (mantissa > 0xff && exponent > 33)
+                || (mantissa > 0xffff && exponent > 32));
+        if negative || overflow {
+            return None;
+        }
+
+        let mantissa = Self([u64::from(mantissa), 0, 0, 0]);
+        Some(if exponent <= 3 {
+            mantissa.shr(8 * (3 - exponent))
+        } else {
+            mantissa.shl(8 * (exponent - 3))
+        })
+    }
+
+    /// Encodes the target in its compact `nBits` representation, dropping the low order bits.
+    pub fn to_compact_lossy(&self) -> u32 {
+        let mut size = self.bits().div_ceil(8);
+        let mut compact = if size <= 3 {
+            (self.0[0] << (8 * (3 - size))) as u32
+        } else {
+            self.shr(8 * (size - 3)).0[0] as u32
+        };
+        // The mantissa is signed, so move it down a byte if its sign bit would be set.
+        if compact & 0x0080_0000 != 0 {
+            compact >>= 8;
+            size += 1;
+        }
+        compact | (size << 24)
+    }
+
+    /// Reads a target from 32 little endian bytes, the order in which block hashes are computed.
+    pub fn from_le_bytes(bytes: [u8; 32]) -> Self {
+        let mut limbs = [0u64; 4];
+        for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks_exact(8)) {
+            *limb = u64::from_le_bytes(chunk.try_into().expect("8 byte chunk"));
+        }
+        Self(limbs)
+    }
+
+    /// Writes the target as 32 little endian bytes.
+    pub fn to_le_bytes(&self) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        for (chunk, limb) in bytes.chunks_exact_mut(8).zip(self.0) {
+            chunk.copy_from_slice(&limb.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Multiplies the target, returning [`None`] on overflow.
+    pub fn checked_mul(&self, rhs: u64) -> Option<Self> {
+        let mut result = [0u64; 4];
+        let mut carry = 0u128;
+        for (r, limb) in result.iter_mut().zip(self.0) {
+            let product = u128::from(limb) * u128::from(rhs) + carry;
+            *r = product as u64;
+            carry = product >> 64;
+        }
+        (carry == 0).then_some(Self(result))
+    }
+
+    /// Divides the target, rounding down.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rhs` is zero.
+    pub fn div(&self, rhs: u64) -> Self {
+        assert!(rhs != 0, "division by zero");
+        let mut result = [0u64; 4];
+        let mut remainder = 0u128;
+        for i in (0..4).rev() {
+            let dividend = (remainder << 64) | u128::from(self.0[i]);
+            result[i] = (dividend / u128::from(rhs)) as u64;
+            remainder = dividend % u128::from(rhs);
+        }
+        Self(result)
+    }
+
+    /// Number of significant bits.
+    fn bits(&self) -> u32 {
+        (0..4)
+            .rev()
+            .find(|&i| self.0[i] != 0)
+            .map_or(0, |i| 64 * i as u32 + 64 - self.0[i].leading_zeros())
+    }
+
+    fn shl(&self, shift: u32) -> Self {
+        let (limbs, bits) = ((shift / 64) as usize, shift % 64);
+        let mut result = [0u64; 4];
+        for i in (limbs..4).rev() {
+            result[i] = self.0[i - limbs] << bits;
+            if bits > 0 && i > limbs {
+                result[i] |= self.0[i - limbs - 1] >> (64 - bits);
+            }
+        }
+        Self(result)
+    }
+
+    fn shr(&self, shift: u32) -> Self {
+        let (limbs, bits) = ((shift / 64) as usize, shift % 64);
+        let mut result = [0u64; 4];
+        for (i, limb) in result
+            .iter_mut()
+            .enumerate()
+            .take(4usize.saturating_sub(limbs))
+        {
+            *limb = self.0[i + limbs] >> bits;
+            if bits > 0 && i + limbs + 1 < 4 {
+                *limb |= self.0[i + limbs + 1] << (64 - bits);
+            }
+        }
+        Self(result)
+    }
+}
+
+impl Ord for Target {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.iter().rev().cmp(other.0.iter().rev())
+    }
+}
+
+impl PartialOrd for Target {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compact_against_rust_bitcoin() {
+        use bitcoin::pow::{CompactTarget, Target as RustBitcoinTarget};
+
+        for bits in [
+            0x1d00ffff, 0x1b0404cb, 0x17034219, 0x1e0377ae, 0x207fffff, 0x03123456,
+        ] {
+            let target = Target::from_compact(bits).unwrap();
+            let expected = RustBitcoinTarget::from_compact(CompactTarget::from_consensus(bits));
+
+            assert_eq!(target.to_le_bytes(), expected.to_le_bytes());
+            assert_eq!(
+                target.to_compact_lossy(),
+                expected.to_compact_lossy().to_consensus()
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_compact_rejects_negative_and_overflow() {
+        assert_eq!(Target::from_compact(0x04923456), None);
+        assert_eq!(Target::from_compact(0xff123456), None);
+        assert_eq!(Target::from_compact(0x01003456), Some(Target::ZERO));
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let target = Target::from_compact(0x1d00ffff).unwrap();
+
+        assert_eq!(target.checked_mul(4).unwrap().div(4), target);
+        assert!(target.checked_mul(4).unwrap() > target);
+        assert_eq!(Target([0, 0, 0, u64::MAX]).checked_mul(2), None);
+    }
+}
@@ -1,3 +1,20 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
+    JsonSchema,
+)]
+#[serde(crate = "near_sdk::serde")]
 pub enum TransactionType {
     /// Pay to public key hash
     P2PKH,
@@ -7,4 +24,6 @@ pub enum TransactionType {
     P2WPKH,
     /// Pay to witness script hash
     P2WSH,
+    /// Pay to taproot, spent through the key path
+    P2TR,
 }
@@ -31,6 +31,19 @@ impl Hash {
         let bytes = hex::decode(hex)?;
         Ok(Self(bytes.try_into().expect("Invalid length")))
     }
+
+    /// Creates a hash from bytes in internal order, as output by sha256d and serialized on the wire.
+    pub fn from_internal_bytes(mut bytes: [u8; 32]) -> Self {
+        bytes.reverse();
+        Self(bytes)
+    }
+
+    /// Returns the bytes in internal order, as output by sha256d and serialized on the wire.
+    pub fn to_internal_bytes(&self) -> [u8; 32] {
+        let mut bytes = self.0;
+        bytes.reverse();
+        bytes
+    }
 }
 
 impl Hash {
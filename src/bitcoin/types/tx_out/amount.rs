@@ -1,6 +1,8 @@
 use std::{
+    fmt,
     io::{BufRead, Write},
     ops,
+    str::FromStr,
 };
 
 use borsh::{BorshDeserialize, BorshSerialize};
@@ -81,6 +83,57 @@ impl Amount {
     pub fn checked_sub(self, rhs: Self) -> Option<Self> {
         self.0.checked_sub(rhs.0).map(Amount)
     }
+
+    /// Parses a decimal string as an amount in the given denomination.
+    ///
+    /// Fails if the string has more decimal places than the denomination allows, is negative
+    /// or exceeds [`Amount::MAX_MONEY`].
+    pub fn from_str_in(s: &str, denomination: Denomination) -> Result<Self, ParseAmountError> {
+        match parse_signed_to_satoshi(s, denomination)? {
+            (true, sat) if sat != 0 => Err(ParseAmountError::Negative),
+            (_, sat) if sat > Self::MAX_MONEY.0 => Err(ParseAmountError::TooBig),
+            (_, sat) => Ok(Self(sat)),
+        }
+    }
+
+    /// Parses an amount followed by a space and its denomination, such as `"0.015 BTC"`.
+    pub fn from_str_with_denomination(s: &str) -> Result<Self, ParseAmountError> {
+        let (amount, denomination) = split_amount_and_denomination(s)?;
+        Self::from_str_in(amount, denomination)
+    }
+
+    /// Formats the amount as a decimal string in the given denomination, without the unit.
+    pub fn to_string_in(self, denomination: Denomination) -> String {
+        format_satoshi_in(self.0, false, denomination)
+    }
+
+    /// Formats the amount in the given denomination, followed by the unit.
+    pub fn to_string_with_denomination(self, denomination: Denomination) -> String {
+        format!("{} {denomination}", self.to_string_in(denomination))
+    }
+
+    /// Converts the amount to a [`SignedAmount`].
+    ///
+    /// Returns [`None`] if the amount does not fit in a signed amount.
+    pub fn to_signed(self) -> Option<SignedAmount> {
+        i64::try_from(self.0).ok().map(SignedAmount)
+    }
+}
+
+impl fmt::Display for Amount {
+    /// Formats the amount in bitcoin, e.g. `0.015 BTC`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_string_with_denomination(Denomination::Bitcoin))
+    }
+}
+
+impl FromStr for Amount {
+    type Err = ParseAmountError;
+
+    /// Parses an amount with its denomination, see [`Amount::from_str_with_denomination`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_str_with_denomination(s)
+    }
 }
 
 impl Encodable for Amount {
@@ -112,6 +165,315 @@ impl ops::Sub for Amount {
     }
 }
 
+/// A signed amount, for example a fee or a balance delta.
+///
+/// Like [`Amount`] it counts satoshis, but it can be negative.
+#[derive(
+    Debug,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
+    JsonSchema,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SignedAmount(i64);
+
+impl SignedAmount {
+    /// The zero amount.
+    pub const ZERO: Self = Self(0);
+    /// Exactly one satoshi.
+    pub const ONE_SAT: Self = Self(1);
+    /// Exactly one bitcoin.
+    pub const ONE_BTC: Self = Self(100_000_000);
+    /// The maximum value allowed as an amount. Useful for sanity checking.
+    pub const MAX_MONEY: Self = Self(21_000_000 * 100_000_000);
+    /// The minimum value of an amount.
+    pub const MIN: Self = Self(i64::MIN);
+    /// The maximum value of an amount.
+    pub const MAX: Self = Self(i64::MAX);
+
+    /// Creates a [`SignedAmount`] with satoshi precision and the given number of satoshis.
+    pub const fn from_sat(satoshi: i64) -> Self {
+        Self(satoshi)
+    }
+
+    /// Gets the number of satoshis in this [`SignedAmount`].
+    pub const fn to_sat(self) -> i64 {
+        self.0
+    }
+
+    /// Returns whether the amount is strictly negative.
+    pub const fn is_negative(self) -> bool {
+        self.0 < 0
+    }
+
+    /// Returns the absolute value as an [`Amount`].
+    pub const fn unsigned_abs(self) -> Amount {
+        Amount(self.0.unsigned_abs())
+    }
+
+    /// Converts the amount to an [`Amount`].
+    ///
+    /// Returns [`None`] if the amount is negative.
+    pub fn to_unsigned(self) -> Option<Amount> {
+        u64::try_from(self.0).ok().map(Amount)
+    }
+
+    /// Checked addition.
+    ///
+    /// Returns [`None`] if overflow occurred.
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        self.0.checked_add(rhs.0).map(SignedAmount)
+    }
+
+    /// Checked subtraction.
+    ///
+    /// Returns [`None`] if overflow occurred.
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.0.checked_sub(rhs.0).map(SignedAmount)
+    }
+
+    /// Checked negation.
+    ///
+    /// Returns [`None`] if overflow occurred, that is for [`SignedAmount::MIN`].
+    pub fn checked_neg(self) -> Option<Self> {
+        self.0.checked_neg().map(SignedAmount)
+    }
+
+    /// Parses a decimal string, optionally prefixed with `-`, as an amount in the given
+    /// denomination.
+    ///
+    /// Fails if the string has more decimal places than the denomination allows or if its
+    /// absolute value exceeds [`SignedAmount::MAX_MONEY`].
+    pub fn from_str_in(s: &str, denomination: Denomination) -> Result<Self, ParseAmountError> {
+        let (negative, sat) = parse_signed_to_satoshi(s, denomination)?;
+        if sat > Self::MAX_MONEY.0.unsigned_abs() {
+            return Err(ParseAmountError::TooBig);
+        }
+
+        let sat = sat as i64;
+        Ok(Self(if negative { -sat } else { sat }))
+    }
+
+    /// Parses an amount followed by a space and its denomination, such as `"-1500 sat"`.
+    pub fn from_str_with_denomination(s: &str) -> Result<Self, ParseAmountError> {
+        let (amount, denomination) = split_amount_and_denomination(s)?;
+        Self::from_str_in(amount, denomination)
+    }
+
+    /// Formats the amount as a decimal string in the given denomination, without the unit.
+    pub fn to_string_in(self, denomination: Denomination) -> String {
+        format_satoshi_in(self.0.unsigned_abs(), self.is_negative(), denomination)
+    }
+
+    /// Formats the amount in the given denomination, followed by the unit.
+    pub fn to_string_with_denomination(self, denomination: Denomination) -> String {
+        format!("{} {denomination}", self.to_string_in(denomination))
+    }
+}
+
+impl fmt::Display for SignedAmount {
+    /// Formats the amount in bitcoin, e.g. `-0.015 BTC`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_string_with_denomination(Denomination::Bitcoin))
+    }
+}
+
+impl FromStr for SignedAmount {
+    type Err = ParseAmountError;
+
+    /// Parses an amount with its denomination, see [`SignedAmount::from_str_with_denomination`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_str_with_denomination(s)
+    }
+}
+
+impl ops::Add for SignedAmount {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.checked_add(rhs).expect("SignedAmount addition error")
+    }
+}
+
+impl ops::Sub for SignedAmount {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.checked_sub(rhs)
+            .expect("SignedAmount subtraction error")
+    }
+}
+
+impl ops::Neg for SignedAmount {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        self.checked_neg().expect("SignedAmount negation error")
+    }
+}
+
+/// A unit in which amounts can be parsed and formatted.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Denomination {
+    /// BTC
+    Bitcoin,
+    /// mBTC
+    MilliBitcoin,
+    /// uBTC, parsed from `uBTC` or `µBTC` and displayed as the ASCII `uBTC`
+    MicroBitcoin,
+    /// bits
+    Bit,
+    /// sat
+    Satoshi,
+}
+
+impl Denomination {
+    /// Number of decimal places of the denomination, i.e. how many satoshis make one unit
+    /// expressed as a power of ten.
+    pub const fn precision(self) -> usize {
+        match self {
+            Self::Bitcoin => 8,
+            Self::MilliBitcoin => 5,
+            Self::MicroBitcoin | Self::Bit => 2,
+            Self::Satoshi => 0,
+        }
+    }
+}
+
+/// Displays the denomination in ASCII, micro bitcoins as `uBTC`.
+impl fmt::Display for Denomination {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Bitcoin => "BTC",
+            Self::MilliBitcoin => "mBTC",
+            Self::MicroBitcoin => "uBTC",
+            Self::Bit => "bits",
+            Self::Satoshi => "sat",
+        })
+    }
+}
+
+impl FromStr for Denomination {
+    type Err = ParseAmountError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "BTC" | "btc" => Ok(Self::Bitcoin),
+            "mBTC" | "mbtc" => Ok(Self::MilliBitcoin),
+            // The micro sign (U+00B5) and the Greek small letter mu (U+03BC) look the same
+            "uBTC" | "ubtc" | "µBTC" | "µbtc" | "μBTC" | "μbtc" => Ok(Self::MicroBitcoin),
+            "bits" | "bit" => Ok(Self::Bit),
+            "sat" | "sats" | "satoshi" | "satoshis" => Ok(Self::Satoshi),
+            _ => Err(ParseAmountError::UnknownDenomination(s.to_string())),
+        }
+    }
+}
+
+/// Errors that can occur while parsing an amount.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseAmountError {
+    /// The amount is empty or contains no digits.
+    Empty,
+    /// The amount contains a character that is not a digit or a single decimal point.
+    InvalidCharacter(char),
+    /// The amount has more decimal places than its denomination allows.
+    TooPrecise,
+    /// The amount exceeds the maximum amount of money.
+    TooBig,
+    /// A negative amount was given where only positive amounts are allowed.
+    Negative,
+    /// The denomination is missing or not recognized.
+    UnknownDenomination(String),
+}
+
+impl fmt::Display for ParseAmountError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "Amount is empty"),
+            Self::InvalidCharacter(c) => write!(f, "Invalid character in amount: {c}"),
+            Self::TooPrecise => write!(f, "Amount has too many decimal places"),
+            Self::TooBig => write!(f, "Amount exceeds the maximum amount of money"),
+            Self::Negative => write!(f, "Amount must not be negative"),
+            Self::UnknownDenomination(d) => write!(f, "Unknown denomination: {d}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseAmountError {}
+
+/// Parses a decimal string into its sign and absolute number of satoshis.
+fn parse_signed_to_satoshi(
+    s: &str,
+    denomination: Denomination,
+) -> Result<(bool, u64), ParseAmountError> {
+    let (negative, s) = s.strip_prefix('-').map_or((false, s), |rest| (true, rest));
+    let (integer, fraction) = s.split_once('.').unwrap_or((s, ""));
+    if integer.is_empty() && fraction.is_empty() {
+        return Err(ParseAmountError::Empty);
+    }
+    if let Some(c) = integer
+        .chars()
+        .chain(fraction.chars())
+        .find(|c| !c.is_ascii_digit())
+    {
+        return Err(ParseAmountError::InvalidCharacter(c));
+    }
+
+    // Extra decimal places are only accepted when they are trailing zeros.
+    let precision = denomination.precision();
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.len() > precision {
+        return Err(ParseAmountError::TooPrecise);
+    }
+
+    let mut sat: u64 = 0;
+    let padding = "0".repeat(precision - fraction.len());
+    for c in integer
+        .chars()
+        .chain(fraction.chars())
+        .chain(padding.chars())
+    {
+        sat = sat
+            .checked_mul(10)
+            .and_then(|sat| sat.checked_add(u64::from(c as u8 - b'0')))
+            .ok_or(ParseAmountError::TooBig)?;
+    }
+
+    Ok((negative, sat))
+}
+
+/// Splits `"<amount> <denomination>"` into its two parts.
+fn split_amount_and_denomination(s: &str) -> Result<(&str, Denomination), ParseAmountError> {
+    let (amount, denomination) = s
+        .split_once(' ')
+        .ok_or_else(|| ParseAmountError::UnknownDenomination(String::new()))?;
+
+    Ok((amount, denomination.parse()?))
+}
+
+/// Formats a number of satoshis in the given denomination, without trailing zeros.
+fn format_satoshi_in(sat: u64, negative: bool, denomination: Denomination) -> String {
+    let sign = if negative { "-" } else { "" };
+    let precision = denomination.precision();
+    let unit = 10u64.pow(precision as u32);
+    let (integer, fraction) = (sat / unit, sat % unit);
+
+    if fraction == 0 {
+        format!("{sign}{integer}")
+    } else {
+        let fraction = format!("{fraction:0precision$}");
+        format!("{sign}{integer}.{}", fraction.trim_end_matches('0'))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -126,4 +488,136 @@ mod tests {
         let decoded_amount = Amount::decode_from_finite_reader(&mut buf.as_slice()).unwrap();
         assert_eq!(decoded_amount, amount);
     }
+
+    #[test]
+    fn test_from_str_in_against_rust_bitcoin() {
+        use bitcoin::amount::Denomination as RustBitcoinDenomination;
+
+        let cases = [
+            (
+                "0.015",
+                Denomination::Bitcoin,
+                RustBitcoinDenomination::Bitcoin,
+            ),
+            (
+                "21000000",
+                Denomination::Bitcoin,
+                RustBitcoinDenomination::Bitcoin,
+            ),
+            (
+                ".5",
+                Denomination::MilliBitcoin,
+                RustBitcoinDenomination::MilliBitcoin,
+            ),
+            (
+                "12.34",
+                Denomination::MicroBitcoin,
+                RustBitcoinDenomination::MicroBitcoin,
+            ),
+            ("1500", Denomination::Bit, RustBitcoinDenomination::Bit),
+            (
+                "1500.00",
+                Denomination::Satoshi,
+                RustBitcoinDenomination::Satoshi,
+            ),
+        ];
+
+        for (s, denomination, rust_bitcoin_denomination) in cases {
+            let amount = Amount::from_str_in(s, denomination).unwrap();
+            let expected = bitcoin::Amount::from_str_in(s, rust_bitcoin_denomination).unwrap();
+
+            assert_eq!(amount.to_sat(), expected.to_sat());
+            assert_eq!(
+                amount.to_string_in(denomination),
+                expected.to_string_in(rust_bitcoin_denomination)
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_str_in_rejects_invalid_amounts() {
+        assert_eq!(
+            Amount::from_str_in("0.123456789", Denomination::Bitcoin),
+            Err(ParseAmountError::TooPrecise)
+        );
+        assert_eq!(
+            Amount::from_str_in("1.5", Denomination::Satoshi),
+            Err(ParseAmountError::TooPrecise)
+        );
+        assert_eq!(
+            Amount::from_str_in("21000000.00000001", Denomination::Bitcoin),
+            Err(ParseAmountError::TooBig)
+        );
+        assert_eq!(
+            Amount::from_str_in("99999999999999999999", Denomination::Satoshi),
+            Err(ParseAmountError::TooBig)
+        );
+        assert_eq!(
+            Amount::from_str_in("-1", Denomination::Satoshi),
+            Err(ParseAmountError::Negative)
+        );
+        assert_eq!(
+            Amount::from_str_in("1.2.3", Denomination::Bitcoin),
+            Err(ParseAmountError::InvalidCharacter('.'))
+        );
+        assert_eq!(
+            Amount::from_str_in(".", Denomination::Bitcoin),
+            Err(ParseAmountError::Empty)
+        );
+    }
+
+    #[test]
+    fn test_amount_with_denomination() {
+        assert_eq!(
+            "0.015 BTC".parse::<Amount>().unwrap(),
+            Amount::from_sat(1_500_000)
+        );
+        assert_eq!(
+            "1500 bits".parse::<Amount>().unwrap(),
+            Amount::from_sat(150_000)
+        );
+        assert_eq!("2.5 µBTC".parse::<Amount>().unwrap(), Amount::from_sat(250));
+        assert_eq!("2.5 uBTC".parse::<Amount>().unwrap(), Amount::from_sat(250));
+        for micro in ["uBTC", "ubtc", "\u{b5}BTC", "\u{3bc}BTC"] {
+            assert_eq!(
+                micro.parse::<Denomination>().unwrap(),
+                Denomination::MicroBitcoin,
+                "{micro}"
+            );
+        }
+        assert_eq!(Denomination::MicroBitcoin.to_string(), "uBTC");
+        assert!("1 doge".parse::<Amount>().is_err());
+
+        assert_eq!(Amount::from_sat(1_500_000).to_string(), "0.015 BTC");
+        assert_eq!(Amount::ONE_BTC.to_string(), "1 BTC");
+        assert_eq!(
+            Amount::from_sat(150_000).to_string_with_denomination(Denomination::Bit),
+            "1500 bits"
+        );
+    }
+
+    #[test]
+    fn test_signed_amount() {
+        let fee = SignedAmount::from_str_in("-0.00001", Denomination::Bitcoin).unwrap();
+
+        assert_eq!(fee.to_sat(), -1000);
+        assert!(fee.is_negative());
+        assert_eq!(fee.unsigned_abs(), Amount::from_sat(1000));
+        assert_eq!(fee.to_unsigned(), None);
+        assert_eq!((-fee).to_unsigned(), Some(Amount::from_sat(1000)));
+        assert_eq!(fee.checked_neg(), Some(SignedAmount::from_sat(1000)));
+        assert_eq!(SignedAmount::MIN.checked_neg(), None);
+        assert_eq!(-SignedAmount::MAX, SignedAmount::from_sat(-i64::MAX));
+        assert_eq!(fee.to_string(), "-0.00001 BTC");
+        assert_eq!("-1000 sat".parse::<SignedAmount>().unwrap(), fee);
+        assert_eq!(
+            SignedAmount::from_str_in("-21000001", Denomination::Bitcoin),
+            Err(ParseAmountError::TooBig)
+        );
+        assert_eq!(
+            fee.to_string_in(Denomination::Satoshi),
+            bitcoin::SignedAmount::from_sat(-1000)
+                .to_string_in(bitcoin::amount::Denomination::Satoshi)
+        );
+    }
 }
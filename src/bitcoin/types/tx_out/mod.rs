@@ -3,5 +3,5 @@
 pub mod amount;
 mod tx_out;
 
-pub use self::amount::Amount;
+pub use self::amount::{Amount, Denomination, ParseAmountError, SignedAmount};
 pub use self::tx_out::TxOut;
@@ -0,0 +1,128 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+
+use crate::bitcoin::error::BitcoinError;
+
+use super::{
+    script_buf::ScriptBuf,
+    transaction_type::TransactionType,
+    tx_in::{OutPoint, Sequence, TxIn, Witness},
+    tx_out::{Amount, TxOut},
+};
+
+/// An unspent transaction output, together with what is needed to sign its spend.
+///
+/// Signature hashes, fees and verification all need the value and script of the outputs
+/// being spent. Keeping them next to the outpoint avoids passing them as loose arguments,
+/// which are easily mixed up between inputs.
+///
+/// ###### Example:
+///
+/// ```rust
+/// let utxo = Utxo::new(
+///     OutPoint::new(txid, 0),
+///     TxOut {
+///         value: Amount::from_sat(100_000),
+///         script_pubkey: ScriptBuf::new_p2wpkh(&pubkey_hash),
+///     },
+///     TransactionType::P2WPKH,
+/// );
+///
+/// let sighash = tx.ecdsa_signature_hash(0, &[utxo], EcdsaSighashType::All).unwrap();
+/// ```
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
+    JsonSchema,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Utxo {
+    /// The output being spent.
+    pub outpoint: OutPoint,
+    /// The value and script of the output being spent.
+    pub txout: TxOut,
+    /// How the output is spent, which determines the signature hash.
+    pub spend_type: TransactionType,
+    /// The redeem script of P2SH outputs, including P2SH wrapped SegWit outputs.
+    pub redeem_script: Option<ScriptBuf>,
+    /// The witness script of P2WSH outputs.
+    pub witness_script: Option<ScriptBuf>,
+}
+
+impl Utxo {
+    pub const fn new(outpoint: OutPoint, txout: TxOut, spend_type: TransactionType) -> Self {
+        Self {
+            outpoint,
+            txout,
+            spend_type,
+            redeem_script: None,
+            witness_script: None,
+        }
+    }
+
+    pub fn redeem_script(mut self, redeem_script: ScriptBuf) -> Self {
+        self.redeem_script = Some(redeem_script);
+        self
+    }
+
+    pub fn witness_script(mut self, witness_script: ScriptBuf) -> Self {
+        self.witness_script = Some(witness_script);
+        self
+    }
+
+    /// The value of the output being spent.
+    pub const fn value(&self) -> Amount {
+        self.txout.value
+    }
+
+    /// An input spending this output, with an empty script sig and witness.
+    pub fn to_txin(&self, sequence: Sequence) -> TxIn {
+        TxIn {
+            previous_output: self.outpoint,
+            script_sig: ScriptBuf::default(),
+            sequence,
+            witness: Witness::default(),
+        }
+    }
+
+    /// The script code signed over by ECDSA signatures.
+    ///
+    /// P2WPKH spends use the P2PKH script of the key hash, read from the output script or,
+    /// for P2SH wrapped outputs, from the redeem script. Taproot spends do not commit to a
+    /// script code, so [`BitcoinError::UnsupportedScript`] is returned for them.
+    pub fn script_code(&self) -> Result<ScriptBuf, BitcoinError> {
+        match self.spend_type {
+            TransactionType::P2PKH => Ok(self.txout.script_pubkey.clone()),
+            TransactionType::P2SH => self
+                .redeem_script
+                .clone()
+                .ok_or(BitcoinError::MissingField("redeem script")),
+            TransactionType::P2WPKH => {
+                let program = if self.txout.script_pubkey.is_p2sh() {
+                    self.redeem_script
+                        .as_ref()
+                        .ok_or(BitcoinError::MissingField("redeem script"))?
+                } else {
+                    &self.txout.script_pubkey
+                };
+                if !program.is_p2wpkh() {
+                    return Err(BitcoinError::UnsupportedScript);
+                }
+                let key_hash = program.as_bytes()[2..].try_into().expect("20 bytes");
+                Ok(ScriptBuf::new_p2pkh(key_hash))
+            }
+            TransactionType::P2WSH => self
+                .witness_script
+                .clone()
+                .ok_or(BitcoinError::MissingField("witness script")),
+            TransactionType::P2TR => Err(BitcoinError::UnsupportedScript),
+        }
+    }
+}
@@ -12,16 +12,24 @@ use crate::bitcoin::encoding::{Decodable, Encodable};
 
 /// The transaction version.
 ///
-/// Currently, as specified by [BIP-68], only version 1 and 2 are considered standard.
+/// Version 1 and 2, as specified by [BIP-68], are standard. Version 3 opts a transaction into
+/// the topologically restricted until confirmation (TRUC) policy of [BIP-431]. Version 0 is
+/// only used by the virtual transactions of [BIP-322] message signatures.
 ///
 /// [BIP-68]: https://github.com/bitcoin/bips/blob/master/bip-0068.mediawiki
+/// [BIP-322]: https://github.com/bitcoin/bips/blob/master/bip-0322.mediawiki
+/// [BIP-431]: https://github.com/bitcoin/bips/blob/master/bip-0431.mediawiki
 #[derive(Debug, Copy, PartialEq, Eq, Clone, BorshSerialize, BorshDeserialize, JsonSchema)]
 #[borsh(use_discriminant = true)]
 pub enum Version {
+    /// The version of BIP-322 virtual transactions, never relayed
+    Zero = 0,
     /// The original Bitcoin transaction version (pre-BIP-68)
     One = 1,
     /// The second Bitcoin transaction version (post-BIP-68)
     Two = 2,
+    /// A TRUC transaction (BIP-431)
+    Three = 3,
 }
 
 impl Version {
@@ -51,8 +59,10 @@ impl Decodable for Version {
         let int = i32::from_le_bytes(buf);
 
         match int {
+            0 => Ok(Self::Zero),
             1 => Ok(Self::One),
             2 => Ok(Self::Two),
+            3 => Ok(Self::Three),
             _ => Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidData,
                 "Invalid version number",
@@ -67,8 +77,10 @@ impl Serialize for Version {
         S: serde::Serializer,
     {
         let version_number = match self {
+            Self::Zero => 0,
             Self::One => 1,
             Self::Two => 2,
+            Self::Three => 3,
         };
         serializer.serialize_i32(version_number)
     }
@@ -98,8 +110,10 @@ impl<'de> Deserialize<'de> for Version {
                     .map_err(serde::de::Error::custom)?;
 
                 match value_parsed {
+                    0 => Ok(Version::Zero),
                     1 => Ok(Version::One),
                     2 => Ok(Version::Two),
+                    3 => Ok(Version::Three),
                     _ => Err(serde::de::Error::custom("Invalid version number")),
                 }
             }
@@ -109,8 +123,10 @@ impl<'de> Deserialize<'de> for Version {
                 E: serde::de::Error,
             {
                 match value {
+                    0 => Ok(Version::Zero),
                     1 => Ok(Version::One),
                     2 => Ok(Version::Two),
+                    3 => Ok(Version::Three),
                     _ => Err(serde::de::Error::custom("Invalid version number")),
                 }
             }
@@ -120,8 +136,10 @@ impl<'de> Deserialize<'de> for Version {
                 E: serde::de::Error,
             {
                 match value {
+                    0 => Ok(Version::Zero),
                     1 => Ok(Version::One),
                     2 => Ok(Version::Two),
+                    3 => Ok(Version::Three),
                     _ => Err(serde::de::Error::custom("Invalid version number")),
                 }
             }
@@ -134,8 +152,10 @@ impl<'de> Deserialize<'de> for Version {
 impl fmt::Display for Version {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let version_number = match self {
+            Self::Zero => "0",
             Self::One => "1",
             Self::Two => "2",
+            Self::Three => "3",
         };
         write!(f, "{version_number}")
     }
@@ -233,4 +253,19 @@ mod tests {
         let version: Version = serde_json::from_str(json).unwrap();
         assert_eq!(version, Version::Two);
     }
+
+    #[test]
+    fn test_version_three() {
+        let mut buf = Vec::new();
+        Version::Three.encode(&mut buf).unwrap();
+        assert_eq!(buf, vec![3, 0, 0, 0]);
+        assert_eq!(
+            Version::decode(&mut Cursor::new(buf)).unwrap(),
+            Version::Three
+        );
+
+        let version: Version = serde_json::from_str(r#""3""#).unwrap();
+        assert_eq!(version, Version::Three);
+        assert_eq!(serde_json::to_string(&Version::Three).unwrap(), "3");
+    }
 }
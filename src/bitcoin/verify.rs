@@ -0,0 +1,554 @@
+//! Local verification of signed Bitcoin transactions.
+//!
+//! Supports the standard single key spends: P2PKH, P2WPKH and P2TR key path. Signatures are
+//! checked with a pure Rust secp256k1 implementation, so verification also runs inside a contract.
+use k256::ecdsa::{signature::hazmat::PrehashVerifier, Signature, VerifyingKey};
+use k256::schnorr::{Signature as SchnorrSignature, VerifyingKey as SchnorrVerifyingKey};
+
+use super::{
+    bitcoin_transaction::BitcoinTransaction,
+    error::BitcoinError,
+    hashes::hash160,
+    types::{
+        opcodes::{OP_0, OP_PUSHDATA1, OP_PUSHDATA2, OP_PUSHDATA4},
+        EcdsaSighashType, ScriptBuf, TapSighashType, TxOut, Utxo,
+    },
+};
+
+/// First byte of the annex, the optional last witness element of Taproot spends (BIP-341).
+const TAPROOT_ANNEX_PREFIX: u8 = 0x50;
+
+impl BitcoinTransaction {
+    /// Verifies the script sig or witness of every input against the output it spends.
+    ///
+    /// `spent_outputs` must contain the outputs spent by the inputs, in the same order.
+    pub fn verify(&self, spent_outputs: &[TxOut]) -> Result<(), BitcoinError> {
+        (0..self.input.len()).try_for_each(|i| self.verify_input(i, spent_outputs))
+    }
+
+    /// Verifies every input against the UTXO it spends, looked up by outpoint in `utxos`.
+    pub fn verify_utxos(&self, utxos: &[Utxo]) -> Result<(), BitcoinError> {
+        self.verify(&self.spent_outputs(utxos)?)
+    }
+
+    /// Verifies the script sig or witness of a single input against the output it spends.
+    ///
+    /// `spent_outputs` must contain the outputs spent by all inputs of the transaction, since
+    /// Taproot signatures commit to every one of them.
+    pub fn verify_input(
+        &self,
+        input_index: usize,
+        spent_outputs: &[TxOut],
+    ) -> Result<(), BitcoinError> {
+        if spent_outputs.len() != self.input.len() {
+            return Err(BitcoinError::SpentOutputsMismatch {
+                expected: self.input.len(),
+                actual: spent_outputs.len(),
+            });
+        }
+        let input = self
+            .input
+            .get(input_index)
+            .ok_or(BitcoinError::InputIndexOutOfRange {
+                index: input_index,
+                len: self.input.len(),
+            })?;
+        let spent_output = &spent_outputs[input_index];
+        let script_pubkey = &spent_output.script_pubkey;
+        let invalid = |reason| BitcoinError::InvalidSpend {
+            index: input_index,
+            reason,
+        };
+
+        if script_pubkey.is_p2pkh() {
+            if !input.witness.is_empty() {
+                return Err(invalid("unexpected witness"));
+            }
+            let pushes = parse_pushes(input.script_sig.as_bytes())
+                .ok_or_else(|| invalid("script sig is not push only"))?;
+            let [signature, pubkey] = pushes[..] else {
+                return Err(invalid("expected a signature and a public key"));
+            };
+            if hash160(pubkey) != script_pubkey.as_bytes()[3..23] {
+                return Err(invalid("public key does not match the public key hash"));
+            }
+            let (signature, sighash_type) = parse_ecdsa_signature(signature, input_index)?;
+            let sighash = self.legacy_signature_hash(input_index, script_pubkey, sighash_type)?;
+
+            verify_ecdsa(&sighash, &signature, pubkey, input_index)
+        } else if script_pubkey.is_p2wpkh() {
+            if !input.script_sig.is_empty() {
+                return Err(invalid("script sig must be empty for native SegWit spends"));
+            }
+            let witness = input.witness.to_vec();
+            let [signature, pubkey] = &witness[..] else {
+                return Err(invalid("expected a signature and a public key"));
+            };
+            if pubkey.len() != 33 {
+                return Err(invalid("public key must be compressed"));
+            }
+            let pubkey_hash = hash160(pubkey);
+            if pubkey_hash != script_pubkey.as_bytes()[2..] {
+                return Err(invalid("public key does not match the public key hash"));
+            }
+            let (signature, sighash_type) = parse_ecdsa_signature(signature, input_index)?;
+            let script_code = ScriptBuf::new_p2pkh(&pubkey_hash);
+            let sighash = self.segwit_v0_signature_hash(
+                input_index,
+                &script_code,
+                spent_output.value,
+                sighash_type,
+            )?;
+
+            verify_ecdsa(&sighash, &signature, pubkey, input_index)
+        } else if script_pubkey.is_p2tr() {
+            if !input.script_sig.is_empty() {
+                return Err(invalid("script sig must be empty for native SegWit spends"));
+            }
+            let mut witness = input.witness.to_vec();
+            let annex = match witness.last() {
+                Some(last) if witness.len() > 1 && last.first() == Some(&TAPROOT_ANNEX_PREFIX) => {
+                    witness.pop()
+                }
+                _ => None,
+            };
+            let [signature] = &witness[..] else {
+                return Err(invalid("only key path spends are supported"));
+            };
+            let (signature, sighash_type) = match signature.len() {
+                64 => (&signature[..], TapSighashType::Default),
+                65 => match signature[64] {
+                    0x01 => (&signature[..64], TapSighashType::All),
+                    sighash_type => {
+                        return Err(BitcoinError::UnsupportedSighashType(sighash_type.into()))
+                    }
+                },
+                _ => return Err(invalid("Schnorr signature must be 64 or 65 bytes")),
+            };
+            let sighash = self.taproot_key_spend_signature_hash(
+                input_index,
+                spent_outputs,
+                annex.as_deref(),
+                sighash_type,
+            )?;
+
+            let output_key = SchnorrVerifyingKey::from_bytes(&script_pubkey.as_bytes()[2..])
+                .map_err(|_| invalid("invalid Taproot output key"))?;
+            let signature = SchnorrSignature::try_from(signature)
+                .map_err(|_| invalid("invalid Schnorr signature encoding"))?;
+
+            output_key
+                .verify_raw(&sighash, &signature)
+                .map_err(|_| BitcoinError::InvalidSignature { index: input_index })
+        } else {
+            Err(BitcoinError::UnsupportedScript)
+        }
+    }
+}
+
+/// Splits a DER signature with its trailing sighash type byte.
+fn parse_ecdsa_signature(
+    signature: &[u8],
+    input_index: usize,
+) -> Result<(Signature, EcdsaSighashType), BitcoinError> {
+    let (sighash_type, der) = signature.split_last().ok_or(BitcoinError::InvalidSpend {
+        index: input_index,
+        reason: "empty signature",
+    })?;
+    let sighash_type = match sighash_type {
+        0x01 => EcdsaSighashType::All,
+        other => return Err(BitcoinError::UnsupportedSighashType((*other).into())),
+    };
+    let signature = Signature::from_der(der).map_err(|_| BitcoinError::InvalidSpend {
+        index: input_index,
+        reason: "invalid DER signature encoding",
+    })?;
+
+    Ok((signature, sighash_type))
+}
+
+/// Verifies an ECDSA signature over a signature hash.
+///
+/// Signatures with a high S value are rejected, as Bitcoin Core does not relay them (BIP-146).
+fn verify_ecdsa(
+    sighash: &[u8; 32],
+    signature: &Signature,
+    pubkey: &[u8],
+    input_index: usize,
+) -> Result<(), BitcoinError> {
+    if signature.normalize_s().is_some() {
+        return Err(BitcoinError::InvalidSpend {
+            index: input_index,
+            reason: "signature S value is not low (BIP-146)",
+        });
+    }
+    let verifying_key =
+        VerifyingKey::from_sec1_bytes(pubkey).map_err(|_| BitcoinError::InvalidSpend {
+            index: input_index,
+            reason: "invalid public key",
+        })?;
+
+    verifying_key
+        .verify_prehash(sighash, signature)
+        .map_err(|_| BitcoinError::InvalidSignature { index: input_index })
+}
+
+/// Splits a push only script into the data it pushes, or `None` if it contains other opcodes.
+fn parse_pushes(script: &[u8]) -> Option<Vec<&[u8]>> {
+    let mut pushes = Vec::new();
+    let mut rest = script;
+
+    while let Some((&opcode, tail)) = rest.split_first() {
+        let (len, tail) = match opcode {
+            OP_0 => (0, tail),
+            0x01..=0x4b => (opcode as usize, tail),
+            OP_PUSHDATA1 => (*tail.first()? as usize, tail.get(1..)?),
+            OP_PUSHDATA2 => (
+                u16::from_le_bytes(tail.get(..2)?.try_into().ok()?) as usize,
+                tail.get(2..)?,
+            ),
+            OP_PUSHDATA4 => (
+                u32::from_le_bytes(tail.get(..4)?.try_into().ok()?) as usize,
+                tail.get(4..)?,
+            ),
+            _ => return None,
+        };
+        pushes.push(tail.get(..len)?);
+        rest = tail.get(len..)?;
+    }
+
+    Some(pushes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitcoin::types::{
+        Amount, Hash, LockTime, OutPoint, Sequence, TransactionType, TxIn, Txid, Version, Witness,
+    };
+
+    use bitcoin::hashes::Hash as _;
+    use bitcoin::key::{Keypair, TapTweak};
+    use bitcoin::secp256k1::{Message, Secp256k1, SecretKey};
+    use bitcoin::sighash::{Prevouts, SighashCache};
+
+    struct SignedTransaction {
+        tx: bitcoin::Transaction,
+        spent_outputs: Vec<bitcoin::TxOut>,
+    }
+
+    /// Spends a P2PKH, a P2WPKH and a P2TR output with rust-bitcoin.
+    fn rust_bitcoin_signed_transaction() -> SignedTransaction {
+        let secp = Secp256k1::new();
+        let secret_key = SecretKey::from_slice(&[0x2a; 32]).unwrap();
+        let public_key = bitcoin::PublicKey::new(secret_key.public_key(&secp));
+        let compressed = bitcoin::CompressedPublicKey(public_key.inner);
+        let keypair = Keypair::from_secret_key(&secp, &secret_key);
+        let (internal_key, _) = keypair.x_only_public_key();
+
+        let spent_outputs = vec![
+            bitcoin::TxOut {
+                value: bitcoin::Amount::from_sat(10_000),
+                script_pubkey: bitcoin::ScriptBuf::new_p2pkh(&public_key.pubkey_hash()),
+            },
+            bitcoin::TxOut {
+                value: bitcoin::Amount::from_sat(20_000),
+                script_pubkey: bitcoin::ScriptBuf::new_p2wpkh(&compressed.wpubkey_hash()),
+            },
+            bitcoin::TxOut {
+                value: bitcoin::Amount::from_sat(30_000),
+                script_pubkey: bitcoin::ScriptBuf::new_p2tr(&secp, internal_key, None),
+            },
+        ];
+
+        let mut tx = bitcoin::Transaction {
+            version: bitcoin::transaction::Version(2),
+            lock_time: bitcoin::absolute::LockTime::ZERO,
+            input: (0..3)
+                .map(|vout| bitcoin::TxIn {
+                    previous_output: bitcoin::OutPoint {
+                        txid: bitcoin::Txid::from_byte_array([0x11; 32]),
+                        vout,
+                    },
+                    script_sig: bitcoin::ScriptBuf::default(),
+                    sequence: bitcoin::Sequence::ENABLE_RBF_NO_LOCKTIME,
+                    witness: bitcoin::Witness::default(),
+                })
+                .collect(),
+            output: vec![bitcoin::TxOut {
+                value: bitcoin::Amount::from_sat(55_000),
+                script_pubkey: spent_outputs[1].script_pubkey.clone(),
+            }],
+        };
+
+        let mut cache = SighashCache::new(&tx);
+        let legacy_sighash = cache
+            .legacy_signature_hash(
+                0,
+                &spent_outputs[0].script_pubkey,
+                bitcoin::EcdsaSighashType::All.to_u32(),
+            )
+            .unwrap();
+        let segwit_sighash = cache
+            .p2wpkh_signature_hash(
+                1,
+                &spent_outputs[1].script_pubkey,
+                spent_outputs[1].value,
+                bitcoin::EcdsaSighashType::All,
+            )
+            .unwrap();
+        let taproot_sighash = SighashCache::new(&tx)
+            .taproot_key_spend_signature_hash(
+                2,
+                &Prevouts::All(&spent_outputs),
+                bitcoin::TapSighashType::Default,
+            )
+            .unwrap();
+
+        let ecdsa_signature = |sighash: [u8; 32]| bitcoin::ecdsa::Signature {
+            signature: secp.sign_ecdsa(&Message::from_digest(sighash), &secret_key),
+            sighash_type: bitcoin::EcdsaSighashType::All,
+        };
+
+        let legacy_signature = ecdsa_signature(legacy_sighash.to_byte_array());
+        tx.input[0].script_sig = bitcoin::ScriptBuf::builder()
+            .push_slice(legacy_signature.serialize())
+            .push_key(&public_key)
+            .into_script();
+
+        let segwit_signature = ecdsa_signature(segwit_sighash.to_byte_array());
+        tx.input[1].witness = bitcoin::Witness::p2wpkh(&segwit_signature, &public_key.inner);
+
+        let tweaked = keypair.tap_tweak(&secp, None).to_keypair();
+        let schnorr_signature = secp.sign_schnorr_no_aux_rand(
+            &Message::from_digest(taproot_sighash.to_byte_array()),
+            &tweaked,
+        );
+        tx.input[2].witness = bitcoin::Witness::from_slice(&[schnorr_signature.as_ref()]);
+
+        SignedTransaction { tx, spent_outputs }
+    }
+
+    fn to_omni_transaction(tx: &bitcoin::Transaction) -> BitcoinTransaction {
+        BitcoinTransaction {
+            version: Version::Two,
+            lock_time: LockTime::from_height(tx.lock_time.to_consensus_u32()).unwrap(),
+            input: tx
+                .input
+                .iter()
+                .map(|input| TxIn {
+                    previous_output: OutPoint {
+                        txid: Txid(
+                            Hash::from_hex(&input.previous_output.txid.to_string()).unwrap(),
+                        ),
+                        vout: input.previous_output.vout,
+                    },
+                    script_sig: ScriptBuf::from_bytes(input.script_sig.to_bytes()),
+                    sequence: Sequence(input.sequence.0),
+                    witness: Witness::from_slice(&input.witness.to_vec()),
+                })
+                .collect(),
+            output: tx.output.iter().map(to_omni_tx_out).collect(),
+        }
+    }
+
+    fn to_omni_tx_out(output: &bitcoin::TxOut) -> TxOut {
+        TxOut {
+            value: Amount::from_sat(output.value.to_sat()),
+            script_pubkey: ScriptBuf::from_bytes(output.script_pubkey.to_bytes()),
+        }
+    }
+
+    #[test]
+    fn test_verify_against_rust_bitcoin_signed_transaction() {
+        let SignedTransaction { tx, spent_outputs } = rust_bitcoin_signed_transaction();
+        let omni_tx = to_omni_transaction(&tx);
+        let omni_spent_outputs: Vec<TxOut> = spent_outputs.iter().map(to_omni_tx_out).collect();
+
+        assert_eq!(omni_tx.serialize(), bitcoin::consensus::serialize(&tx));
+        assert_eq!(omni_tx.verify(&omni_spent_outputs), Ok(()));
+    }
+
+    #[test]
+    fn test_signature_hashes_against_rust_bitcoin_with_witnesses_attached() {
+        let SignedTransaction { tx, spent_outputs } = rust_bitcoin_signed_transaction();
+        let omni_tx = to_omni_transaction(&tx);
+        let omni_spent_outputs: Vec<TxOut> = spent_outputs.iter().map(to_omni_tx_out).collect();
+        let mut cache = SighashCache::new(&tx);
+
+        let legacy = cache
+            .legacy_signature_hash(0, &spent_outputs[0].script_pubkey, 0x01)
+            .unwrap();
+        assert_eq!(
+            omni_tx
+                .legacy_signature_hash(
+                    0,
+                    &omni_spent_outputs[0].script_pubkey,
+                    EcdsaSighashType::All
+                )
+                .unwrap(),
+            legacy.to_byte_array()
+        );
+
+        let segwit = cache
+            .p2wpkh_signature_hash(
+                1,
+                &spent_outputs[1].script_pubkey,
+                spent_outputs[1].value,
+                bitcoin::EcdsaSighashType::All,
+            )
+            .unwrap();
+        let script_code = ScriptBuf::new_p2pkh(
+            omni_spent_outputs[1].script_pubkey.as_bytes()[2..]
+                .try_into()
+                .unwrap(),
+        );
+        assert_eq!(
+            omni_tx
+                .segwit_v0_signature_hash(
+                    1,
+                    &script_code,
+                    omni_spent_outputs[1].value,
+                    EcdsaSighashType::All
+                )
+                .unwrap(),
+            segwit.to_byte_array()
+        );
+
+        let annex = [TAPROOT_ANNEX_PREFIX, 0x01, 0x02];
+        let taproot = cache
+            .taproot_signature_hash(
+                2,
+                &Prevouts::All(&spent_outputs),
+                Some(bitcoin::sighash::Annex::new(&annex).unwrap()),
+                None,
+                bitcoin::TapSighashType::All,
+            )
+            .unwrap();
+        assert_eq!(
+            omni_tx
+                .taproot_key_spend_signature_hash(
+                    2,
+                    &omni_spent_outputs,
+                    Some(&annex),
+                    TapSighashType::All
+                )
+                .unwrap(),
+            taproot.to_byte_array()
+        );
+    }
+
+    #[test]
+    fn test_utxos_against_rust_bitcoin_signed_transaction() {
+        let SignedTransaction { tx, spent_outputs } = rust_bitcoin_signed_transaction();
+        let omni_tx = to_omni_transaction(&tx);
+        let spend_types = [
+            TransactionType::P2PKH,
+            TransactionType::P2WPKH,
+            TransactionType::P2TR,
+        ];
+        // Given in reverse order, as they are looked up by outpoint.
+        let utxos: Vec<Utxo> = omni_tx
+            .input
+            .iter()
+            .zip(&spent_outputs)
+            .zip(spend_types)
+            .map(|((input, output), spend_type)| {
+                Utxo::new(input.previous_output, to_omni_tx_out(output), spend_type)
+            })
+            .rev()
+            .collect();
+
+        assert_eq!(omni_tx.verify_utxos(&utxos), Ok(()));
+        assert_eq!(omni_tx.fee(&utxos), Ok(Amount::from_sat(5_000)));
+
+        let mut cache = SighashCache::new(&tx);
+        let legacy = cache
+            .legacy_signature_hash(0, &spent_outputs[0].script_pubkey, 0x01)
+            .unwrap();
+        let segwit = cache
+            .p2wpkh_signature_hash(
+                1,
+                &spent_outputs[1].script_pubkey,
+                spent_outputs[1].value,
+                bitcoin::EcdsaSighashType::All,
+            )
+            .unwrap();
+        assert_eq!(
+            omni_tx.ecdsa_signature_hash(0, &utxos, EcdsaSighashType::All),
+            Ok(legacy.to_byte_array())
+        );
+        assert_eq!(
+            omni_tx.ecdsa_signature_hash(1, &utxos, EcdsaSighashType::All),
+            Ok(segwit.to_byte_array())
+        );
+        assert_eq!(
+            omni_tx.ecdsa_signature_hash(2, &utxos, EcdsaSighashType::All),
+            Err(BitcoinError::UnsupportedScript)
+        );
+
+        assert_eq!(
+            omni_tx.verify_utxos(&utxos[1..]),
+            Err(BitcoinError::MissingUtxo { index: 2 })
+        );
+    }
+
+    #[test]
+    fn test_verify_rejects_tampered_outputs() {
+        let SignedTransaction { tx, spent_outputs } = rust_bitcoin_signed_transaction();
+        let mut omni_tx = to_omni_transaction(&tx);
+        let omni_spent_outputs: Vec<TxOut> = spent_outputs.iter().map(to_omni_tx_out).collect();
+
+        omni_tx.output[0].value = Amount::from_sat(59_000);
+
+        for index in 0..omni_tx.input.len() {
+            assert_eq!(
+                omni_tx.verify_input(index, &omni_spent_outputs),
+                Err(BitcoinError::InvalidSignature { index })
+            );
+        }
+    }
+
+    #[test]
+    fn test_verify_rejects_invalid_spends() {
+        let SignedTransaction { tx, spent_outputs } = rust_bitcoin_signed_transaction();
+        let omni_tx = to_omni_transaction(&tx);
+        let mut omni_spent_outputs: Vec<TxOut> = spent_outputs.iter().map(to_omni_tx_out).collect();
+
+        assert_eq!(
+            omni_tx.verify(&omni_spent_outputs[..2]),
+            Err(BitcoinError::SpentOutputsMismatch {
+                expected: 3,
+                actual: 2
+            })
+        );
+
+        // The P2WPKH witness does not match a different public key hash
+        omni_spent_outputs[1].script_pubkey = ScriptBuf::new_p2wpkh(&[0x00; 20]);
+        assert_eq!(
+            omni_tx.verify_input(1, &omni_spent_outputs),
+            Err(BitcoinError::InvalidSpend {
+                index: 1,
+                reason: "public key does not match the public key hash"
+            })
+        );
+
+        omni_spent_outputs[1].script_pubkey = ScriptBuf::from_hex("6a").unwrap();
+        assert_eq!(
+            omni_tx.verify_input(1, &omni_spent_outputs),
+            Err(BitcoinError::UnsupportedScript)
+        );
+    }
+
+    #[test]
+    fn test_parse_pushes() {
+        let script = [0x00, 0x02, 0xaa, 0xbb, OP_PUSHDATA1, 0x01, 0xcc];
+
+        assert_eq!(
+            parse_pushes(&script),
+            Some(vec![&[][..], &[0xaa, 0xbb][..], &[0xcc][..]])
+        );
+        assert_eq!(parse_pushes(&[0x02, 0xaa]), None);
+        assert_eq!(parse_pushes(&[0x76]), None);
+    }
+}
@@ -1,5 +1,15 @@
+/// EIP-2930 transaction type
+pub const EIP_2930_TYPE: u8 = 0x01;
 /// EIP-1559 transaction type
 pub const EIP_1559_TYPE: u8 = 0x02;
+/// EIP-4844 transaction type
+pub const EIP_4844_TYPE: u8 = 0x03;
+/// EIP-7702 transaction type
+pub const EIP_7702_TYPE: u8 = 0x04;
+/// Prefix of the EIP-7702 authorization signing payload
+pub const EIP_7702_AUTH_MAGIC: u8 = 0x05;
+/// Version byte of versioned hashes of KZG commitments (EIP-4844)
+pub const VERSIONED_HASH_VERSION_KZG: u8 = 0x01;
 /// Length of an Ed25519 public key
 pub const ED25519_PUBLIC_KEY_LENGTH: usize = 32;
 /// Length of a secp256k1 public key
@@ -0,0 +1,692 @@
+//! Solidity ABI encoding of function calls.
+//!
+//! ###### Example:
+//!
+//! ```rust
+//! let recipient: Address = "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045".parse()?;
+//!
+//! let input = encode_call(
+//!     "transfer(address,uint256)",
+//!     &[Token::Address(recipient), Token::uint(1_000_000)],
+//! )?;
+//! ```
+use std::fmt;
+use std::str::FromStr;
+
+use super::error::EVMError;
+use super::types::Address;
+use super::utils::keccak256;
+
+/// A Solidity ABI type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParamType {
+    Address,
+    Bool,
+    /// `uintN`, with its size in bits.
+    Uint(usize),
+    /// `intN`, with its size in bits.
+    Int(usize),
+    /// `bytesN`, with its size in bytes.
+    FixedBytes(usize),
+    Bytes,
+    String,
+    /// `T[]`
+    Array(Box<ParamType>),
+    /// `T[k]`
+    FixedArray(Box<ParamType>, usize),
+    /// `(T1,T2,...)`
+    Tuple(Vec<ParamType>),
+}
+
+/// A value to encode, checked against the [`ParamType`] of its parameter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Token {
+    Address(Address),
+    Bool(bool),
+    /// A big-endian unsigned integer, for `uintN`.
+    Uint([u8; 32]),
+    /// A big-endian two's complement integer, for `intN`.
+    Int([u8; 32]),
+    /// The value of a `bytesN`, of exactly `N` bytes.
+    FixedBytes(Vec<u8>),
+    Bytes(Vec<u8>),
+    String(String),
+    /// The elements of a `T[]` or `T[k]`.
+    Array(Vec<Token>),
+    Tuple(Vec<Token>),
+}
+
+impl Token {
+    pub fn uint(value: u128) -> Self {
+        Self::Uint(uint_word(value))
+    }
+
+    pub fn int(value: i128) -> Self {
+        let mut word = if value < 0 { [0xff; 32] } else { [0u8; 32] };
+        word[16..].copy_from_slice(&value.to_be_bytes());
+        Self::Int(word)
+    }
+}
+
+impl ParamType {
+    /// Whether the type is encoded in the tail, behind an offset.
+    pub fn is_dynamic(&self) -> bool {
+        match self {
+            Self::Bytes | Self::String | Self::Array(_) => true,
+            Self::FixedArray(param, _) => param.is_dynamic(),
+            Self::Tuple(params) => params.iter().any(Self::is_dynamic),
+            _ => false,
+        }
+    }
+
+    /// The size of the type in the head of a sequence, 32 bytes for dynamic types.
+    ///
+    /// Returns [`None`] if the size overflows, for fixed arrays too large to be encoded.
+    fn head_size(&self) -> Option<usize> {
+        match self {
+            _ if self.is_dynamic() => Some(32),
+            Self::FixedArray(param, len) => param.head_size()?.checked_mul(*len),
+            Self::Tuple(params) => sequence_head_size(params.iter()),
+            _ => Some(32),
+        }
+    }
+}
+
+impl fmt::Display for ParamType {
+    /// Formats the canonical type name, as used in function signatures.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Address => write!(f, "address"),
+            Self::Bool => write!(f, "bool"),
+            Self::Uint(size) => write!(f, "uint{size}"),
+            Self::Int(size) => write!(f, "int{size}"),
+            Self::FixedBytes(size) => write!(f, "bytes{size}"),
+            Self::Bytes => write!(f, "bytes"),
+            Self::String => write!(f, "string"),
+            Self::Array(param) => write!(f, "{param}[]"),
+            Self::FixedArray(param, len) => write!(f, "{param}[{len}]"),
+            Self::Tuple(params) => write!(f, "({})", join(params)),
+        }
+    }
+}
+
+impl FromStr for ParamType {
+    type Err = EVMError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || EVMError::InvalidAbiSignature(s.to_string());
+        let s = s.trim();
+
+        if let Some(rest) = s.strip_suffix(']') {
+            let open = rest.rfind('[').ok_or_else(invalid)?;
+            let param = Box::new(rest[..open].parse()?);
+
+            return match &rest[open + 1..] {
+                "" => Ok(Self::Array(param)),
+                // Like `bytes0`, arrays of no elements are not valid types
+                len => parse_size(len)
+                    .filter(|&len| len > 0)
+                    .map(|len| Self::FixedArray(param, len))
+                    .ok_or_else(invalid),
+            };
+        }
+
+        if let Some(components) = s.strip_prefix('(').and_then(|s| s.strip_suffix(')')) {
+            return parse_params(components).map(Self::Tuple);
+        }
+
+        let size = |prefix: &str| -> Option<usize> {
+            let size = s.strip_prefix(prefix)?;
+            // Sizes are written without leading zeros
+            if size.starts_with('0') {
+                return None;
+            }
+            parse_size(size)
+        };
+
+        match s {
+            "address" => Ok(Self::Address),
+            "bool" => Ok(Self::Bool),
+            "bytes" => Ok(Self::Bytes),
+            "string" => Ok(Self::String),
+            "uint" => Ok(Self::Uint(256)),
+            "int" => Ok(Self::Int(256)),
+            _ => match (size("uint"), size("int"), size("bytes")) {
+                (Some(bits), _, _) if bits % 8 == 0 && (8..=256).contains(&bits) => {
+                    Ok(Self::Uint(bits))
+                }
+                (_, Some(bits), _) if bits % 8 == 0 && (8..=256).contains(&bits) => {
+                    Ok(Self::Int(bits))
+                }
+                (_, _, Some(len)) if (1..=32).contains(&len) => Ok(Self::FixedBytes(len)),
+                _ => Err(invalid()),
+            },
+        }
+    }
+}
+
+/// Computes the canonical form of a function signature, such as `transfer(address,uint256)`.
+pub fn canonical_signature(signature: &str) -> Result<String, EVMError> {
+    let (name, params) = parse_signature(signature)?;
+    Ok(format!("{name}({})", join(&params)))
+}
+
+/// Computes the 4 byte selector of a function signature, the first bytes of the keccak256 hash
+/// of its canonical form.
+pub fn selector(signature: &str) -> Result<[u8; 4], EVMError> {
+    let hash = keccak256(canonical_signature(signature)?.as_bytes());
+    let mut selector = [0u8; 4];
+    selector.copy_from_slice(&hash[..4]);
+    Ok(selector)
+}
+
+/// Encodes a function call, the selector of `signature` followed by the encoded `args`.
+pub fn encode_call(signature: &str, args: &[Token]) -> Result<Vec<u8>, EVMError> {
+    let (_, params) = parse_signature(signature)?;
+
+    let mut call = selector(signature)?.to_vec();
+    call.extend(encode(&params, args)?);
+    Ok(call)
+}
+
+/// Encodes `args` as a sequence of `params`, such as the arguments of a function or a constructor.
+pub fn encode(params: &[ParamType], args: &[Token]) -> Result<Vec<u8>, EVMError> {
+    if params.len() != args.len() {
+        return Err(EVMError::InvalidAbiArgumentCount {
+            expected: params.len(),
+            actual: args.len(),
+        });
+    }
+
+    let mut head = Vec::new();
+    let mut tail = Vec::new();
+    let head_size = sequence_head_size(params.iter())
+        .ok_or_else(|| EVMError::InvalidAbiSignature(join(params)))?;
+
+    for (index, (param, arg)) in params.iter().zip(args).enumerate() {
+        encode_head_and_tail(param, arg, head_size, &mut head, &mut tail)
+            .map_err(|reason| EVMError::InvalidAbiArgument { index, reason })?;
+    }
+
+    head.extend(tail);
+    Ok(head)
+}
+
+/// Splits a function signature into its name and parameter types.
+fn parse_signature(signature: &str) -> Result<(&str, Vec<ParamType>), EVMError> {
+    let invalid = || EVMError::InvalidAbiSignature(signature.to_string());
+
+    let signature = signature.trim();
+    let open = signature.find('(').ok_or_else(invalid)?;
+    let name = &signature[..open];
+    let params = signature[open + 1..]
+        .strip_suffix(')')
+        .ok_or_else(invalid)?;
+
+    let is_identifier = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if !is_identifier {
+        return Err(invalid());
+    }
+
+    Ok((name, parse_params(params)?))
+}
+
+/// Parses comma separated parameter types, ignoring commas inside tuples.
+fn parse_params(params: &str) -> Result<Vec<ParamType>, EVMError> {
+    if params.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut depth = 0usize;
+    let mut start = 0;
+    let mut types = Vec::new();
+
+    for (i, c) in params.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth = depth
+                    .checked_sub(1)
+                    .ok_or_else(|| EVMError::InvalidAbiSignature(params.to_string()))?;
+            }
+            ',' if depth == 0 => {
+                types.push(params[start..i].parse()?);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    types.push(params[start..].parse()?);
+
+    Ok(types)
+}
+
+/// Parses a size or length written in decimal digits only, without a sign.
+fn parse_size(size: &str) -> Option<usize> {
+    if size.is_empty() || !size.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    size.parse().ok()
+}
+
+/// The size of the head of a sequence of `params`, or [`None`] if it overflows.
+fn sequence_head_size<'a>(mut params: impl Iterator<Item = &'a ParamType>) -> Option<usize> {
+    params.try_fold(0usize, |total, param| total.checked_add(param.head_size()?))
+}
+
+fn join(params: &[ParamType]) -> String {
+    params
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Encodes a sequence of values of a tuple or array, static values in the head and dynamic
+/// values in the tail behind their offset from the start of the sequence.
+fn encode_sequence<'a>(
+    params: impl Iterator<Item = &'a ParamType> + Clone,
+    args: &[Token],
+    out: &mut Vec<u8>,
+) -> Result<(), &'static str> {
+    let head_size = sequence_head_size(params.clone()).ok_or("encoding is too large")?;
+
+    let mut head = Vec::new();
+    let mut tail = Vec::new();
+    for (param, arg) in params.zip(args) {
+        encode_head_and_tail(param, arg, head_size, &mut head, &mut tail)?;
+    }
+
+    out.extend(head);
+    out.extend(tail);
+    Ok(())
+}
+
+fn encode_head_and_tail(
+    param: &ParamType,
+    arg: &Token,
+    head_size: usize,
+    head: &mut Vec<u8>,
+    tail: &mut Vec<u8>,
+) -> Result<(), &'static str> {
+    if param.is_dynamic() {
+        let offset = head_size
+            .checked_add(tail.len())
+            .ok_or("encoding is too large")?;
+        head.extend(uint_word(offset as u128));
+        encode_token(param, arg, tail)
+    } else {
+        encode_token(param, arg, head)
+    }
+}
+
+fn encode_token(param: &ParamType, arg: &Token, out: &mut Vec<u8>) -> Result<(), &'static str> {
+    match (param, arg) {
+        (ParamType::Address, Token::Address(address)) => {
+            out.extend([0u8; 12]);
+            out.extend(address.as_bytes());
+        }
+        (ParamType::Bool, Token::Bool(value)) => out.extend(uint_word(u128::from(*value))),
+        (ParamType::Uint(bits), Token::Uint(word)) => {
+            let unused = unused_bytes(*bits)?;
+            if word[..unused].iter().any(|&b| b != 0) {
+                return Err("value does not fit in the uint type");
+            }
+            out.extend(word);
+        }
+        (ParamType::Int(bits), Token::Int(word)) => {
+            // The unused bytes must all be the sign extension of the value
+            let unused = unused_bytes(*bits)?;
+            let sign = if word[unused] & 0x80 == 0 { 0 } else { 0xff };
+            if word[..unused].iter().any(|&b| b != sign) {
+                return Err("value does not fit in the int type");
+            }
+            out.extend(word);
+        }
+        (ParamType::FixedBytes(len), Token::FixedBytes(bytes)) => {
+            if *len > 32 {
+                return Err("bytes type should be at most 32 bytes long");
+            }
+            if bytes.len() != *len {
+                return Err("value does not match the length of the bytes type");
+            }
+            out.extend(bytes);
+            out.extend(vec![0u8; 32 - len]);
+        }
+        (ParamType::Bytes, Token::Bytes(bytes)) => encode_bytes(bytes, out),
+        (ParamType::String, Token::String(string)) => encode_bytes(string.as_bytes(), out),
+        (ParamType::Array(param), Token::Array(elements)) => {
+            out.extend(uint_word(elements.len() as u128));
+            encode_sequence(
+                std::iter::repeat(&**param).take(elements.len()),
+                elements,
+                out,
+            )?;
+        }
+        (ParamType::FixedArray(param, len), Token::Array(elements)) => {
+            if elements.len() != *len {
+                return Err("value does not match the length of the fixed array type");
+            }
+            encode_sequence(std::iter::repeat(&**param).take(*len), elements, out)?;
+        }
+        (ParamType::Tuple(params), Token::Tuple(elements)) => {
+            if elements.len() != params.len() {
+                return Err("value does not match the number of tuple components");
+            }
+            encode_sequence(params.iter(), elements, out)?;
+        }
+        _ => return Err("value does not match the parameter type"),
+    }
+
+    Ok(())
+}
+
+/// The number of leading bytes of a word not used by an integer of `bits` bits.
+fn unused_bytes(bits: usize) -> Result<usize, &'static str> {
+    if bits == 0 || bits % 8 != 0 || bits > 256 {
+        return Err("integer type should be a multiple of 8 bits, up to 256");
+    }
+    Ok(32 - bits / 8)
+}
+
+/// Encodes the length of `bytes` followed by `bytes`, right padded to a multiple of 32 bytes.
+fn encode_bytes(bytes: &[u8], out: &mut Vec<u8>) {
+    out.extend(uint_word(bytes.len() as u128));
+    out.extend(bytes);
+    out.extend(vec![0u8; (32 - bytes.len() % 32) % 32]);
+}
+
+fn uint_word(value: u128) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[16..].copy_from_slice(&value.to_be_bytes());
+    word
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{canonical_signature, encode_call, selector, ParamType, Token};
+    use crate::evm::types::Address;
+    use crate::evm::EVMError;
+
+    fn words(words: &[&str]) -> String {
+        words.concat()
+    }
+
+    #[test]
+    fn test_encode_transfer() {
+        let recipient = "d8dA6BF26964aF9D7eEd9e03E53415D37aA96045"
+            .parse::<Address>()
+            .unwrap();
+
+        let call = encode_call(
+            "transfer(address,uint256)",
+            &[Token::Address(recipient), Token::uint(1_000_000)],
+        )
+        .unwrap();
+
+        assert_eq!(
+            hex::encode(call),
+            words(&[
+                "a9059cbb",
+                "000000000000000000000000d8da6bf26964af9d7eed9e03e53415d37aa96045",
+                "00000000000000000000000000000000000000000000000000000000000f4240",
+            ])
+        );
+    }
+
+    #[test]
+    fn test_encode_static_arguments() {
+        // The examples of the Solidity ABI specification
+        let call = encode_call("baz(uint32,bool)", &[Token::uint(69), Token::Bool(true)]).unwrap();
+        assert_eq!(
+            hex::encode(call),
+            words(&[
+                "cdcd77c0",
+                "0000000000000000000000000000000000000000000000000000000000000045",
+                "0000000000000000000000000000000000000000000000000000000000000001",
+            ])
+        );
+
+        let call = encode_call(
+            "bar(bytes3[2])",
+            &[Token::Array(vec![
+                Token::FixedBytes(b"abc".to_vec()),
+                Token::FixedBytes(b"def".to_vec()),
+            ])],
+        )
+        .unwrap();
+        assert_eq!(
+            hex::encode(call),
+            words(&[
+                "fce353f6",
+                "6162630000000000000000000000000000000000000000000000000000000000",
+                "6465660000000000000000000000000000000000000000000000000000000000",
+            ])
+        );
+    }
+
+    #[test]
+    fn test_encode_dynamic_arguments() {
+        // The examples of the Solidity ABI specification
+        let call = encode_call(
+            "sam(bytes,bool,uint256[])",
+            &[
+                Token::Bytes(b"dave".to_vec()),
+                Token::Bool(true),
+                Token::Array(vec![Token::uint(1), Token::uint(2), Token::uint(3)]),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            hex::encode(call),
+            words(&[
+                "a5643bf2",
+                "0000000000000000000000000000000000000000000000000000000000000060",
+                "0000000000000000000000000000000000000000000000000000000000000001",
+                "00000000000000000000000000000000000000000000000000000000000000a0",
+                "0000000000000000000000000000000000000000000000000000000000000004",
+                "6461766500000000000000000000000000000000000000000000000000000000",
+                "0000000000000000000000000000000000000000000000000000000000000003",
+                "0000000000000000000000000000000000000000000000000000000000000001",
+                "0000000000000000000000000000000000000000000000000000000000000002",
+                "0000000000000000000000000000000000000000000000000000000000000003",
+            ])
+        );
+
+        let call = encode_call(
+            "f(uint256,uint32[],bytes10,bytes)",
+            &[
+                Token::uint(0x123),
+                Token::Array(vec![Token::uint(0x456), Token::uint(0x789)]),
+                Token::FixedBytes(b"1234567890".to_vec()),
+                Token::Bytes(b"Hello, world!".to_vec()),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            hex::encode(call),
+            words(&[
+                "8be65246",
+                "0000000000000000000000000000000000000000000000000000000000000123",
+                "0000000000000000000000000000000000000000000000000000000000000080",
+                "3132333435363738393000000000000000000000000000000000000000000000",
+                "00000000000000000000000000000000000000000000000000000000000000e0",
+                "0000000000000000000000000000000000000000000000000000000000000002",
+                "0000000000000000000000000000000000000000000000000000000000000456",
+                "0000000000000000000000000000000000000000000000000000000000000789",
+                "000000000000000000000000000000000000000000000000000000000000000d",
+                "48656c6c6f2c20776f726c642100000000000000000000000000000000000000",
+            ])
+        );
+
+        let call = encode_call(
+            "g(uint256[][],string[])",
+            &[
+                Token::Array(vec![
+                    Token::Array(vec![Token::uint(1), Token::uint(2)]),
+                    Token::Array(vec![Token::uint(3)]),
+                ]),
+                Token::Array(vec![
+                    Token::String("one".to_string()),
+                    Token::String("two".to_string()),
+                    Token::String("three".to_string()),
+                ]),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            hex::encode(call),
+            words(&[
+                "2289b18c",
+                "0000000000000000000000000000000000000000000000000000000000000040",
+                "0000000000000000000000000000000000000000000000000000000000000140",
+                "0000000000000000000000000000000000000000000000000000000000000002",
+                "0000000000000000000000000000000000000000000000000000000000000040",
+                "00000000000000000000000000000000000000000000000000000000000000a0",
+                "0000000000000000000000000000000000000000000000000000000000000002",
+                "0000000000000000000000000000000000000000000000000000000000000001",
+                "0000000000000000000000000000000000000000000000000000000000000002",
+                "0000000000000000000000000000000000000000000000000000000000000001",
+                "0000000000000000000000000000000000000000000000000000000000000003",
+                "0000000000000000000000000000000000000000000000000000000000000003",
+                "0000000000000000000000000000000000000000000000000000000000000060",
+                "00000000000000000000000000000000000000000000000000000000000000a0",
+                "00000000000000000000000000000000000000000000000000000000000000e0",
+                "0000000000000000000000000000000000000000000000000000000000000003",
+                "6f6e650000000000000000000000000000000000000000000000000000000000",
+                "0000000000000000000000000000000000000000000000000000000000000003",
+                "74776f0000000000000000000000000000000000000000000000000000000000",
+                "0000000000000000000000000000000000000000000000000000000000000005",
+                "7468726565000000000000000000000000000000000000000000000000000000",
+            ])
+        );
+    }
+
+    #[test]
+    fn test_encode_tuples_and_signed_integers() {
+        let call = encode_call(
+            "h((int8,bytes),int256)",
+            &[
+                Token::Tuple(vec![Token::int(-1), Token::Bytes(vec![0xaa])]),
+                Token::int(-2),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(
+            hex::encode(&call[4..]),
+            words(&[
+                "0000000000000000000000000000000000000000000000000000000000000040",
+                "fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffe",
+                "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+                "0000000000000000000000000000000000000000000000000000000000000040",
+                "0000000000000000000000000000000000000000000000000000000000000001",
+                "aa00000000000000000000000000000000000000000000000000000000000000",
+            ])
+        );
+        assert_eq!(call[..4], selector("h((int8,bytes),int256)").unwrap());
+    }
+
+    #[test]
+    fn test_parse_signature() {
+        assert_eq!(
+            canonical_signature("transfer(address, uint)").unwrap(),
+            "transfer(address,uint256)"
+        );
+        assert_eq!(
+            canonical_signature("f((int,bytes32)[2][],string)").unwrap(),
+            "f((int256,bytes32)[2][],string)"
+        );
+        assert_eq!(
+            "(uint8,bool)[3]".parse::<ParamType>().unwrap(),
+            ParamType::FixedArray(
+                Box::new(ParamType::Tuple(vec![ParamType::Uint(8), ParamType::Bool])),
+                3
+            )
+        );
+
+        for invalid in [
+            "transfer",
+            "transfer(address",
+            "(address)",
+            "f(uint7)",
+            "f(uint512)",
+            "f(bytes33)",
+            "f(uint08)",
+            "f(addres)",
+            "f((uint256)",
+            "f(uint256[x])",
+            "f(uint256[+2])",
+            "f(uint256[0])",
+            "f(uint256[00])",
+            "f(bytes0)",
+            "f(uint+8)",
+            "f(int+8)",
+            "f(bytes+4)",
+        ] {
+            assert!(
+                matches!(
+                    canonical_signature(invalid),
+                    Err(EVMError::InvalidAbiSignature(_))
+                ),
+                "{invalid}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_encode_invalid_arguments() {
+        assert_eq!(
+            encode_call("f(uint256,bool)", &[Token::uint(1)]),
+            Err(EVMError::InvalidAbiArgumentCount {
+                expected: 2,
+                actual: 1
+            })
+        );
+
+        for (signature, arg) in [
+            ("f(uint8)", Token::uint(256)),
+            ("f(int8)", Token::int(128)),
+            ("f(int8)", Token::int(-129)),
+            ("f(uint256)", Token::int(1)),
+            ("f(bytes4)", Token::FixedBytes(vec![0; 3])),
+            ("f(uint256[2])", Token::Array(vec![Token::uint(1)])),
+            ("f((bool,bool))", Token::Tuple(vec![Token::Bool(true)])),
+        ] {
+            assert!(
+                matches!(
+                    encode_call(signature, &[arg]),
+                    Err(EVMError::InvalidAbiArgument { index: 0, .. })
+                ),
+                "{signature}"
+            );
+        }
+
+        assert!(matches!(
+            encode_call(
+                "f(uint256[18446744073709551615],uint256)",
+                &[Token::Array(vec![]), Token::uint(1)]
+            ),
+            Err(EVMError::InvalidAbiSignature(_))
+        ));
+        assert!(matches!(
+            encode_call(
+                "f((uint256[18446744073709551615],uint256)[])",
+                &[Token::Array(vec![Token::Tuple(vec![
+                    Token::Array(vec![]),
+                    Token::uint(1)
+                ])])]
+            ),
+            Err(EVMError::InvalidAbiArgument { index: 0, .. })
+        ));
+
+        assert!(encode_call("f(int8)", &[Token::int(-128)]).is_ok());
+        assert!(encode_call("f(uint8)", &[Token::uint(255)]).is_ok());
+    }
+}
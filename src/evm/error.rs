@@ -0,0 +1,60 @@
+//! Error type returned by the fallible EVM APIs.
+use std::fmt;
+
+/// Errors that can occur while handling EVM signatures, addresses and ABI encoding.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EVMError {
+    /// The signature is malformed or does not recover to a public key.
+    InvalidSignature(&'static str),
+    /// The recovery id of the signature is not 0 or 1.
+    InvalidRecoveryId(u64),
+    /// The chain id is too large to be encoded in an EIP-155 `v`.
+    InvalidChainId(u64),
+    /// The address is not 20 hex encoded bytes.
+    InvalidAddress(&'static str),
+    /// The mixed case address does not match its EIP-55 checksum.
+    InvalidAddressChecksum,
+    /// The function signature or ABI type could not be parsed.
+    InvalidAbiSignature(String),
+    /// The number of arguments does not match the number of parameters.
+    InvalidAbiArgumentCount {
+        /// The number of parameters.
+        expected: usize,
+        /// The number of arguments.
+        actual: usize,
+    },
+    /// An argument does not match the type of its parameter.
+    InvalidAbiArgument {
+        /// The index of the argument.
+        index: usize,
+        /// Why the argument does not match.
+        reason: &'static str,
+    },
+}
+
+impl fmt::Display for EVMError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidSignature(reason) => write!(f, "Invalid signature: {reason}"),
+            Self::InvalidRecoveryId(recovery_id) => {
+                write!(f, "Invalid recovery id: {recovery_id}, expected 0 or 1")
+            }
+            Self::InvalidChainId(chain_id) => {
+                write!(f, "Chain id {chain_id} is too large for an EIP-155 v")
+            }
+            Self::InvalidAddress(reason) => write!(f, "Invalid address: {reason}"),
+            Self::InvalidAddressChecksum => write!(f, "Invalid EIP-55 address checksum"),
+            Self::InvalidAbiSignature(signature) => {
+                write!(f, "Invalid ABI signature: {signature}")
+            }
+            Self::InvalidAbiArgumentCount { expected, actual } => {
+                write!(f, "Expected {expected} ABI arguments, got {actual}")
+            }
+            Self::InvalidAbiArgument { index, reason } => {
+                write!(f, "Invalid ABI argument {index}: {reason}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for EVMError {}
@@ -0,0 +1,211 @@
+//! EVM access list transaction
+use near_sdk::serde::{Deserialize, Serialize};
+use rlp::{DecoderError, Rlp, RlpStream};
+use schemars::JsonSchema;
+
+use crate::constants::EIP_2930_TYPE;
+
+use super::evm_signable_transaction::EVMSignableTransaction;
+use super::types::{AccessList, Address, Signature};
+use super::utils::{
+    append_uint_bytes, decode_access_list, decode_optional_address, decode_signature,
+    decode_typed_list, encode_access_list,
+};
+
+/// EIP-2930 (type 1) transaction, with a gas price and an access list.
+///
+/// ###### Example:
+///
+/// ```rust
+/// let tx = EVMAccessListTransaction {
+///     chain_id: 1,
+///     nonce: 0,
+///     to: Some("d8dA6BF26964aF9D7eEd9e03E53415D37aA96045".parse::<Address>().unwrap()),
+///     value: 0,
+///     input: vec![],
+///     gas_limit: 30_000,
+///     gas_price: 20_000_000_000,
+///     access_list: vec![(contract, vec![storage_key])],
+/// };
+///
+/// let payload = tx.build_for_signing();
+/// ```
+///
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct EVMAccessListTransaction {
+    pub chain_id: u64,
+    pub nonce: u64,
+    pub to: Option<Address>,
+    pub value: u128,
+    pub input: Vec<u8>,
+    pub gas_limit: u128,
+    pub gas_price: u128,
+    pub access_list: AccessList,
+}
+
+impl EVMAccessListTransaction {
+    pub fn build_for_signing(&self) -> Vec<u8> {
+        let mut rlp_stream = RlpStream::new();
+
+        rlp_stream.append(&EIP_2930_TYPE);
+
+        rlp_stream.begin_unbounded_list();
+
+        self.encode_fields(&mut rlp_stream);
+
+        rlp_stream.finalize_unbounded_list();
+
+        rlp_stream.out().to_vec()
+    }
+
+    pub fn build_with_signature(&self, signature: &Signature) -> Vec<u8> {
+        let mut rlp_stream = RlpStream::new();
+
+        rlp_stream.append(&EIP_2930_TYPE);
+
+        rlp_stream.begin_unbounded_list();
+
+        self.encode_fields(&mut rlp_stream);
+
+        rlp_stream.append(&signature.v);
+        append_uint_bytes(&mut rlp_stream, &signature.r);
+        append_uint_bytes(&mut rlp_stream, &signature.s);
+
+        rlp_stream.finalize_unbounded_list();
+
+        rlp_stream.out().to_vec()
+    }
+
+    /// Decodes an unsigned transaction from its signing payload, see [`Self::build_for_signing`].
+    pub fn decode_for_signing(raw: &[u8]) -> Result<Self, DecoderError> {
+        let rlp = decode_typed_list(raw, EIP_2930_TYPE, 8)?;
+
+        Self::decode_fields(&rlp)
+    }
+
+    /// Decodes a signed transaction and its signature, see [`Self::build_with_signature`].
+    pub fn decode_with_signature(raw: &[u8]) -> Result<(Self, Signature), DecoderError> {
+        let rlp = decode_typed_list(raw, EIP_2930_TYPE, 11)?;
+
+        Ok((Self::decode_fields(&rlp)?, decode_signature(&rlp, 8)?))
+    }
+
+    fn encode_fields(&self, rlp_stream: &mut RlpStream) {
+        let to: Vec<u8> = self.to.map_or(vec![], |to| to.as_bytes().to_vec());
+
+        rlp_stream.append(&self.chain_id);
+        rlp_stream.append(&self.nonce);
+        rlp_stream.append(&self.gas_price);
+        rlp_stream.append(&self.gas_limit);
+        rlp_stream.append(&to);
+        rlp_stream.append(&self.value);
+        rlp_stream.append(&self.input);
+
+        encode_access_list(rlp_stream, &self.access_list);
+    }
+
+    fn decode_fields(rlp: &Rlp) -> Result<Self, DecoderError> {
+        Ok(Self {
+            chain_id: rlp.val_at(0)?,
+            nonce: rlp.val_at(1)?,
+            gas_price: rlp.val_at(2)?,
+            gas_limit: rlp.val_at(3)?,
+            to: decode_optional_address(&rlp.at(4)?)?,
+            value: rlp.val_at(5)?,
+            input: rlp.val_at(6)?,
+            access_list: decode_access_list(&rlp.at(7)?)?,
+        })
+    }
+}
+
+impl EVMSignableTransaction for EVMAccessListTransaction {
+    fn build_for_signing(&self) -> Vec<u8> {
+        Self::build_for_signing(self)
+    }
+
+    fn build_with_signature(&self, signature: &Signature) -> Vec<u8> {
+        Self::build_with_signature(self, signature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        consensus::{SignableTransaction, TxEip2930},
+        primitives::{address, b256, hex, Bytes, TxKind, U256},
+        rpc::types::{AccessList, AccessListItem},
+    };
+    use alloy_primitives::Signature;
+
+    use crate::evm::evm_access_list_transaction::EVMAccessListTransaction;
+    use crate::evm::types::{Address, Signature as OmniSignature};
+
+    #[test]
+    fn test_build_for_signing_and_with_signature_for_access_list_evm_against_alloy() {
+        let chain_id = 1;
+        let nonce = 0x42;
+        let gas_limit = 44386;
+        let gas_price = 0x4a817c800;
+        let value = 0_u128;
+        let contract = address!("6069a6c32cf691f5982febae4faf8a6f3ab2f0f6");
+        let storage_key = b256!("0000000000000000000000000000000000000000000000000000000000000003");
+        let input: Bytes = hex!("a22cb4650000000000000000000000005eee75727d804a2b13038928d36f8b188945a57a0000000000000000000000000000000000000000000000000000000000000000").into();
+
+        let tx = TxEip2930 {
+            chain_id,
+            nonce,
+            gas_price,
+            gas_limit,
+            to: TxKind::Call(contract),
+            value: U256::from(value),
+            access_list: AccessList(vec![AccessListItem {
+                address: contract,
+                storage_keys: vec![storage_key],
+            }]),
+            input: input.clone(),
+        };
+
+        let mut tx_encoded = vec![];
+        tx.encode_for_signing(&mut tx_encoded);
+
+        // Generate using EVMAccessListTransaction
+        let tx_omni = EVMAccessListTransaction {
+            chain_id,
+            nonce,
+            to: Some(
+                "6069a6c32cf691f5982febae4faf8a6f3ab2f0f6"
+                    .parse::<Address>()
+                    .unwrap(),
+            ),
+            value,
+            input: input.to_vec(),
+            gas_limit,
+            gas_price,
+            access_list: vec![(contract.0 .0.into(), vec![storage_key.0])],
+        };
+
+        assert_eq!(tx_omni.build_for_signing(), tx_encoded);
+
+        let sig = Signature::from_scalars_and_parity(
+            b256!("840cfc572845f5786e702984c2a582528cad4b49b2a10b9db1be7fca90058565"),
+            b256!("25e7109ceb98168d95b09b18bbf6b685130e0562f233877d492b94eee0c5b6d1"),
+            false,
+        )
+        .unwrap();
+
+        let mut tx_encoded_with_signature: Vec<u8> = vec![];
+        tx.encode_with_signature(&sig, &mut tx_encoded_with_signature, false);
+
+        let signature: OmniSignature = OmniSignature {
+            v: sig.v().to_u64(),
+            r: sig.r().to_be_bytes::<32>().to_vec(),
+            s: sig.s().to_be_bytes::<32>().to_vec(),
+        };
+
+        assert_eq!(
+            tx_omni.build_with_signature(&signature),
+            tx_encoded_with_signature
+        );
+    }
+}
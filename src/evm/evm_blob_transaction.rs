@@ -0,0 +1,333 @@
+//! EVM blob transaction
+use near_sdk::serde::{Deserialize, Serialize};
+use rlp::{DecoderError, Rlp, RlpStream};
+use schemars::JsonSchema;
+
+use crate::constants::EIP_4844_TYPE;
+
+use super::evm_signable_transaction::EVMSignableTransaction;
+use super::types::{AccessList, Address, BlobSidecar, Signature};
+use super::utils::{
+    append_uint_bytes, decode_access_list, decode_address, decode_bytes32_list,
+    decode_bytes48_list, decode_signature, decode_typed_list, encode_access_list,
+};
+
+/// EIP-4844 (type 3) transaction, carrying data blobs.
+///
+/// Blob transactions cannot create contracts, so `to` is mandatory. The transaction commits
+/// to the blobs through `blob_versioned_hashes`, see [`BlobSidecar::versioned_hashes`].
+///
+/// ###### Example:
+///
+/// ```rust
+/// let tx = EVMBlobTransaction {
+///     chain_id: 1,
+///     nonce: 0,
+///     to: "d8dA6BF26964aF9D7eEd9e03E53415D37aA96045".parse::<Address>().unwrap(),
+///     value: 0,
+///     input: vec![],
+///     gas_limit: 21_000,
+///     max_fee_per_gas: MAX_FEE_PER_GAS,
+///     max_priority_fee_per_gas: MAX_PRIORITY_FEE_PER_GAS,
+///     access_list: vec![],
+///     max_fee_per_blob_gas: MAX_FEE_PER_BLOB_GAS,
+///     blob_versioned_hashes: sidecar.versioned_hashes(),
+/// };
+///
+/// let payload = tx.build_for_signing();
+/// let raw_tx = tx.build_with_sidecar(&signature, &sidecar);
+/// ```
+///
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct EVMBlobTransaction {
+    pub chain_id: u64,
+    pub nonce: u64,
+    pub to: Address,
+    pub value: u128,
+    pub input: Vec<u8>,
+    pub gas_limit: u128,
+    pub max_fee_per_gas: u128,
+    pub max_priority_fee_per_gas: u128,
+    pub access_list: AccessList,
+    pub max_fee_per_blob_gas: u128,
+    pub blob_versioned_hashes: Vec<[u8; 32]>,
+}
+
+impl EVMBlobTransaction {
+    pub fn build_for_signing(&self) -> Vec<u8> {
+        let mut rlp_stream = RlpStream::new();
+
+        rlp_stream.append(&EIP_4844_TYPE);
+
+        rlp_stream.begin_unbounded_list();
+
+        self.encode_fields(&mut rlp_stream);
+
+        rlp_stream.finalize_unbounded_list();
+
+        rlp_stream.out().to_vec()
+    }
+
+    /// Encodes the signed transaction, as included in blocks.
+    pub fn build_with_signature(&self, signature: &Signature) -> Vec<u8> {
+        let mut rlp_stream = RlpStream::new();
+
+        rlp_stream.append(&EIP_4844_TYPE);
+
+        self.encode_signed_fields(&mut rlp_stream, signature);
+
+        rlp_stream.out().to_vec()
+    }
+
+    /// Encodes the signed transaction with its blobs, commitments and proofs, as sent to
+    /// `eth_sendRawTransaction`: `0x03 || rlp([tx, blobs, commitments, proofs])`.
+    pub fn build_with_sidecar(&self, signature: &Signature, sidecar: &BlobSidecar) -> Vec<u8> {
+        let mut rlp_stream = RlpStream::new();
+
+        rlp_stream.append(&EIP_4844_TYPE);
+
+        rlp_stream.begin_unbounded_list();
+
+        self.encode_signed_fields(&mut rlp_stream, signature);
+
+        rlp_stream.begin_unbounded_list();
+        for blob in &sidecar.blobs {
+            rlp_stream.append(blob);
+        }
+        rlp_stream.finalize_unbounded_list();
+
+        rlp_stream.begin_unbounded_list();
+        for commitment in &sidecar.commitments {
+            rlp_stream.append(&commitment.to_vec());
+        }
+        rlp_stream.finalize_unbounded_list();
+
+        rlp_stream.begin_unbounded_list();
+        for proof in &sidecar.proofs {
+            rlp_stream.append(&proof.to_vec());
+        }
+        rlp_stream.finalize_unbounded_list();
+
+        rlp_stream.finalize_unbounded_list();
+
+        rlp_stream.out().to_vec()
+    }
+
+    fn encode_signed_fields(&self, rlp_stream: &mut RlpStream, signature: &Signature) {
+        rlp_stream.begin_unbounded_list();
+
+        self.encode_fields(rlp_stream);
+
+        rlp_stream.append(&signature.v);
+        append_uint_bytes(rlp_stream, &signature.r);
+        append_uint_bytes(rlp_stream, &signature.s);
+
+        rlp_stream.finalize_unbounded_list();
+    }
+
+    /// Decodes an unsigned transaction from its signing payload, see [`Self::build_for_signing`].
+    pub fn decode_for_signing(raw: &[u8]) -> Result<Self, DecoderError> {
+        let rlp = decode_typed_list(raw, EIP_4844_TYPE, 11)?;
+
+        Self::decode_fields(&rlp)
+    }
+
+    /// Decodes a signed transaction and its signature, see [`Self::build_with_signature`].
+    pub fn decode_with_signature(raw: &[u8]) -> Result<(Self, Signature), DecoderError> {
+        let rlp = decode_typed_list(raw, EIP_4844_TYPE, 14)?;
+
+        Ok((Self::decode_fields(&rlp)?, decode_signature(&rlp, 11)?))
+    }
+
+    /// Decodes a signed transaction with its blobs, commitments and proofs, see
+    /// [`Self::build_with_sidecar`].
+    pub fn decode_with_sidecar(raw: &[u8]) -> Result<(Self, Signature, BlobSidecar), DecoderError> {
+        let rlp = decode_typed_list(raw, EIP_4844_TYPE, 4)?;
+
+        let tx = rlp.at(0)?;
+        if tx.item_count()? != 14 {
+            return Err(DecoderError::RlpIncorrectListLen);
+        }
+
+        let blobs = rlp.at(1)?;
+        if !blobs.is_list() {
+            return Err(DecoderError::RlpExpectedToBeList);
+        }
+
+        let sidecar = BlobSidecar {
+            blobs: blobs.as_list()?,
+            commitments: decode_bytes48_list(&rlp.at(2)?)?,
+            proofs: decode_bytes48_list(&rlp.at(3)?)?,
+        };
+
+        Ok((
+            Self::decode_fields(&tx)?,
+            decode_signature(&tx, 11)?,
+            sidecar,
+        ))
+    }
+
+    fn encode_fields(&self, rlp_stream: &mut RlpStream) {
+        rlp_stream.append(&self.chain_id);
+        rlp_stream.append(&self.nonce);
+        rlp_stream.append(&self.max_priority_fee_per_gas);
+        rlp_stream.append(&self.max_fee_per_gas);
+        rlp_stream.append(&self.gas_limit);
+        rlp_stream.append(&self.to.as_bytes().to_vec());
+        rlp_stream.append(&self.value);
+        rlp_stream.append(&self.input);
+
+        encode_access_list(rlp_stream, &self.access_list);
+
+        rlp_stream.append(&self.max_fee_per_blob_gas);
+
+        rlp_stream.begin_unbounded_list();
+        for hash in &self.blob_versioned_hashes {
+            rlp_stream.append(&hash.to_vec());
+        }
+        rlp_stream.finalize_unbounded_list();
+    }
+
+    fn decode_fields(rlp: &Rlp) -> Result<Self, DecoderError> {
+        Ok(Self {
+            chain_id: rlp.val_at(0)?,
+            nonce: rlp.val_at(1)?,
+            max_priority_fee_per_gas: rlp.val_at(2)?,
+            max_fee_per_gas: rlp.val_at(3)?,
+            gas_limit: rlp.val_at(4)?,
+            to: decode_address(&rlp.at(5)?)?,
+            value: rlp.val_at(6)?,
+            input: rlp.val_at(7)?,
+            access_list: decode_access_list(&rlp.at(8)?)?,
+            max_fee_per_blob_gas: rlp.val_at(9)?,
+            blob_versioned_hashes: decode_bytes32_list(&rlp.at(10)?)?,
+        })
+    }
+}
+
+impl EVMSignableTransaction for EVMBlobTransaction {
+    fn build_for_signing(&self) -> Vec<u8> {
+        Self::build_for_signing(self)
+    }
+
+    fn build_with_signature(&self, signature: &Signature) -> Vec<u8> {
+        Self::build_with_signature(self, signature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        consensus::{
+            SignableTransaction, TxEip4844, TxEip4844Variant, TxEip4844WithSidecar, TxEnvelope,
+        },
+        eips::{
+            eip2718::Encodable2718,
+            eip4844::{kzg_to_versioned_hash, Blob, BlobTransactionSidecar, Bytes48},
+        },
+        primitives::{address, b256, hex, Bytes, U256},
+        rpc::types::AccessList,
+    };
+    use alloy_primitives::Signature;
+
+    use crate::evm::evm_blob_transaction::EVMBlobTransaction;
+    use crate::evm::types::{Address, BlobSidecar, Signature as OmniSignature};
+
+    const MAX_FEE_PER_GAS: u128 = 0x4a817c800;
+    const MAX_PRIORITY_FEE_PER_GAS: u128 = 0x3b9aca00;
+    const MAX_FEE_PER_BLOB_GAS: u128 = 0x3b9aca00;
+
+    #[test]
+    fn test_build_for_signing_and_with_sidecar_for_blob_evm_against_alloy() {
+        let chain_id = 1;
+        let nonce = 0x42;
+        let gas_limit = 44386;
+        let value = 7_u128;
+        let input: Bytes = hex!("a22cb465").into();
+        let commitments = [[0xc0; 48], [0xa1; 48]];
+        let proofs = [[0xb0; 48], [0xb1; 48]];
+
+        let sidecar = BlobSidecar {
+            blobs: vec![vec![0x01; 131072], vec![0x02; 131072]],
+            commitments: commitments.to_vec(),
+            proofs: proofs.to_vec(),
+        };
+        let blob_versioned_hashes = sidecar.versioned_hashes();
+
+        let tx = TxEip4844 {
+            chain_id,
+            nonce,
+            gas_limit,
+            max_fee_per_gas: MAX_FEE_PER_GAS,
+            max_priority_fee_per_gas: MAX_PRIORITY_FEE_PER_GAS,
+            to: address!("6069a6c32cf691f5982febae4faf8a6f3ab2f0f6"),
+            value: U256::from(value),
+            access_list: AccessList::default(),
+            blob_versioned_hashes: commitments
+                .iter()
+                .map(|commitment| kzg_to_versioned_hash(commitment))
+                .collect(),
+            max_fee_per_blob_gas: MAX_FEE_PER_BLOB_GAS,
+            input: input.clone(),
+        };
+
+        let mut tx_encoded = vec![];
+        tx.encode_for_signing(&mut tx_encoded);
+
+        // Generate using EVMBlobTransaction
+        let tx_omni = EVMBlobTransaction {
+            chain_id,
+            nonce,
+            to: "6069a6c32cf691f5982febae4faf8a6f3ab2f0f6"
+                .parse::<Address>()
+                .unwrap(),
+            value,
+            input: input.to_vec(),
+            gas_limit,
+            max_fee_per_gas: MAX_FEE_PER_GAS,
+            max_priority_fee_per_gas: MAX_PRIORITY_FEE_PER_GAS,
+            access_list: vec![],
+            max_fee_per_blob_gas: MAX_FEE_PER_BLOB_GAS,
+            blob_versioned_hashes,
+        };
+
+        assert_eq!(tx_omni.build_for_signing(), tx_encoded);
+
+        let sig = Signature::from_scalars_and_parity(
+            b256!("840cfc572845f5786e702984c2a582528cad4b49b2a10b9db1be7fca90058565"),
+            b256!("25e7109ceb98168d95b09b18bbf6b685130e0562f233877d492b94eee0c5b6d1"),
+            true,
+        )
+        .unwrap();
+        let signature = OmniSignature {
+            v: sig.v().to_u64(),
+            r: sig.r().to_be_bytes::<32>().to_vec(),
+            s: sig.s().to_be_bytes::<32>().to_vec(),
+        };
+
+        let mut tx_encoded_with_signature: Vec<u8> = vec![];
+        tx.encode_with_signature(&sig, &mut tx_encoded_with_signature, false);
+
+        assert_eq!(
+            tx_omni.build_with_signature(&signature),
+            tx_encoded_with_signature
+        );
+
+        let alloy_sidecar = BlobTransactionSidecar {
+            blobs: vec![Blob::repeat_byte(0x01), Blob::repeat_byte(0x02)],
+            commitments: commitments.iter().copied().map(Bytes48::from).collect(),
+            proofs: proofs.iter().copied().map(Bytes48::from).collect(),
+        };
+        let tx_with_sidecar: TxEnvelope = TxEip4844Variant::TxEip4844WithSidecar(
+            TxEip4844WithSidecar::from_tx_and_sidecar(tx, alloy_sidecar),
+        )
+        .into_signed(sig)
+        .into();
+
+        assert_eq!(
+            tx_omni.build_with_sidecar(&signature, &sidecar),
+            tx_with_sidecar.encoded_2718()
+        );
+    }
+}
@@ -0,0 +1,282 @@
+//! EVM legacy transaction
+use near_sdk::serde::{Deserialize, Serialize};
+use rlp::{DecoderError, Rlp, RlpStream};
+use schemars::JsonSchema;
+
+use super::error::EVMError;
+use super::evm_signable_transaction::EVMSignableTransaction;
+use super::types::{Address, Signature};
+use super::utils::{append_uint_bytes, decode_list, decode_optional_address, decode_uint_bytes};
+
+/// Legacy (type 0) transaction, replay protected with EIP-155.
+///
+/// ###### Example:
+///
+/// ```rust
+/// let tx = EVMLegacyTransaction {
+///     chain_id: 56,
+///     nonce: 0,
+///     to: Some("d8dA6BF26964aF9D7eEd9e03E53415D37aA96045".parse::<Address>().unwrap()),
+///     value: 10000000000000000u128, // 0.01 BNB
+///     input: vec![],
+///     gas_limit: 21_000,
+///     gas_price: 3_000_000_000,
+/// };
+///
+/// let payload = tx.build_for_signing();
+/// ```
+///
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct EVMLegacyTransaction {
+    pub chain_id: u64,
+    pub nonce: u64,
+    pub to: Option<Address>,
+    pub value: u128,
+    pub input: Vec<u8>,
+    pub gas_limit: u128,
+    pub gas_price: u128,
+}
+
+impl EVMLegacyTransaction {
+    /// Encodes the EIP-155 signing payload `(nonce, gasPrice, gas, to, value, data, chainId, 0, 0)`.
+    pub fn build_for_signing(&self) -> Vec<u8> {
+        let mut rlp_stream = RlpStream::new();
+
+        rlp_stream.begin_unbounded_list();
+
+        self.encode_fields(&mut rlp_stream);
+
+        rlp_stream.append(&self.chain_id);
+        rlp_stream.append(&0u8);
+        rlp_stream.append(&0u8);
+
+        rlp_stream.finalize_unbounded_list();
+
+        rlp_stream.out().to_vec()
+    }
+
+    /// Encodes the signed transaction.
+    ///
+    /// Panics if the EIP-155 `v` cannot be computed, see [`Self::try_build_with_signature`].
+    pub fn build_with_signature(&self, signature: &Signature) -> Vec<u8> {
+        self.try_build_with_signature(signature)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Encodes the signed transaction, returning an error instead of panicking.
+    ///
+    /// `signature.v` is the recovery id (0 or 1), from which the EIP-155 `v` is computed, see
+    /// [`Signature::eip155_v`].
+    pub fn try_build_with_signature(&self, signature: &Signature) -> Result<Vec<u8>, EVMError> {
+        let v = signature.eip155_v(self.chain_id)?;
+
+        let mut rlp_stream = RlpStream::new();
+
+        rlp_stream.begin_unbounded_list();
+
+        self.encode_fields(&mut rlp_stream);
+
+        rlp_stream.append(&v);
+        append_uint_bytes(&mut rlp_stream, &signature.r);
+        append_uint_bytes(&mut rlp_stream, &signature.s);
+
+        rlp_stream.finalize_unbounded_list();
+
+        Ok(rlp_stream.out().to_vec())
+    }
+
+    /// Decodes an unsigned transaction from its EIP-155 signing payload, see
+    /// [`Self::build_for_signing`].
+    pub fn decode_for_signing(raw: &[u8]) -> Result<Self, DecoderError> {
+        let rlp = decode_list(raw, 9)?;
+
+        if rlp.val_at::<u8>(7)? != 0 || rlp.val_at::<u8>(8)? != 0 {
+            return Err(DecoderError::Custom(
+                "EIP-155 signing payload should end with 0, 0",
+            ));
+        }
+
+        Self::decode_fields(&rlp, rlp.val_at(6)?)
+    }
+
+    /// Decodes a signed transaction and its signature, see [`Self::build_with_signature`].
+    ///
+    /// The chain id is recovered from the EIP-155 `v`, and the returned `signature.v` is the
+    /// recovery id. Transactions signed without replay protection (`v` of 27 or 28) are rejected.
+    pub fn decode_with_signature(raw: &[u8]) -> Result<(Self, Signature), DecoderError> {
+        let rlp = decode_list(raw, 9)?;
+
+        let v: u64 = rlp.val_at(6)?;
+        if v < 35 {
+            return Err(DecoderError::Custom(
+                "transaction is not replay protected with EIP-155",
+            ));
+        }
+
+        let signature = Signature {
+            v: (v - 35) % 2,
+            r: decode_uint_bytes(&rlp.at(7)?)?,
+            s: decode_uint_bytes(&rlp.at(8)?)?,
+        };
+
+        Ok((Self::decode_fields(&rlp, (v - 35) / 2)?, signature))
+    }
+
+    fn encode_fields(&self, rlp_stream: &mut RlpStream) {
+        let to: Vec<u8> = self.to.map_or(vec![], |to| to.as_bytes().to_vec());
+
+        rlp_stream.append(&self.nonce);
+        rlp_stream.append(&self.gas_price);
+        rlp_stream.append(&self.gas_limit);
+        rlp_stream.append(&to);
+        rlp_stream.append(&self.value);
+        rlp_stream.append(&self.input);
+    }
+
+    fn decode_fields(rlp: &Rlp, chain_id: u64) -> Result<Self, DecoderError> {
+        Ok(Self {
+            chain_id,
+            nonce: rlp.val_at(0)?,
+            gas_price: rlp.val_at(1)?,
+            gas_limit: rlp.val_at(2)?,
+            to: decode_optional_address(&rlp.at(3)?)?,
+            value: rlp.val_at(4)?,
+            input: rlp.val_at(5)?,
+        })
+    }
+}
+
+impl EVMSignableTransaction for EVMLegacyTransaction {
+    fn build_for_signing(&self) -> Vec<u8> {
+        Self::build_for_signing(self)
+    }
+
+    fn build_with_signature(&self, signature: &Signature) -> Vec<u8> {
+        Self::build_with_signature(self, signature)
+    }
+
+    fn try_build_with_signature(&self, signature: &Signature) -> Result<Vec<u8>, EVMError> {
+        Self::try_build_with_signature(self, signature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        consensus::{SignableTransaction, TxLegacy},
+        primitives::{address, hex, Bytes, TxKind, U256},
+    };
+
+    use crate::evm::types::{Address, Signature as OmniSignature};
+    use crate::evm::{evm_legacy_transaction::EVMLegacyTransaction, EVMError};
+
+    const GAS_PRICE: u128 = 20_000_000_000;
+    const GAS_LIMIT: u128 = 21_000;
+
+    #[test]
+    fn test_build_for_signing_for_legacy_evm_against_alloy() {
+        let input: Bytes = hex!("a22cb4650000000000000000000000005eee75727d804a2b13038928d36f8b188945a57a0000000000000000000000000000000000000000000000000000000000000000").into();
+        let chain_id = 56;
+        let nonce = 7;
+        let value = 10000000000000000u128; // 0.01 BNB
+
+        let tx = TxLegacy {
+            chain_id: Some(chain_id),
+            nonce,
+            gas_price: GAS_PRICE,
+            gas_limit: GAS_LIMIT,
+            to: TxKind::Call(address!("d8dA6BF26964aF9D7eEd9e03E53415D37aA96045")),
+            value: U256::from(value),
+            input: input.clone(),
+        };
+
+        let mut tx_encoded = vec![];
+        tx.encode_for_signing(&mut tx_encoded);
+
+        // Generate using EVMLegacyTransaction
+        let tx_omni = EVMLegacyTransaction {
+            chain_id,
+            nonce,
+            to: Some(
+                "d8dA6BF26964aF9D7eEd9e03E53415D37aA96045"
+                    .parse::<Address>()
+                    .unwrap(),
+            ),
+            value,
+            input: input.to_vec(),
+            gas_limit: GAS_LIMIT,
+            gas_price: GAS_PRICE,
+        };
+
+        assert_eq!(tx_omni.build_for_signing(), tx_encoded);
+    }
+
+    #[test]
+    fn test_build_with_signature_for_legacy_evm_against_eip_155_example() {
+        // The example transaction of EIP-155, signed with the private key 0x4646...46.
+        let tx_omni = EVMLegacyTransaction {
+            chain_id: 1,
+            nonce: 9,
+            to: Some(
+                "3535353535353535353535353535353535353535"
+                    .parse::<Address>()
+                    .unwrap(),
+            ),
+            value: 1_000_000_000_000_000_000,
+            input: vec![],
+            gas_limit: GAS_LIMIT,
+            gas_price: GAS_PRICE,
+        };
+
+        assert_eq!(
+            tx_omni.build_for_signing(),
+            hex!("ec098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a764000080018080")
+        );
+
+        let signature = OmniSignature {
+            v: 0,
+            r: hex!("28ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276").to_vec(),
+            s: hex!("67cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83").to_vec(),
+        };
+
+        assert_eq!(
+            tx_omni.build_with_signature(&signature),
+            hex!("f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83")
+        );
+    }
+
+    #[test]
+    fn test_try_build_with_signature_rejects_invalid_v() {
+        let tx_omni = EVMLegacyTransaction {
+            chain_id: u64::MAX,
+            nonce: 9,
+            to: None,
+            value: 0,
+            input: vec![],
+            gas_limit: GAS_LIMIT,
+            gas_price: GAS_PRICE,
+        };
+        let signature = OmniSignature {
+            v: 0,
+            r: vec![1; 32],
+            s: vec![1; 32],
+        };
+
+        assert_eq!(
+            tx_omni.try_build_with_signature(&signature),
+            Err(EVMError::InvalidChainId(u64::MAX))
+        );
+
+        // An already encoded v is rejected instead of being encoded again
+        let tx_omni = EVMLegacyTransaction {
+            chain_id: 1,
+            ..tx_omni
+        };
+        let signature = OmniSignature { v: 37, ..signature };
+        assert_eq!(
+            tx_omni.try_build_with_signature(&signature),
+            Err(EVMError::InvalidRecoveryId(37))
+        );
+    }
+}
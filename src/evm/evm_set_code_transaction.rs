@@ -0,0 +1,274 @@
+//! EVM set code transaction
+use near_sdk::serde::{Deserialize, Serialize};
+use rlp::{DecoderError, Rlp, RlpStream};
+use schemars::JsonSchema;
+
+use crate::constants::EIP_7702_TYPE;
+
+use super::evm_signable_transaction::EVMSignableTransaction;
+use super::types::{AccessList, Address, Signature, SignedAuthorization};
+use super::utils::{
+    append_uint_bytes, decode_access_list, decode_address, decode_authorization_list,
+    decode_signature, decode_typed_list, encode_access_list,
+};
+
+/// EIP-7702 (type 4) transaction, delegating the code of the authorizing accounts.
+///
+/// Each authorization is signed by the account delegating its code, see
+/// [`Authorization::signing_hash`](super::types::Authorization::signing_hash). Set code
+/// transactions cannot create contracts, so `to` is mandatory.
+///
+/// ###### Example:
+///
+/// ```rust
+/// let authorization = Authorization {
+///     chain_id: 1,
+///     address: delegate,
+///     nonce: 0,
+/// };
+/// let payload = authorization.signing_hash();
+///
+/// let tx = EVMSetCodeTransaction {
+///     chain_id: 1,
+///     nonce: 0,
+///     to: authority,
+///     value: 0,
+///     input: vec![],
+///     gas_limit: 100_000,
+///     max_fee_per_gas: MAX_FEE_PER_GAS,
+///     max_priority_fee_per_gas: MAX_PRIORITY_FEE_PER_GAS,
+///     access_list: vec![],
+///     authorization_list: vec![authorization.into_signed(authorization_signature)],
+/// };
+/// ```
+///
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct EVMSetCodeTransaction {
+    pub chain_id: u64,
+    pub nonce: u64,
+    pub to: Address,
+    pub value: u128,
+    pub input: Vec<u8>,
+    pub gas_limit: u128,
+    pub max_fee_per_gas: u128,
+    pub max_priority_fee_per_gas: u128,
+    pub access_list: AccessList,
+    pub authorization_list: Vec<SignedAuthorization>,
+}
+
+impl EVMSetCodeTransaction {
+    pub fn build_for_signing(&self) -> Vec<u8> {
+        let mut rlp_stream = RlpStream::new();
+
+        rlp_stream.append(&EIP_7702_TYPE);
+
+        rlp_stream.begin_unbounded_list();
+
+        self.encode_fields(&mut rlp_stream);
+
+        rlp_stream.finalize_unbounded_list();
+
+        rlp_stream.out().to_vec()
+    }
+
+    pub fn build_with_signature(&self, signature: &Signature) -> Vec<u8> {
+        let mut rlp_stream = RlpStream::new();
+
+        rlp_stream.append(&EIP_7702_TYPE);
+
+        rlp_stream.begin_unbounded_list();
+
+        self.encode_fields(&mut rlp_stream);
+
+        rlp_stream.append(&signature.v);
+        append_uint_bytes(&mut rlp_stream, &signature.r);
+        append_uint_bytes(&mut rlp_stream, &signature.s);
+
+        rlp_stream.finalize_unbounded_list();
+
+        rlp_stream.out().to_vec()
+    }
+
+    /// Decodes an unsigned transaction from its signing payload, see [`Self::build_for_signing`].
+    pub fn decode_for_signing(raw: &[u8]) -> Result<Self, DecoderError> {
+        let rlp = decode_typed_list(raw, EIP_7702_TYPE, 10)?;
+
+        Self::decode_fields(&rlp)
+    }
+
+    /// Decodes a signed transaction and its signature, see [`Self::build_with_signature`].
+    pub fn decode_with_signature(raw: &[u8]) -> Result<(Self, Signature), DecoderError> {
+        let rlp = decode_typed_list(raw, EIP_7702_TYPE, 13)?;
+
+        Ok((Self::decode_fields(&rlp)?, decode_signature(&rlp, 10)?))
+    }
+
+    fn encode_fields(&self, rlp_stream: &mut RlpStream) {
+        rlp_stream.append(&self.chain_id);
+        rlp_stream.append(&self.nonce);
+        rlp_stream.append(&self.max_priority_fee_per_gas);
+        rlp_stream.append(&self.max_fee_per_gas);
+        rlp_stream.append(&self.gas_limit);
+        rlp_stream.append(&self.to.as_bytes().to_vec());
+        rlp_stream.append(&self.value);
+        rlp_stream.append(&self.input);
+
+        encode_access_list(rlp_stream, &self.access_list);
+
+        rlp_stream.begin_unbounded_list();
+        for authorization in &self.authorization_list {
+            authorization.encode(rlp_stream);
+        }
+        rlp_stream.finalize_unbounded_list();
+    }
+
+    fn decode_fields(rlp: &Rlp) -> Result<Self, DecoderError> {
+        Ok(Self {
+            chain_id: rlp.val_at(0)?,
+            nonce: rlp.val_at(1)?,
+            max_priority_fee_per_gas: rlp.val_at(2)?,
+            max_fee_per_gas: rlp.val_at(3)?,
+            gas_limit: rlp.val_at(4)?,
+            to: decode_address(&rlp.at(5)?)?,
+            value: rlp.val_at(6)?,
+            input: rlp.val_at(7)?,
+            access_list: decode_access_list(&rlp.at(8)?)?,
+            authorization_list: decode_authorization_list(&rlp.at(9)?)?,
+        })
+    }
+}
+
+impl EVMSignableTransaction for EVMSetCodeTransaction {
+    fn build_for_signing(&self) -> Vec<u8> {
+        Self::build_for_signing(self)
+    }
+
+    fn build_with_signature(&self, signature: &Signature) -> Vec<u8> {
+        Self::build_with_signature(self, signature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        consensus::{SignableTransaction, TxEip7702},
+        eips::eip7702::Authorization,
+        primitives::{address, b256, hex, Bytes, U256},
+        rpc::types::AccessList,
+    };
+    use alloy_primitives::Signature;
+
+    use crate::evm::evm_set_code_transaction::EVMSetCodeTransaction;
+    use crate::evm::types::{Authorization as OmniAuthorization, Signature as OmniSignature};
+
+    const MAX_FEE_PER_GAS: u128 = 0x4a817c800;
+    const MAX_PRIORITY_FEE_PER_GAS: u128 = 0x3b9aca00;
+
+    fn to_omni_signature(sig: &Signature) -> OmniSignature {
+        OmniSignature {
+            v: sig.v().to_u64(),
+            r: sig.r().to_be_bytes::<32>().to_vec(),
+            s: sig.s().to_be_bytes::<32>().to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_authorization_signing_hash_against_alloy() {
+        let delegate = address!("6069a6c32cf691f5982febae4faf8a6f3ab2f0f6");
+
+        let authorization = Authorization {
+            chain_id: 1,
+            address: delegate,
+            nonce: 3,
+        };
+        let omni_authorization = OmniAuthorization {
+            chain_id: 1,
+            address: delegate.0 .0.into(),
+            nonce: 3,
+        };
+
+        assert_eq!(
+            omni_authorization.signing_hash(),
+            authorization.signature_hash().0
+        );
+        assert_eq!(
+            omni_authorization.signing_hash(),
+            hex!("6ee4177fdc47240bde1c5429b01899259ca1cc4b3a1bbc7ec5b55a050340c058")
+        );
+    }
+
+    #[test]
+    fn test_build_for_signing_and_with_signature_for_set_code_evm_against_alloy() {
+        let delegate = address!("6069a6c32cf691f5982febae4faf8a6f3ab2f0f6");
+        let authority = address!("d8dA6BF26964aF9D7eEd9e03E53415D37aA96045");
+        let input: Bytes = hex!("a22cb465").into();
+
+        // The s value has a leading zero byte, which must be dropped in the authorization.
+        let authorization_sig = Signature::from_scalars_and_parity(
+            b256!("48b55bfa915ac795c431978d8a6a992b628d557da5ff759b307d495a36649353"),
+            b256!("00fb5ea2e9f0c0bd2e5c3f6c0f2c0a0b4b8a17c4a5b8a1e0b9c3d3f0e4a5b6c7"),
+            true,
+        )
+        .unwrap();
+        let authorization = Authorization {
+            chain_id: 1,
+            address: delegate,
+            nonce: 3,
+        };
+        let omni_authorization = OmniAuthorization {
+            chain_id: 1,
+            address: delegate.0 .0.into(),
+            nonce: 3,
+        };
+
+        let tx = TxEip7702 {
+            chain_id: 1,
+            nonce: 0x42,
+            gas_limit: 100_000,
+            max_fee_per_gas: MAX_FEE_PER_GAS,
+            max_priority_fee_per_gas: MAX_PRIORITY_FEE_PER_GAS,
+            to: authority,
+            value: U256::ZERO,
+            access_list: AccessList::default(),
+            authorization_list: vec![authorization.into_signed(authorization_sig)],
+            input: input.clone(),
+        };
+
+        let mut tx_encoded = vec![];
+        tx.encode_for_signing(&mut tx_encoded);
+
+        // Generate using EVMSetCodeTransaction
+        let tx_omni = EVMSetCodeTransaction {
+            chain_id: 1,
+            nonce: 0x42,
+            to: authority.0 .0.into(),
+            value: 0,
+            input: input.to_vec(),
+            gas_limit: 100_000,
+            max_fee_per_gas: MAX_FEE_PER_GAS,
+            max_priority_fee_per_gas: MAX_PRIORITY_FEE_PER_GAS,
+            access_list: vec![],
+            authorization_list: vec![
+                omni_authorization.into_signed(to_omni_signature(&authorization_sig))
+            ],
+        };
+
+        assert_eq!(tx_omni.build_for_signing(), tx_encoded);
+
+        let sig = Signature::from_scalars_and_parity(
+            b256!("840cfc572845f5786e702984c2a582528cad4b49b2a10b9db1be7fca90058565"),
+            b256!("25e7109ceb98168d95b09b18bbf6b685130e0562f233877d492b94eee0c5b6d1"),
+            false,
+        )
+        .unwrap();
+
+        let mut tx_encoded_with_signature: Vec<u8> = vec![];
+        tx.encode_with_signature(&sig, &mut tx_encoded_with_signature, false);
+
+        assert_eq!(
+            tx_omni.build_with_signature(&to_omni_signature(&sig)),
+            tx_encoded_with_signature
+        );
+    }
+}
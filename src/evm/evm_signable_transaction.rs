@@ -0,0 +1,58 @@
+//! Hashes and sender recovery shared by the EVM transaction types
+use super::error::EVMError;
+use super::types::{Address, Signature};
+use super::utils::{keccak256, recover_address};
+
+/// An EVM transaction signed by its sender over the keccak256 hash of its signing payload.
+///
+/// Every transaction type and [`EVMTxEnvelope`](super::EVMTxEnvelope) implement the encodings,
+/// the hashes and the sender recovery are derived from them.
+///
+/// ###### Example:
+///
+/// ```rust
+/// let payload = tx.signing_hash();
+///
+/// assert_eq!(tx.recover_sender(&signature)?, sender);
+/// let tx_hash = tx.tx_hash(&signature);
+/// ```
+///
+pub trait EVMSignableTransaction {
+    /// Encodes the payload signed by the sender.
+    fn build_for_signing(&self) -> Vec<u8>;
+
+    /// Encodes the signed transaction.
+    ///
+    /// Panics if the signature cannot be encoded, see [`Self::try_build_with_signature`].
+    fn build_with_signature(&self, signature: &Signature) -> Vec<u8>;
+
+    /// Encodes the signed transaction, returning an error instead of panicking.
+    ///
+    /// Only legacy transactions can fail, when the EIP-155 `v` cannot be computed.
+    fn try_build_with_signature(&self, signature: &Signature) -> Result<Vec<u8>, EVMError> {
+        Ok(self.build_with_signature(signature))
+    }
+
+    /// The keccak256 hash of the signing payload, to be signed by the sender.
+    fn signing_hash(&self) -> [u8; 32] {
+        keccak256(&self.build_for_signing())
+    }
+
+    /// The transaction hash, the keccak256 hash of the signed transaction.
+    ///
+    /// Panics like [`Self::build_with_signature`], see [`Self::try_tx_hash`].
+    fn tx_hash(&self, signature: &Signature) -> [u8; 32] {
+        keccak256(&self.build_with_signature(signature))
+    }
+
+    /// The transaction hash, returning an error instead of panicking.
+    fn try_tx_hash(&self, signature: &Signature) -> Result<[u8; 32], EVMError> {
+        self.try_build_with_signature(signature)
+            .map(|raw| keccak256(&raw))
+    }
+
+    /// Recovers the address of the sender from the signature of the transaction.
+    fn recover_sender(&self, signature: &Signature) -> Result<Address, EVMError> {
+        recover_address(&self.signing_hash(), signature)
+    }
+}
@@ -1,12 +1,16 @@
 //! EVM transaction
-use near_sdk::serde::{Deserialize, Serialize};
-use rlp::RlpStream;
+use near_sdk::serde::{de, Deserialize, Serialize};
+use rlp::{DecoderError, Rlp, RlpStream};
 use schemars::JsonSchema;
 
 use crate::constants::EIP_1559_TYPE;
 
+use super::evm_signable_transaction::EVMSignableTransaction;
 use super::types::{AccessList, Address, Signature};
-use super::utils::parse_eth_address;
+use super::utils::{
+    append_uint_bytes, decode_access_list, decode_optional_address, decode_signature,
+    decode_typed_list, encode_access_list,
+};
 
 ///
 /// ###### Example:
@@ -18,7 +22,7 @@ use super::utils::parse_eth_address;
 /// let data: Vec<u8> = vec![];
 /// let chain_id = 1;
 /// let to_address_str = "d8dA6BF26964aF9D7eEd9e03E53415D37aA96045";
-/// let to_address = Some(parse_eth_address(to_address_str));
+/// let to_address = Some(to_address_str.parse::<OmniAddress>().unwrap());
 /// // Generate using EVMTransaction
 /// let tx = EVMTransaction {
 ///     chain_id,
@@ -72,17 +76,30 @@ impl EVMTransaction {
         self.encode_fields(&mut rlp_stream);
 
         rlp_stream.append(&signature.v);
-        rlp_stream.append(&signature.r);
-        rlp_stream.append(&signature.s);
+        append_uint_bytes(&mut rlp_stream, &signature.r);
+        append_uint_bytes(&mut rlp_stream, &signature.s);
 
         rlp_stream.finalize_unbounded_list();
 
         rlp_stream.out().to_vec()
     }
 
+    /// Decodes an unsigned transaction from its signing payload, see [`Self::build_for_signing`].
+    pub fn decode_for_signing(raw: &[u8]) -> Result<Self, DecoderError> {
+        let rlp = decode_typed_list(raw, EIP_1559_TYPE, 9)?;
+
+        Self::decode_fields(&rlp)
+    }
+
+    /// Decodes a signed transaction and its signature, see [`Self::build_with_signature`].
+    pub fn decode_with_signature(raw: &[u8]) -> Result<(Self, Signature), DecoderError> {
+        let rlp = decode_typed_list(raw, EIP_1559_TYPE, 12)?;
+
+        Ok((Self::decode_fields(&rlp)?, decode_signature(&rlp, 9)?))
+    }
+
     fn encode_fields(&self, rlp_stream: &mut RlpStream) {
-        let to: Vec<u8> = self.to.map_or(vec![], |to| to.to_vec());
-        let access_list = self.access_list.clone();
+        let to: Vec<u8> = self.to.map_or(vec![], |to| to.as_bytes().to_vec());
 
         rlp_stream.append(&self.chain_id);
         rlp_stream.append(&self.nonce);
@@ -93,35 +110,32 @@ impl EVMTransaction {
         rlp_stream.append(&self.value);
         rlp_stream.append(&self.input);
 
-        // Write access list.
-        {
-            rlp_stream.begin_unbounded_list();
-            for access in access_list {
-                rlp_stream.begin_unbounded_list();
-                rlp_stream.append(&access.0.to_vec());
-                // Append list of storage keys.
-                {
-                    rlp_stream.begin_unbounded_list();
-                    for storage_key in access.1 {
-                        rlp_stream.append(&storage_key.to_vec());
-                    }
-                    rlp_stream.finalize_unbounded_list();
-                }
-                rlp_stream.finalize_unbounded_list();
-            }
-            rlp_stream.finalize_unbounded_list();
-        }
+        encode_access_list(rlp_stream, &self.access_list);
+    }
+
+    fn decode_fields(rlp: &Rlp) -> Result<Self, DecoderError> {
+        Ok(Self {
+            chain_id: rlp.val_at(0)?,
+            nonce: rlp.val_at(1)?,
+            max_priority_fee_per_gas: rlp.val_at(2)?,
+            max_fee_per_gas: rlp.val_at(3)?,
+            gas_limit: rlp.val_at(4)?,
+            to: decode_optional_address(&rlp.at(5)?)?,
+            value: rlp.val_at(6)?,
+            input: rlp.val_at(7)?,
+            access_list: decode_access_list(&rlp.at(8)?)?,
+        })
     }
 
     pub fn from_json(json: &str) -> Result<Self, near_sdk::serde_json::Error> {
         let v: near_sdk::serde_json::Value = near_sdk::serde_json::from_str(json)?;
 
-        let to = v["to"].as_str().unwrap_or_default().to_string();
-
-        let to_parsed = parse_eth_address(
-            to.strip_prefix("0x")
-                .unwrap_or("0000000000000000000000000000000000000000"),
-        );
+        let to_parsed = match v["to"].as_str() {
+            Some(to) => to
+                .parse()
+                .map_err(<near_sdk::serde_json::Error as de::Error>::custom)?,
+            None => Address::default(),
+        };
 
         let nonce_str = v["nonce"].as_str().expect("nonce should be provided");
         let nonce = parse_u64(nonce_str).expect("nonce should be a valid u64");
@@ -168,6 +182,16 @@ impl EVMTransaction {
     }
 }
 
+impl EVMSignableTransaction for EVMTransaction {
+    fn build_for_signing(&self) -> Vec<u8> {
+        Self::build_for_signing(self)
+    }
+
+    fn build_with_signature(&self, signature: &Signature) -> Vec<u8> {
+        Self::build_with_signature(self, signature)
+    }
+}
+
 fn parse_u64(value: &str) -> Result<u64, std::num::ParseIntError> {
     value.strip_prefix("0x").map_or_else(
         || value.parse::<u64>(),
@@ -192,8 +216,8 @@ mod tests {
     };
     use alloy_primitives::{b256, Signature};
 
-    use crate::evm::types::Signature as OmniSignature;
-    use crate::evm::{evm_transaction::EVMTransaction, utils::parse_eth_address};
+    use crate::evm::types::{Address as OmniAddress, Signature as OmniSignature};
+    use crate::evm::{evm_transaction::EVMTransaction, EVMSignableTransaction};
     const MAX_FEE_PER_GAS: u128 = 20_000_000_000;
     const MAX_PRIORITY_FEE_PER_GAS: u128 = 1_000_000_000;
     const GAS_LIMIT: u128 = 21_000;
@@ -206,7 +230,7 @@ mod tests {
         let data: Vec<u8> = vec![];
         let chain_id = 1;
         let to_address_str = "d8dA6BF26964aF9D7eEd9e03E53415D37aA96045";
-        let to_address = Some(parse_eth_address(to_address_str));
+        let to_address = Some(to_address_str.parse::<OmniAddress>().unwrap());
 
         // Generate using EVMTransaction
         let tx = EVMTransaction {
@@ -255,7 +279,7 @@ mod tests {
         let value = 10000000000000000u128; // 0.01 ETH
         let chain_id = 1;
         let to_address_str = "d8dA6BF26964aF9D7eEd9e03E53415D37aA96045";
-        let to_address = Some(parse_eth_address(to_address_str));
+        let to_address = Some(to_address_str.parse::<OmniAddress>().unwrap());
 
         // Generate using EVMTransaction
         let tx = EVMTransaction {
@@ -305,7 +329,7 @@ mod tests {
 
         let to_str = "6069a6c32cf691f5982febae4faf8a6f3ab2f0f6";
         let to = address!("6069a6c32cf691f5982febae4faf8a6f3ab2f0f6").into();
-        let to_address = Some(parse_eth_address(to_str));
+        let to_address = Some(to_str.parse::<OmniAddress>().unwrap());
         let value_as_128 = 0_u128;
         let value = U256::from(value_as_128);
 
@@ -390,9 +414,9 @@ mod tests {
         assert_eq!(
             evm_tx1.to,
             Some(
-                address!("525521d79134822a342d330bd91DA67976569aF1")
-                    .0
-                    .into()
+                "525521d79134822a342d330bd91DA67976569aF1"
+                    .parse::<OmniAddress>()
+                    .unwrap()
             )
         );
         assert_eq!(evm_tx1.value, 0x038d7ea4c68000);
@@ -419,9 +443,9 @@ mod tests {
         assert_eq!(
             evm_tx2.to,
             Some(
-                address!("525521d79134822a342d330bd91DA67976569aF1")
-                    .0
-                    .into()
+                "525521d79134822a342d330bd91DA67976569aF1"
+                    .parse::<OmniAddress>()
+                    .unwrap()
             )
         );
         assert_eq!(evm_tx2.value, 0);
@@ -430,5 +454,64 @@ mod tests {
             hex!("6a627842000000000000000000000000525521d79134822a342d330bd91DA67976569aF1")
                 .to_vec()
         );
+
+        // Addresses are parsed like `Address::from_str`, checksums included
+        let tx3 = tx1.replace("976569aF1", "976569af1");
+        assert!(EVMTransaction::from_json(&tx3).is_err());
+    }
+
+    #[test]
+    fn test_signing_hash_and_tx_hash_for_evm_against_alloy() {
+        let to = address!("6069a6c32cf691f5982febae4faf8a6f3ab2f0f6");
+        let input: Bytes = hex!("a22cb4650000000000000000000000005eee75727d804a2b13038928d36f8b188945a57a0000000000000000000000000000000000000000000000000000000000000000").into();
+
+        let tx: TxEip1559 = TxEip1559 {
+            chain_id: 1,
+            nonce: 0x42,
+            gas_limit: 44386,
+            to: to.into(),
+            value: U256::ZERO,
+            input: input.clone(),
+            max_fee_per_gas: 0x4a817c800,
+            max_priority_fee_per_gas: 0x3b9aca00,
+            access_list: AccessList::default(),
+        };
+
+        let tx_omni = EVMTransaction {
+            chain_id: 1,
+            nonce: 0x42,
+            to: Some(to.0 .0.into()),
+            value: 0,
+            input: input.to_vec(),
+            gas_limit: 44386,
+            max_fee_per_gas: 0x4a817c800,
+            max_priority_fee_per_gas: 0x3b9aca00,
+            access_list: vec![],
+        };
+
+        assert_eq!(tx_omni.signing_hash(), tx.signature_hash().0);
+        assert_eq!(
+            tx_omni.signing_hash(),
+            hex!("0d5688ac3897124635b6cf1bc0e29d6dfebceebdc10a54d74f2ef8b56535b682")
+        );
+
+        let sig = Signature::from_scalars_and_parity(
+            b256!("840cfc572845f5786e702984c2a582528cad4b49b2a10b9db1be7fca90058565"),
+            b256!("25e7109ceb98168d95b09b18bbf6b685130e0562f233877d492b94eee0c5b6d1"),
+            false,
+        )
+        .unwrap();
+
+        let signature: OmniSignature = OmniSignature {
+            v: sig.v().to_u64(),
+            r: sig.r().to_be_bytes::<32>().to_vec(),
+            s: sig.s().to_be_bytes::<32>().to_vec(),
+        };
+
+        assert_eq!(tx_omni.tx_hash(&signature), tx.into_signed(sig).hash().0);
+        assert_eq!(
+            tx_omni.tx_hash(&signature),
+            hex!("0ec0b6a2df4d87424e5f6ad2a654e27aaeb7dac20ae9e8385cc09087ad532ee0")
+        );
     }
 }
@@ -120,7 +120,7 @@ mod tests {
     };
 
     use crate::{
-        evm::{evm_transaction_builder::EVMTransactionBuilder, utils::parse_eth_address},
+        evm::{evm_transaction_builder::EVMTransactionBuilder, types::Address as OmniAddress},
         transaction_builder::TxBuilder,
     };
 
@@ -136,7 +136,7 @@ mod tests {
         let data: Vec<u8> = vec![];
         let chain_id = 1;
         let to_address_str = "d8dA6BF26964aF9D7eEd9e03E53415D37aA96045";
-        let to_address = parse_eth_address(to_address_str);
+        let to_address = to_address_str.parse::<OmniAddress>().unwrap();
 
         // Generate using EVMTransactionBuilder
         let tx_1 = EVMTransactionBuilder::new()
@@ -184,7 +184,7 @@ mod tests {
         let value = 10000000000000000u128; // 0.01 ETH
         let chain_id = 1;
         let to_address_str = "d8dA6BF26964aF9D7eEd9e03E53415D37aA96045";
-        let to_address = parse_eth_address(to_address_str);
+        let to_address = to_address_str.parse::<OmniAddress>().unwrap();
 
         // Generate using EVMTransactionBuilder
         let evm_transaction = EVMTransactionBuilder::new()
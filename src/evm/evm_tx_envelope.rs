@@ -0,0 +1,551 @@
+//! EVM transaction envelope
+use near_sdk::serde::{Deserialize, Serialize};
+use rlp::DecoderError;
+use schemars::JsonSchema;
+
+use crate::constants::{EIP_1559_TYPE, EIP_2930_TYPE, EIP_4844_TYPE, EIP_7702_TYPE};
+
+use super::error::EVMError;
+use super::evm_signable_transaction::EVMSignableTransaction;
+use super::types::Signature;
+use super::{
+    EVMAccessListTransaction, EVMBlobTransaction, EVMLegacyTransaction, EVMSetCodeTransaction,
+    EVMTransaction,
+};
+
+/// Any of the supported EVM transaction types, identified by their EIP-2718 type prefix.
+///
+/// Blob transactions are decoded in their canonical form, as included in blocks. Use
+/// [`EVMBlobTransaction::decode_with_sidecar`] for the network form sent to
+/// `eth_sendRawTransaction`.
+///
+/// ###### Example:
+///
+/// ```rust
+/// let (envelope, signature) = EVMTxEnvelope::decode_with_signature(&raw_tx)?;
+///
+/// if let EVMTxEnvelope::Eip1559(tx) = &envelope {
+///     println!("transfer of {} wei to {:?}", tx.value, tx.to);
+/// }
+///
+/// assert_eq!(envelope.build_with_signature(&signature), raw_tx);
+/// ```
+///
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub enum EVMTxEnvelope {
+    Legacy(EVMLegacyTransaction),
+    Eip2930(EVMAccessListTransaction),
+    Eip1559(EVMTransaction),
+    Eip4844(EVMBlobTransaction),
+    Eip7702(EVMSetCodeTransaction),
+}
+
+impl EVMTxEnvelope {
+    /// The EIP-2718 transaction type, 0 for legacy transactions.
+    pub const fn tx_type(&self) -> u8 {
+        match self {
+            Self::Legacy(_) => 0,
+            Self::Eip2930(_) => EIP_2930_TYPE,
+            Self::Eip1559(_) => EIP_1559_TYPE,
+            Self::Eip4844(_) => EIP_4844_TYPE,
+            Self::Eip7702(_) => EIP_7702_TYPE,
+        }
+    }
+
+    pub const fn chain_id(&self) -> u64 {
+        match self {
+            Self::Legacy(tx) => tx.chain_id,
+            Self::Eip2930(tx) => tx.chain_id,
+            Self::Eip1559(tx) => tx.chain_id,
+            Self::Eip4844(tx) => tx.chain_id,
+            Self::Eip7702(tx) => tx.chain_id,
+        }
+    }
+
+    pub const fn nonce(&self) -> u64 {
+        match self {
+            Self::Legacy(tx) => tx.nonce,
+            Self::Eip2930(tx) => tx.nonce,
+            Self::Eip1559(tx) => tx.nonce,
+            Self::Eip4844(tx) => tx.nonce,
+            Self::Eip7702(tx) => tx.nonce,
+        }
+    }
+
+    pub fn build_for_signing(&self) -> Vec<u8> {
+        match self {
+            Self::Legacy(tx) => tx.build_for_signing(),
+            Self::Eip2930(tx) => tx.build_for_signing(),
+            Self::Eip1559(tx) => tx.build_for_signing(),
+            Self::Eip4844(tx) => tx.build_for_signing(),
+            Self::Eip7702(tx) => tx.build_for_signing(),
+        }
+    }
+
+    /// Encodes the signed transaction.
+    ///
+    /// Panics if a legacy transaction cannot compute its EIP-155 `v`, see
+    /// [`Self::try_build_with_signature`].
+    pub fn build_with_signature(&self, signature: &Signature) -> Vec<u8> {
+        self.try_build_with_signature(signature)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Encodes the signed transaction, returning an error instead of panicking.
+    pub fn try_build_with_signature(&self, signature: &Signature) -> Result<Vec<u8>, EVMError> {
+        match self {
+            Self::Legacy(tx) => tx.try_build_with_signature(signature),
+            Self::Eip2930(tx) => Ok(tx.build_with_signature(signature)),
+            Self::Eip1559(tx) => Ok(tx.build_with_signature(signature)),
+            Self::Eip4844(tx) => Ok(tx.build_with_signature(signature)),
+            Self::Eip7702(tx) => Ok(tx.build_with_signature(signature)),
+        }
+    }
+
+    /// Decodes an unsigned transaction from its signing payload, see [`Self::build_for_signing`].
+    pub fn decode_for_signing(raw: &[u8]) -> Result<Self, DecoderError> {
+        match Self::decode_type(raw)? {
+            0 => EVMLegacyTransaction::decode_for_signing(raw).map(Self::Legacy),
+            EIP_2930_TYPE => EVMAccessListTransaction::decode_for_signing(raw).map(Self::Eip2930),
+            EIP_1559_TYPE => EVMTransaction::decode_for_signing(raw).map(Self::Eip1559),
+            EIP_4844_TYPE => EVMBlobTransaction::decode_for_signing(raw).map(Self::Eip4844),
+            _ => EVMSetCodeTransaction::decode_for_signing(raw).map(Self::Eip7702),
+        }
+    }
+
+    /// Decodes a signed transaction and its signature, see [`Self::build_with_signature`].
+    ///
+    /// The returned `signature.v` is the y parity (0 or 1) for every transaction type,
+    /// including legacy transactions.
+    pub fn decode_with_signature(raw: &[u8]) -> Result<(Self, Signature), DecoderError> {
+        match Self::decode_type(raw)? {
+            0 => EVMLegacyTransaction::decode_with_signature(raw)
+                .map(|(tx, signature)| (Self::Legacy(tx), signature)),
+            EIP_2930_TYPE => EVMAccessListTransaction::decode_with_signature(raw)
+                .map(|(tx, signature)| (Self::Eip2930(tx), signature)),
+            EIP_1559_TYPE => EVMTransaction::decode_with_signature(raw)
+                .map(|(tx, signature)| (Self::Eip1559(tx), signature)),
+            EIP_4844_TYPE => EVMBlobTransaction::decode_with_signature(raw)
+                .map(|(tx, signature)| (Self::Eip4844(tx), signature)),
+            _ => EVMSetCodeTransaction::decode_with_signature(raw)
+                .map(|(tx, signature)| (Self::Eip7702(tx), signature)),
+        }
+    }
+
+    /// Reads the transaction type from the first byte, legacy transactions start with an RLP
+    /// list header instead.
+    fn decode_type(raw: &[u8]) -> Result<u8, DecoderError> {
+        match raw.first() {
+            None => Err(DecoderError::RlpIsTooShort),
+            Some(&byte) if byte >= 0xc0 => Ok(0),
+            Some(&byte)
+                if [EIP_2930_TYPE, EIP_1559_TYPE, EIP_4844_TYPE, EIP_7702_TYPE].contains(&byte) =>
+            {
+                Ok(byte)
+            }
+            Some(_) => Err(DecoderError::Custom("unsupported transaction type")),
+        }
+    }
+}
+
+impl EVMSignableTransaction for EVMTxEnvelope {
+    fn build_for_signing(&self) -> Vec<u8> {
+        Self::build_for_signing(self)
+    }
+
+    fn build_with_signature(&self, signature: &Signature) -> Vec<u8> {
+        Self::build_with_signature(self, signature)
+    }
+
+    fn try_build_with_signature(&self, signature: &Signature) -> Result<Vec<u8>, EVMError> {
+        Self::try_build_with_signature(self, signature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        consensus::{
+            SignableTransaction, TxEip1559, TxEip2930, TxEip4844, TxEip4844Variant, TxEip7702,
+            TxEnvelope, TxLegacy,
+        },
+        eips::{eip2718::Encodable2718, eip7702::Authorization as AlloyAuthorization},
+        primitives::{TxKind, B256, U256},
+        rpc::types::{AccessList as AlloyAccessList, AccessListItem},
+    };
+    use rlp::DecoderError;
+
+    use super::EVMTxEnvelope;
+    use crate::evm::test_utils::{sign, to_alloy_signature, SENDER};
+    use crate::evm::types::{AccessList, Address, Authorization, Signature};
+    use crate::evm::{
+        EVMAccessListTransaction, EVMBlobTransaction, EVMError, EVMLegacyTransaction,
+        EVMSetCodeTransaction, EVMSignableTransaction, EVMTransaction,
+    };
+
+    const EIP_155_EXAMPLE: &str = "f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83";
+
+    fn signature(v: u64) -> Signature {
+        // The s value has a leading zero byte, which is dropped in the encoding.
+        let mut s = [0x5au8; 32];
+        s[0] = 0;
+
+        Signature {
+            v,
+            r: [0x84u8; 32].to_vec(),
+            s: s.to_vec(),
+        }
+    }
+
+    fn envelopes() -> Vec<EVMTxEnvelope> {
+        let to = "d8dA6BF26964aF9D7eEd9e03E53415D37aA96045"
+            .parse::<Address>()
+            .unwrap();
+        let access_list = vec![(to, vec![[1u8; 32], [2u8; 32]])];
+
+        vec![
+            EVMTxEnvelope::Legacy(EVMLegacyTransaction {
+                chain_id: 56,
+                nonce: 7,
+                to: None,
+                value: 0,
+                input: vec![0x60, 0x80, 0x60, 0x40],
+                gas_limit: 1_000_000,
+                gas_price: 3_000_000_000,
+            }),
+            EVMTxEnvelope::Eip2930(EVMAccessListTransaction {
+                chain_id: 1,
+                nonce: 0,
+                to: Some(to),
+                value: 10_000_000_000_000_000,
+                input: vec![],
+                gas_limit: 30_000,
+                gas_price: 20_000_000_000,
+                access_list: access_list.clone(),
+            }),
+            EVMTxEnvelope::Eip1559(EVMTransaction {
+                chain_id: 10,
+                nonce: 42,
+                to: Some(to),
+                value: 1,
+                input: vec![0xa9, 0x05, 0x9c, 0xbb],
+                gas_limit: 21_000,
+                max_fee_per_gas: 20_000_000_000,
+                max_priority_fee_per_gas: 1_000_000_000,
+                access_list: vec![],
+            }),
+            EVMTxEnvelope::Eip4844(EVMBlobTransaction {
+                chain_id: 1,
+                nonce: 3,
+                to,
+                value: 0,
+                input: vec![],
+                gas_limit: 21_000,
+                max_fee_per_gas: 20_000_000_000,
+                max_priority_fee_per_gas: 1_000_000_000,
+                access_list: access_list.clone(),
+                max_fee_per_blob_gas: 1_000_000,
+                blob_versioned_hashes: vec![[1u8; 32]],
+            }),
+            EVMTxEnvelope::Eip7702(EVMSetCodeTransaction {
+                chain_id: 1,
+                nonce: 4,
+                to,
+                value: 0,
+                input: vec![],
+                gas_limit: 100_000,
+                max_fee_per_gas: 20_000_000_000,
+                max_priority_fee_per_gas: 1_000_000_000,
+                access_list,
+                authorization_list: vec![Authorization {
+                    chain_id: 0,
+                    address: to,
+                    nonce: 5,
+                }
+                .into_signed(signature(1))],
+            }),
+        ]
+    }
+
+    /// The alloy transaction signed with `signature`, with the same fields as `envelope`.
+    fn to_alloy(envelope: &EVMTxEnvelope, signature: &Signature) -> TxEnvelope {
+        let address = |address: &Address| alloy::primitives::Address::from(address.0);
+        let to = |to: &Option<Address>| to.as_ref().map_or(TxKind::Create, |to| address(to).into());
+        let access_list = |access_list: &AccessList| {
+            AlloyAccessList(
+                access_list
+                    .iter()
+                    .map(|(contract, storage_keys)| AccessListItem {
+                        address: address(contract),
+                        storage_keys: storage_keys.iter().copied().map(B256::from).collect(),
+                    })
+                    .collect(),
+            )
+        };
+        let sig = to_alloy_signature(signature);
+
+        match envelope {
+            EVMTxEnvelope::Legacy(tx) => TxLegacy {
+                chain_id: Some(tx.chain_id),
+                nonce: tx.nonce,
+                gas_price: tx.gas_price,
+                gas_limit: tx.gas_limit,
+                to: to(&tx.to),
+                value: U256::from(tx.value),
+                input: tx.input.clone().into(),
+            }
+            .into_signed(sig)
+            .into(),
+            EVMTxEnvelope::Eip2930(tx) => TxEip2930 {
+                chain_id: tx.chain_id,
+                nonce: tx.nonce,
+                gas_price: tx.gas_price,
+                gas_limit: tx.gas_limit,
+                to: to(&tx.to),
+                value: U256::from(tx.value),
+                access_list: access_list(&tx.access_list),
+                input: tx.input.clone().into(),
+            }
+            .into_signed(sig)
+            .into(),
+            EVMTxEnvelope::Eip1559(tx) => TxEip1559 {
+                chain_id: tx.chain_id,
+                nonce: tx.nonce,
+                gas_limit: tx.gas_limit,
+                max_fee_per_gas: tx.max_fee_per_gas,
+                max_priority_fee_per_gas: tx.max_priority_fee_per_gas,
+                to: to(&tx.to),
+                value: U256::from(tx.value),
+                access_list: access_list(&tx.access_list),
+                input: tx.input.clone().into(),
+            }
+            .into_signed(sig)
+            .into(),
+            EVMTxEnvelope::Eip4844(tx) => TxEip4844Variant::TxEip4844(TxEip4844 {
+                chain_id: tx.chain_id,
+                nonce: tx.nonce,
+                gas_limit: tx.gas_limit,
+                max_fee_per_gas: tx.max_fee_per_gas,
+                max_priority_fee_per_gas: tx.max_priority_fee_per_gas,
+                to: address(&tx.to),
+                value: U256::from(tx.value),
+                access_list: access_list(&tx.access_list),
+                blob_versioned_hashes: tx
+                    .blob_versioned_hashes
+                    .iter()
+                    .copied()
+                    .map(B256::from)
+                    .collect(),
+                max_fee_per_blob_gas: tx.max_fee_per_blob_gas,
+                input: tx.input.clone().into(),
+            })
+            .into_signed(sig)
+            .into(),
+            EVMTxEnvelope::Eip7702(tx) => TxEip7702 {
+                chain_id: tx.chain_id,
+                nonce: tx.nonce,
+                gas_limit: tx.gas_limit,
+                max_fee_per_gas: tx.max_fee_per_gas,
+                max_priority_fee_per_gas: tx.max_priority_fee_per_gas,
+                to: address(&tx.to),
+                value: U256::from(tx.value),
+                access_list: access_list(&tx.access_list),
+                authorization_list: tx
+                    .authorization_list
+                    .iter()
+                    .map(|signed| {
+                        AlloyAuthorization {
+                            chain_id: signed.authorization.chain_id,
+                            address: address(&signed.authorization.address),
+                            nonce: signed.authorization.nonce,
+                        }
+                        .into_signed(to_alloy_signature(&signed.signature))
+                    })
+                    .collect(),
+                input: tx.input.clone().into(),
+            }
+            .into_signed(sig)
+            .into(),
+        }
+    }
+
+    #[test]
+    fn test_signed_transactions_against_alloy() {
+        for envelope in envelopes() {
+            // A signature by a known key, and one whose s has a leading zero byte
+            for signature in [sign(&envelope.signing_hash()), signature(1)] {
+                let alloy_tx = to_alloy(&envelope, &signature);
+
+                assert_eq!(
+                    envelope.build_with_signature(&signature),
+                    alloy_tx.encoded_2718()
+                );
+                assert_eq!(envelope.tx_hash(&signature), alloy_tx.tx_hash().0);
+            }
+
+            let signature = sign(&envelope.signing_hash());
+            let sender = to_alloy(&envelope, &signature).recover_signer().unwrap();
+
+            assert_eq!(sender.0 .0, SENDER.parse::<Address>().unwrap().0);
+            assert_eq!(envelope.recover_sender(&signature), Ok(sender.0 .0.into()));
+        }
+    }
+
+    #[test]
+    fn test_try_build_with_signature_for_invalid_legacy_v() {
+        let EVMTxEnvelope::Legacy(tx) = envelopes().remove(0) else {
+            unreachable!();
+        };
+        let envelope = EVMTxEnvelope::Legacy(EVMLegacyTransaction {
+            chain_id: u64::MAX,
+            ..tx
+        });
+
+        assert_eq!(
+            envelope.try_build_with_signature(&signature(1)),
+            Err(EVMError::InvalidChainId(u64::MAX))
+        );
+        assert_eq!(
+            envelope.try_tx_hash(&signature(1)),
+            Err(EVMError::InvalidChainId(u64::MAX))
+        );
+        assert_eq!(
+            envelopes()[0].try_tx_hash(&signature(27)),
+            Err(EVMError::InvalidRecoveryId(27))
+        );
+
+        // Typed transactions encode any y parity
+        assert_eq!(
+            envelopes()[2].try_tx_hash(&signature(1)),
+            Ok(envelopes()[2].tx_hash(&signature(1)))
+        );
+    }
+
+    #[test]
+    fn test_round_trip_for_signing() {
+        for envelope in envelopes() {
+            let encoded = envelope.build_for_signing();
+            let decoded = EVMTxEnvelope::decode_for_signing(&encoded).unwrap();
+
+            assert_eq!(decoded.tx_type(), envelope.tx_type());
+            assert_eq!(decoded.build_for_signing(), encoded);
+        }
+    }
+
+    #[test]
+    fn test_round_trip_with_signature() {
+        for envelope in envelopes() {
+            let signature = signature(1);
+            let encoded = envelope.build_with_signature(&signature);
+            let (decoded, decoded_signature) =
+                EVMTxEnvelope::decode_with_signature(&encoded).unwrap();
+
+            assert_eq!(decoded.tx_type(), envelope.tx_type());
+            assert_eq!(decoded.chain_id(), envelope.chain_id());
+            assert_eq!(decoded.nonce(), envelope.nonce());
+            assert_eq!(decoded_signature, signature);
+            assert_eq!(decoded.build_with_signature(&decoded_signature), encoded);
+        }
+    }
+
+    #[test]
+    fn test_decode_eip_155_example() {
+        let raw = hex::decode(EIP_155_EXAMPLE).unwrap();
+
+        let (envelope, signature) = EVMTxEnvelope::decode_with_signature(&raw).unwrap();
+
+        let EVMTxEnvelope::Legacy(tx) = &envelope else {
+            panic!("expected a legacy transaction");
+        };
+        assert_eq!(tx.chain_id, 1);
+        assert_eq!(tx.nonce, 9);
+        assert_eq!(
+            tx.to,
+            Some(
+                "3535353535353535353535353535353535353535"
+                    .parse::<Address>()
+                    .unwrap()
+            )
+        );
+        assert_eq!(tx.value, 1_000_000_000_000_000_000);
+        assert_eq!(tx.gas_price, 20_000_000_000);
+        assert_eq!(tx.gas_limit, 21_000);
+        assert_eq!(signature.v, 0);
+        assert_eq!(
+            hex::encode(&signature.r),
+            "28ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276"
+        );
+        assert_eq!(envelope.build_with_signature(&signature), raw);
+
+        assert_eq!(
+            hex::encode(envelope.signing_hash()),
+            "daf5a779ae972f972197303d7b574746c7ef83eadac0f2791ad23db92e4c8e53"
+        );
+        assert_eq!(
+            hex::encode(envelope.tx_hash(&signature)),
+            "33469b22e9f636356c4160a87eb19df52b7412e8eac32a4a55ffe88ea8350788"
+        );
+    }
+
+    #[test]
+    fn test_decode_blob_transaction_with_sidecar() {
+        let EVMTxEnvelope::Eip4844(tx) = envelopes().remove(3) else {
+            unreachable!();
+        };
+        let sidecar = crate::evm::types::BlobSidecar {
+            blobs: vec![vec![0x11; 64]],
+            commitments: vec![[0x22; 48]],
+            proofs: vec![[0x33; 48]],
+        };
+
+        let raw = tx.build_with_sidecar(&signature(0), &sidecar);
+        let (decoded, decoded_signature, decoded_sidecar) =
+            EVMBlobTransaction::decode_with_sidecar(&raw).unwrap();
+
+        assert_eq!(decoded_signature, signature(0));
+        assert_eq!(decoded_sidecar, sidecar);
+        assert_eq!(
+            decoded.build_with_sidecar(&decoded_signature, &sidecar),
+            raw
+        );
+        assert!(EVMTxEnvelope::decode_with_signature(&raw).is_err());
+    }
+
+    #[test]
+    fn test_decode_invalid_transactions() {
+        let raw = hex::decode(EIP_155_EXAMPLE).unwrap();
+
+        // Trailing bytes
+        let mut trailing = raw.clone();
+        trailing.push(0);
+        assert_eq!(
+            EVMTxEnvelope::decode_with_signature(&trailing).unwrap_err(),
+            DecoderError::RlpInconsistentLengthAndData
+        );
+
+        // Unknown transaction type
+        let mut unknown = raw;
+        unknown.insert(0, 0x7f);
+        assert_eq!(
+            EVMTxEnvelope::decode_with_signature(&unknown).unwrap_err(),
+            DecoderError::Custom("unsupported transaction type")
+        );
+
+        // Unsigned payload decoded as a signed transaction
+        let envelope = envelopes().remove(2);
+        assert_eq!(
+            EVMTxEnvelope::decode_with_signature(&envelope.build_for_signing()).unwrap_err(),
+            DecoderError::RlpIncorrectListLen
+        );
+
+        // Legacy transaction without replay protection (v = 27)
+        let pre_eip_155 = hex::decode("f85f800182520894095e7baea6a6c7c4c2dfeb977efac326af552d870a801ba048b55bfa915ac795c431978d8a6a992b628d557da5ff759b307d495a36649353a01fffd310ac743f371de3b9f7f9cb56c0b28ad43601b4ab949f53faa07bd2c804").unwrap();
+        assert!(EVMTxEnvelope::decode_with_signature(&pre_eip_155).is_err());
+
+        assert_eq!(
+            EVMTxEnvelope::decode_with_signature(&[]).unwrap_err(),
+            DecoderError::RlpIsTooShort
+        );
+    }
+}
@@ -1,10 +1,34 @@
 //! Transaction builder, encoders, types and utilities for EVM.
+pub mod abi;
+mod error;
+mod evm_access_list_transaction;
+mod evm_blob_transaction;
+mod evm_legacy_transaction;
+mod evm_set_code_transaction;
+mod evm_signable_transaction;
 mod evm_transaction;
 mod evm_transaction_builder;
+mod evm_tx_envelope;
+#[cfg(test)]
+mod test_utils;
 pub mod types;
 pub mod utils;
 
+/// EVM error
+pub use error::EVMError;
+/// EVM access list transaction
+pub use evm_access_list_transaction::EVMAccessListTransaction;
+/// EVM blob transaction
+pub use evm_blob_transaction::EVMBlobTransaction;
+/// EVM legacy transaction
+pub use evm_legacy_transaction::EVMLegacyTransaction;
+/// EVM set code transaction
+pub use evm_set_code_transaction::EVMSetCodeTransaction;
+/// EVM signable transaction
+pub use evm_signable_transaction::EVMSignableTransaction;
 /// EVM transaction
 pub use evm_transaction::EVMTransaction;
 /// EVM transaction builder
 pub use evm_transaction_builder::EVMTransactionBuilder;
+/// EVM transaction envelope
+pub use evm_tx_envelope::EVMTxEnvelope;
@@ -0,0 +1,39 @@
+//! Signing fixture shared by the EVM tests
+use alloy_primitives::B256;
+use k256::ecdsa::{RecoveryId, Signature as EcdsaSignature, SigningKey};
+
+use super::types::Signature;
+
+/// The private key of the EIP-155 example, 0x4646...46.
+pub(crate) const PRIVATE_KEY: [u8; 32] = [0x46; 32];
+/// The address of [`PRIVATE_KEY`].
+pub(crate) const SENDER: &str = "9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f";
+
+/// Signs `hash` with [`PRIVATE_KEY`], returning the low `s` signature and its recovery id.
+pub(crate) fn sign_prehash(hash: &[u8; 32]) -> (EcdsaSignature, RecoveryId) {
+    SigningKey::from_bytes(&PRIVATE_KEY.into())
+        .unwrap()
+        .sign_prehash_recoverable(hash)
+        .unwrap()
+}
+
+/// Signs `hash` with [`PRIVATE_KEY`], with the recovery id as `v`.
+pub(crate) fn sign(hash: &[u8; 32]) -> Signature {
+    let (signature, recovery_id) = sign_prehash(hash);
+
+    Signature {
+        v: u64::from(recovery_id.to_byte()),
+        r: signature.r().to_bytes().to_vec(),
+        s: signature.s().to_bytes().to_vec(),
+    }
+}
+
+/// Converts `signature` to an alloy signature, to compare against alloy.
+pub(crate) fn to_alloy_signature(signature: &Signature) -> alloy_primitives::Signature {
+    alloy_primitives::Signature::from_scalars_and_parity(
+        B256::from_slice(&signature.r),
+        B256::from_slice(&signature.s),
+        signature.v == 1,
+    )
+    .unwrap()
+}
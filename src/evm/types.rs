@@ -1,15 +1,575 @@
 //! Types used by the EVM transaction builder.
-use near_sdk::serde::{Deserialize, Serialize};
+use k256::ecdsa::Signature as K256Signature;
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use near_sdk::serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use rlp::{DecoderError, Rlp, RlpStream};
 use schemars::JsonSchema;
+use std::fmt;
+use std::str::FromStr;
 
-pub type Address = [u8; 20];
+use crate::constants::EIP_7702_AUTH_MAGIC;
+use crate::signer::types::SignatureResponse;
+
+use super::error::EVMError;
+use super::utils::{
+    append_uint_bytes, decode_address, decode_signature, keccak256, kzg_to_versioned_hash,
+};
+
+/// A 20 byte EVM account address.
+///
+/// It is parsed from hex with an optional `0x` prefix, validating the EIP-55 checksum of mixed
+/// case addresses, and is displayed and serialized to JSON with its checksum.
+///
+/// ###### Example:
+///
+/// ```rust
+/// let address: Address = "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045".parse()?;
+///
+/// assert_eq!(address.to_string(), "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045");
+/// ```
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Address(pub [u8; 20]);
+
+impl Address {
+    pub const fn as_bytes(&self) -> &[u8; 20] {
+        &self.0
+    }
+
+    /// The EIP-55 checksummed hex encoding, with the `0x` prefix.
+    pub fn to_checksum(&self) -> String {
+        let hex = hex::encode(self.0);
+        let hash = keccak256(hex.as_bytes());
+
+        let mut checksummed = String::with_capacity(42);
+        checksummed.push_str("0x");
+        for (i, c) in hex.chars().enumerate() {
+            let nibble = if i % 2 == 0 {
+                hash[i / 2] >> 4
+            } else {
+                hash[i / 2] & 0x0f
+            };
+            checksummed.push(if nibble >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            });
+        }
+        checksummed
+    }
+}
+
+impl From<[u8; 20]> for Address {
+    fn from(bytes: [u8; 20]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<Address> for [u8; 20] {
+    fn from(address: Address) -> Self {
+        address.0
+    }
+}
+
+impl AsRef<[u8]> for Address {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_checksum())
+    }
+}
+
+impl FromStr for Address {
+    type Err = EVMError;
+
+    /// Parses a hex address with an optional `0x` prefix.
+    ///
+    /// All lowercase and all uppercase addresses carry no checksum, mixed case addresses must
+    /// match their EIP-55 checksum.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let hex = s
+            .strip_prefix("0x")
+            .or_else(|| s.strip_prefix("0X"))
+            .unwrap_or(s);
+        if hex.len() != 40 {
+            return Err(EVMError::InvalidAddress(
+                "address should be 40 hex characters long",
+            ));
+        }
+
+        let mut bytes = [0u8; 20];
+        hex::decode_to_slice(hex, &mut bytes)
+            .map_err(|_| EVMError::InvalidAddress("address should be hex encoded"))?;
+        let address = Self(bytes);
+
+        let is_mixed_case = hex.chars().any(|c| c.is_ascii_lowercase())
+            && hex.chars().any(|c| c.is_ascii_uppercase());
+        if is_mixed_case && address.to_checksum()[2..] != *hex {
+            return Err(EVMError::InvalidAddressChecksum);
+        }
+
+        Ok(address)
+    }
+}
+
+impl Serialize for Address {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_checksum())
+    }
+}
+
+impl<'de> Deserialize<'de> for Address {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct AddressOrBytes;
+
+        impl<'de> de::Visitor<'de> for AddressOrBytes {
+            type Value = Address;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a hex string or byte array representing an address")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                value.parse().map_err(de::Error::custom)
+            }
+
+            // Addresses used to be serialized as an array of 20 bytes.
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: de::SeqAccess<'de>,
+            {
+                let mut bytes = [0u8; 20];
+                for (i, byte) in bytes.iter_mut().enumerate() {
+                    *byte = seq
+                        .next_element()?
+                        .ok_or_else(|| de::Error::invalid_length(i, &self))?;
+                }
+                Ok(Address(bytes))
+            }
+        }
+
+        deserializer.deserialize_any(AddressOrBytes)
+    }
+}
+
+impl JsonSchema for Address {
+    fn schema_name() -> String {
+        "Address".to_string()
+    }
+
+    /// A checksummed hex string, or the array of 20 bytes that is still accepted when
+    /// deserializing.
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        schemars::schema::SchemaObject {
+            subschemas: Some(Box::new(schemars::schema::SubschemaValidation {
+                any_of: Some(vec![
+                    <String>::json_schema(gen),
+                    <[u8; 20]>::json_schema(gen),
+                ]),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
 
 pub type AccessList = Vec<(Address, Vec<[u8; 32]>)>;
 
-#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+/// A KZG commitment or proof, as a compressed BLS12-381 G1 point.
+pub type Bytes48 = [u8; 48];
+
+/// The blobs of an EIP-4844 transaction, with their KZG commitments and proofs.
+///
+/// They are only sent along with the transaction when it is broadcast, the transaction itself
+/// commits to the versioned hashes of the commitments.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct BlobSidecar {
+    /// The blobs, 131072 bytes each.
+    pub blobs: Vec<Vec<u8>>,
+    /// The KZG commitment of each blob.
+    pub commitments: Vec<Bytes48>,
+    /// The KZG proof of each blob.
+    pub proofs: Vec<Bytes48>,
+}
+
+impl BlobSidecar {
+    /// The versioned hashes of the commitments, to set as `blob_versioned_hashes`.
+    pub fn versioned_hashes(&self) -> Vec<[u8; 32]> {
+        self.commitments.iter().map(kzg_to_versioned_hash).collect()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq, Eq)]
 #[serde(crate = "near_sdk::serde")]
 pub struct Signature {
     pub v: u64,
     pub r: Vec<u8>,
     pub s: Vec<u8>,
 }
+
+impl Signature {
+    /// The `v` of a legacy transaction on `chain_id`, `y_parity + chain_id * 2 + 35` (EIP-155).
+    ///
+    /// `self.v` must be the y parity (0 or 1), not an already encoded `v` such as 27 or 28.
+    pub fn eip155_v(&self, chain_id: u64) -> Result<u64, EVMError> {
+        if self.v > 1 {
+            return Err(EVMError::InvalidRecoveryId(self.v));
+        }
+        chain_id
+            .checked_mul(2)
+            .and_then(|v| v.checked_add(35 + self.v))
+            .ok_or(EVMError::InvalidChainId(chain_id))
+    }
+}
+
+/// Converts a signature of the MPC signer into an EVM signature.
+///
+/// `r` is the x coordinate of `big_r`, and a high `s` is normalized to `n - s` (EIP-2), flipping
+/// the recovery id. `v` is the y parity (0 or 1), as encoded by typed transactions; legacy
+/// transactions encode it as [`Signature::eip155_v`].
+impl TryFrom<&SignatureResponse> for Signature {
+    type Error = EVMError;
+
+    fn try_from(response: &SignatureResponse) -> Result<Self, Self::Error> {
+        let v = match response.recovery_id {
+            0 | 1 => u64::from(response.recovery_id),
+            recovery_id => return Err(EVMError::InvalidRecoveryId(recovery_id.into())),
+        };
+
+        let big_r = hex::decode(&response.big_r.affine_point)
+            .ok()
+            .and_then(|big_r| k256::PublicKey::from_sec1_bytes(&big_r).ok())
+            .ok_or(EVMError::InvalidSignature(
+                "big_r should be a hex encoded SEC1 curve point",
+            ))?;
+        let r =
+            *big_r
+                .as_affine()
+                .to_encoded_point(false)
+                .x()
+                .ok_or(EVMError::InvalidSignature(
+                    "big_r should not be the identity",
+                ))?;
+
+        let s: [u8; 32] = hex::decode(&response.s.scalar)
+            .ok()
+            .and_then(|s| s.try_into().ok())
+            .ok_or(EVMError::InvalidSignature(
+                "s should be a hex encoded 32 byte scalar",
+            ))?;
+
+        let signature = K256Signature::from_scalars(r, s)
+            .map_err(|_| EVMError::InvalidSignature("r and s should be in the range [1, n)"))?;
+        let (signature, v) = match signature.normalize_s() {
+            Some(normalized) => (normalized, v ^ 1),
+            None => (signature, v),
+        };
+
+        Ok(Self {
+            v,
+            r: signature.r().to_bytes().to_vec(),
+            s: signature.s().to_bytes().to_vec(),
+        })
+    }
+}
+
+impl TryFrom<SignatureResponse> for Signature {
+    type Error = EVMError;
+
+    fn try_from(response: SignatureResponse) -> Result<Self, Self::Error> {
+        Self::try_from(&response)
+    }
+}
+
+/// An EIP-7702 authorization to delegate the code of the signing account to `address`.
+///
+/// A `chain_id` of 0 makes the authorization valid on every chain.
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Authorization {
+    pub chain_id: u64,
+    pub address: Address,
+    pub nonce: u64,
+}
+
+impl Authorization {
+    /// Encodes the signing payload `0x05 || rlp([chain_id, address, nonce])`.
+    pub fn build_for_signing(&self) -> Vec<u8> {
+        let mut rlp_stream = RlpStream::new();
+
+        rlp_stream.append(&EIP_7702_AUTH_MAGIC);
+
+        rlp_stream.begin_unbounded_list();
+        self.encode_fields(&mut rlp_stream);
+        rlp_stream.finalize_unbounded_list();
+
+        rlp_stream.out().to_vec()
+    }
+
+    /// The keccak256 hash of the signing payload, to be signed by the delegating account.
+    pub fn signing_hash(&self) -> [u8; 32] {
+        keccak256(&self.build_for_signing())
+    }
+
+    /// Attaches the signature of the delegating account, whose `v` is the y parity (0 or 1).
+    pub const fn into_signed(self, signature: Signature) -> SignedAuthorization {
+        SignedAuthorization {
+            authorization: self,
+            signature,
+        }
+    }
+
+    fn encode_fields(&self, rlp_stream: &mut RlpStream) {
+        rlp_stream.append(&self.chain_id);
+        rlp_stream.append(&self.address.as_bytes().to_vec());
+        rlp_stream.append(&self.nonce);
+    }
+}
+
+/// An EIP-7702 authorization with the signature of the delegating account.
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SignedAuthorization {
+    pub authorization: Authorization,
+    pub signature: Signature,
+}
+
+impl SignedAuthorization {
+    /// Appends the authorization tuple `[chain_id, address, nonce, y_parity, r, s]`.
+    pub(crate) fn encode(&self, rlp_stream: &mut RlpStream) {
+        rlp_stream.begin_unbounded_list();
+
+        self.authorization.encode_fields(rlp_stream);
+
+        rlp_stream.append(&self.signature.v);
+        append_uint_bytes(rlp_stream, &self.signature.r);
+        append_uint_bytes(rlp_stream, &self.signature.s);
+
+        rlp_stream.finalize_unbounded_list();
+    }
+
+    /// Decodes an authorization tuple `[chain_id, address, nonce, y_parity, r, s]`.
+    pub(crate) fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+        if rlp.item_count()? != 6 {
+            return Err(DecoderError::RlpIncorrectListLen);
+        }
+
+        Ok(Self {
+            authorization: Authorization {
+                chain_id: rlp.val_at(0)?,
+                address: decode_address(&rlp.at(1)?)?,
+                nonce: rlp.val_at(2)?,
+            },
+            signature: decode_signature(rlp, 3)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Address, Signature};
+    use crate::evm::test_utils::{sign_prehash, SENDER};
+    use crate::evm::utils::recover_address;
+    use crate::evm::EVMError;
+    use crate::signer::types::{SerializableAffinePoint, SerializableScalar, SignatureResponse};
+
+    fn response(big_r: &[u8], s: &[u8], recovery_id: u8) -> SignatureResponse {
+        SignatureResponse {
+            big_r: SerializableAffinePoint {
+                affine_point: hex::encode_upper(big_r),
+            },
+            s: SerializableScalar {
+                scalar: hex::encode_upper(s),
+            },
+            recovery_id,
+        }
+    }
+
+    /// Signs `hash` and returns the MPC response, with the low `s` and with `n - s`.
+    fn sign(hash: &[u8; 32]) -> (SignatureResponse, SignatureResponse) {
+        let (signature, recovery_id) = sign_prehash(hash);
+
+        let mut big_r = vec![0x02 | recovery_id.to_byte()];
+        big_r.extend_from_slice(&signature.r().to_bytes());
+        let high_s = (-*signature.s()).to_bytes();
+
+        (
+            response(&big_r, &signature.s().to_bytes(), recovery_id.to_byte()),
+            response(&big_r, &high_s, recovery_id.to_byte() ^ 1),
+        )
+    }
+
+    #[test]
+    fn test_signature_from_signature_response() {
+        let hash = [0xab; 32];
+        let (low_s, high_s) = sign(&hash);
+
+        let signature = Signature::try_from(&low_s).unwrap();
+
+        assert_eq!(signature.v, u64::from(low_s.recovery_id));
+        assert_eq!(
+            signature.r,
+            hex::decode(&low_s.big_r.affine_point).unwrap()[1..]
+        );
+        assert_eq!(signature.s, hex::decode(&low_s.s.scalar).unwrap());
+        assert_eq!(
+            recover_address(&hash, &signature).unwrap(),
+            SENDER.parse::<Address>().unwrap()
+        );
+
+        // A high s is normalized and the parity flipped back
+        assert_eq!(Signature::try_from(high_s).unwrap(), signature);
+    }
+
+    #[test]
+    fn test_eip155_v() {
+        let (response, _) = sign(&[0xab; 32]);
+        let signature = Signature::try_from(response).unwrap();
+
+        assert_eq!(signature.eip155_v(1), Ok(37 + signature.v));
+        assert_eq!(signature.eip155_v(56), Ok(147 + signature.v));
+
+        assert_eq!(
+            signature.eip155_v(u64::MAX / 2),
+            Err(EVMError::InvalidChainId(u64::MAX / 2))
+        );
+        let encoded = Signature { v: 27, ..signature };
+        assert_eq!(encoded.eip155_v(1), Err(EVMError::InvalidRecoveryId(27)));
+    }
+
+    #[test]
+    fn test_signature_from_invalid_signature_response() {
+        let (response, _) = sign(&[0xab; 32]);
+
+        let mut invalid = response.clone();
+        invalid.recovery_id = 2;
+        assert_eq!(
+            Signature::try_from(&invalid),
+            Err(EVMError::InvalidRecoveryId(2))
+        );
+
+        let mut invalid = response.clone();
+        invalid.big_r.affine_point = invalid.big_r.affine_point[2..].to_string();
+        assert!(matches!(
+            Signature::try_from(&invalid),
+            Err(EVMError::InvalidSignature(_))
+        ));
+
+        let mut invalid = response.clone();
+        invalid.s.scalar = "00".repeat(32);
+        assert!(matches!(
+            Signature::try_from(&invalid),
+            Err(EVMError::InvalidSignature(_))
+        ));
+
+        let mut invalid = response;
+        invalid.s.scalar.push_str("00");
+        assert!(matches!(
+            Signature::try_from(&invalid),
+            Err(EVMError::InvalidSignature(_))
+        ));
+    }
+
+    #[test]
+    fn test_address_eip55_checksum() {
+        // The test vectors of EIP-55
+        for checksummed in [
+            "0x52908400098527886E0F7030069857D2E4169EE7",
+            "0x8617E340B3D01FA5F11F306F4090FD50E238070D",
+            "0xde709f2102306220921060314715629080e2fb77",
+            "0x27b1fdb04752bbc536007a920d24acb045561c26",
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+            "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+            "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+            "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+        ] {
+            let address: Address = checksummed.parse().unwrap();
+
+            assert_eq!(address.to_string(), checksummed);
+            assert_eq!(
+                address,
+                checksummed.to_lowercase().parse::<Address>().unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_address_from_str() {
+        let expected = Address::from([
+            0x5a, 0xae, 0xb6, 0x05, 0x3f, 0x3e, 0x94, 0xc9, 0xb9, 0xa0, 0x9f, 0x33, 0x66, 0x94,
+            0x35, 0xe7, 0xef, 0x1b, 0xea, 0xed,
+        ]);
+
+        for valid in [
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+            "5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+            "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed",
+            "0X5AAEB6053F3E94C9B9A09F33669435E7EF1BEAED",
+        ] {
+            assert_eq!(valid.parse::<Address>(), Ok(expected));
+        }
+
+        assert_eq!(
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD".parse::<Address>(),
+            Err(EVMError::InvalidAddressChecksum)
+        );
+        assert!(matches!(
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeA".parse::<Address>(),
+            Err(EVMError::InvalidAddress(_))
+        ));
+        assert!(matches!(
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeg".parse::<Address>(),
+            Err(EVMError::InvalidAddress(_))
+        ));
+    }
+
+    #[test]
+    fn test_address_json() {
+        let address = "d8da6bf26964af9d7eed9e03e53415d37aa96045"
+            .parse::<Address>()
+            .unwrap();
+
+        let json = near_sdk::serde_json::to_string(&address).unwrap();
+        assert_eq!(json, "\"0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045\"");
+        assert_eq!(
+            near_sdk::serde_json::from_str::<Address>(&json).unwrap(),
+            address
+        );
+
+        // Addresses used to be serialized as byte arrays
+        let bytes = near_sdk::serde_json::to_string(&address.0).unwrap();
+        assert_eq!(
+            near_sdk::serde_json::from_str::<Address>(&bytes).unwrap(),
+            address
+        );
+
+        assert!(near_sdk::serde_json::from_str::<Address>(
+            "\"0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96046\""
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_address_json_schema() {
+        let schema = near_sdk::serde_json::to_value(schemars::schema_for!(Address)).unwrap();
+
+        assert_eq!(schema["anyOf"][0]["type"], "string");
+        assert_eq!(schema["anyOf"][1]["type"], "array");
+        assert_eq!(schema["anyOf"][1]["minItems"], 20);
+        assert_eq!(schema["anyOf"][1]["maxItems"], 20);
+    }
+}
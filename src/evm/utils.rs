@@ -1,12 +1,374 @@
 //! Utility functions for serialization and encoding of EVM data structures
-use hex;
+use k256::ecdsa::{RecoveryId, Signature as K256Signature, VerifyingKey};
+use k256::FieldBytes;
+use rlp::{DecoderError, Rlp, RlpStream};
+use sha2::{Digest, Sha256};
+use sha3::Keccak256;
 
-use super::types::Address;
+use crate::constants::VERSIONED_HASH_VERSION_KZG;
 
+use super::error::EVMError;
+use super::types::{AccessList, Address, Bytes48, Signature, SignedAuthorization};
+
+/// Parses a hex address with an optional `0x` prefix.
+///
+/// Kept for backward compatibility, it panics if the address is not 20 hex encoded bytes or
+/// fails its EIP-55 checksum. Parse an [`Address`] with `str::parse` to handle invalid addresses.
+#[deprecated(note = "parse an `Address` with `str::parse` instead")]
 pub fn parse_eth_address(address: &str) -> Address {
-    let address = hex::decode(address).expect("address should be hex");
-    assert_eq!(address.len(), 20, "address should be 20 bytes long");
-    let mut result = [0u8; 20];
-    result.copy_from_slice(&address);
-    result
+    address.parse().unwrap_or_else(|e| panic!("{e}"))
+}
+
+/// Computes the keccak256 hash of the given data.
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    Keccak256::digest(data).into()
+}
+
+/// Computes the address of an uncompressed secp256k1 public key (`x || y`), the last 20 bytes
+/// of its keccak256 hash.
+pub fn public_key_to_address(public_key: &[u8; 64]) -> Address {
+    let hash = keccak256(public_key);
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&hash[12..]);
+    Address(address)
+}
+
+/// Recovers the uncompressed public key (`x || y`) that produced `signature` over `hash`.
+///
+/// `signature.v` is the recovery id (0 or 1). Signatures with a high `s` are rejected, they are
+/// not valid for transactions since EIP-2.
+pub fn recover_public_key(hash: &[u8; 32], signature: &Signature) -> Result<[u8; 64], EVMError> {
+    let recovery_id = match signature.v {
+        0 | 1 => RecoveryId::new(signature.v == 1, false),
+        v => return Err(EVMError::InvalidRecoveryId(v)),
+    };
+
+    let k256_signature =
+        K256Signature::from_scalars(scalar_bytes(&signature.r)?, scalar_bytes(&signature.s)?)
+            .map_err(|_| EVMError::InvalidSignature("r and s should be in the range [1, n)"))?;
+    if k256_signature.normalize_s().is_some() {
+        return Err(EVMError::InvalidSignature(
+            "s should be in the lower half of the curve order",
+        ));
+    }
+
+    let verifying_key = VerifyingKey::recover_from_prehash(hash, &k256_signature, recovery_id)
+        .map_err(|_| EVMError::InvalidSignature("no public key recovers from the signature"))?;
+
+    let mut public_key = [0u8; 64];
+    public_key.copy_from_slice(&verifying_key.to_encoded_point(false).as_bytes()[1..]);
+    Ok(public_key)
+}
+
+/// Recovers the address of the account that produced `signature` over `hash`, see
+/// [`recover_public_key`].
+pub fn recover_address(hash: &[u8; 32], signature: &Signature) -> Result<Address, EVMError> {
+    recover_public_key(hash, signature).map(|public_key| public_key_to_address(&public_key))
+}
+
+/// Left pads a big-endian signature scalar to 32 bytes.
+fn scalar_bytes(bytes: &[u8]) -> Result<FieldBytes, EVMError> {
+    if bytes.len() > 32 {
+        return Err(EVMError::InvalidSignature(
+            "r and s should be at most 32 bytes long",
+        ));
+    }
+
+    let mut scalar = FieldBytes::default();
+    scalar[32 - bytes.len()..].copy_from_slice(bytes);
+    Ok(scalar)
+}
+
+/// Computes the versioned hash of a KZG commitment, `0x01 || sha256(commitment)[1..]` (EIP-4844).
+pub fn kzg_to_versioned_hash(commitment: &Bytes48) -> [u8; 32] {
+    let mut hash: [u8; 32] = Sha256::digest(commitment).into();
+    hash[0] = VERSIONED_HASH_VERSION_KZG;
+    hash
+}
+
+/// Appends an EIP-2930 access list to the RLP stream.
+pub(crate) fn encode_access_list(rlp_stream: &mut RlpStream, access_list: &AccessList) {
+    rlp_stream.begin_unbounded_list();
+    for access in access_list {
+        rlp_stream.begin_unbounded_list();
+        rlp_stream.append(&access.0.as_bytes().to_vec());
+        // Append list of storage keys.
+        {
+            rlp_stream.begin_unbounded_list();
+            for storage_key in &access.1 {
+                rlp_stream.append(&storage_key.to_vec());
+            }
+            rlp_stream.finalize_unbounded_list();
+        }
+        rlp_stream.finalize_unbounded_list();
+    }
+    rlp_stream.finalize_unbounded_list();
+}
+
+/// Appends a big-endian unsigned integer, such as a signature scalar, without its leading zeros.
+pub(crate) fn append_uint_bytes(rlp_stream: &mut RlpStream, bytes: &[u8]) {
+    let start = bytes.iter().position(|&b| b != 0).unwrap_or(bytes.len());
+    rlp_stream.append(&&bytes[start..]);
+}
+
+/// Parses the RLP list of a typed transaction `tx_type || rlp([...])`.
+pub(crate) fn decode_typed_list(
+    raw: &[u8],
+    tx_type: u8,
+    item_count: usize,
+) -> Result<Rlp<'_>, DecoderError> {
+    match raw.split_first() {
+        Some((&prefix, payload)) if prefix == tx_type => decode_list(payload, item_count),
+        Some(_) => Err(DecoderError::Custom("unexpected transaction type")),
+        None => Err(DecoderError::RlpIsTooShort),
+    }
+}
+
+/// Parses an RLP list of `item_count` items spanning the whole input.
+pub(crate) fn decode_list(raw: &[u8], item_count: usize) -> Result<Rlp<'_>, DecoderError> {
+    let rlp = Rlp::new(raw);
+
+    if !rlp.is_list() {
+        return Err(DecoderError::RlpExpectedToBeList);
+    }
+    if rlp.payload_info()?.total() != raw.len() {
+        return Err(DecoderError::RlpInconsistentLengthAndData);
+    }
+    if rlp.item_count()? != item_count {
+        return Err(DecoderError::RlpIncorrectListLen);
+    }
+
+    Ok(rlp)
+}
+
+pub(crate) fn decode_address(rlp: &Rlp) -> Result<Address, DecoderError> {
+    let bytes: Vec<u8> = rlp.as_val()?;
+    bytes
+        .try_into()
+        .map(Address)
+        .map_err(|_| DecoderError::Custom("address should be 20 bytes long"))
+}
+
+/// Decodes the `to` field, empty for contract creations.
+pub(crate) fn decode_optional_address(rlp: &Rlp) -> Result<Option<Address>, DecoderError> {
+    if rlp.is_data() && rlp.is_empty() {
+        return Ok(None);
+    }
+    decode_address(rlp).map(Some)
+}
+
+pub(crate) fn decode_bytes32(rlp: &Rlp) -> Result<[u8; 32], DecoderError> {
+    let bytes: Vec<u8> = rlp.as_val()?;
+    bytes
+        .try_into()
+        .map_err(|_| DecoderError::Custom("value should be 32 bytes long"))
+}
+
+/// Decodes a list of 32 byte values, such as storage keys or blob versioned hashes.
+pub(crate) fn decode_bytes32_list(rlp: &Rlp) -> Result<Vec<[u8; 32]>, DecoderError> {
+    if !rlp.is_list() {
+        return Err(DecoderError::RlpExpectedToBeList);
+    }
+    rlp.iter().map(|item| decode_bytes32(&item)).collect()
+}
+
+/// Decodes a list of KZG commitments or proofs.
+pub(crate) fn decode_bytes48_list(rlp: &Rlp) -> Result<Vec<Bytes48>, DecoderError> {
+    if !rlp.is_list() {
+        return Err(DecoderError::RlpExpectedToBeList);
+    }
+    rlp.iter()
+        .map(|item| {
+            let bytes: Vec<u8> = item.as_val()?;
+            bytes
+                .try_into()
+                .map_err(|_| DecoderError::Custom("value should be 48 bytes long"))
+        })
+        .collect()
+}
+
+/// Decodes an EIP-2930 access list.
+pub(crate) fn decode_access_list(rlp: &Rlp) -> Result<AccessList, DecoderError> {
+    if !rlp.is_list() {
+        return Err(DecoderError::RlpExpectedToBeList);
+    }
+    rlp.iter()
+        .map(|access| {
+            if access.item_count()? != 2 {
+                return Err(DecoderError::RlpIncorrectListLen);
+            }
+            Ok((
+                decode_address(&access.at(0)?)?,
+                decode_bytes32_list(&access.at(1)?)?,
+            ))
+        })
+        .collect()
+}
+
+/// Decodes a big-endian unsigned integer of at most 32 bytes, such as a signature scalar,
+/// and left pads it to 32 bytes.
+pub(crate) fn decode_uint_bytes(rlp: &Rlp) -> Result<Vec<u8>, DecoderError> {
+    let bytes: Vec<u8> = rlp.as_val()?;
+
+    if bytes.len() > 32 {
+        return Err(DecoderError::RlpIsTooBig);
+    }
+    if bytes.first() == Some(&0) {
+        return Err(DecoderError::RlpInvalidIndirection);
+    }
+
+    let mut padded = vec![0u8; 32 - bytes.len()];
+    padded.extend_from_slice(&bytes);
+    Ok(padded)
+}
+
+/// Decodes the `[y_parity, r, s]` signature items starting at `index`.
+pub(crate) fn decode_signature(rlp: &Rlp, index: usize) -> Result<Signature, DecoderError> {
+    let v: u64 = rlp.val_at(index)?;
+    if v > 1 {
+        return Err(DecoderError::Custom("y parity should be 0 or 1"));
+    }
+
+    Ok(Signature {
+        v,
+        r: decode_uint_bytes(&rlp.at(index + 1)?)?,
+        s: decode_uint_bytes(&rlp.at(index + 2)?)?,
+    })
+}
+
+/// Decodes an EIP-7702 authorization list.
+pub(crate) fn decode_authorization_list(
+    rlp: &Rlp,
+) -> Result<Vec<SignedAuthorization>, DecoderError> {
+    if !rlp.is_list() {
+        return Err(DecoderError::RlpExpectedToBeList);
+    }
+    rlp.iter()
+        .map(|authorization| SignedAuthorization::decode(&authorization))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use k256::ecdsa::SigningKey;
+
+    use super::{public_key_to_address, recover_address, recover_public_key};
+    use crate::evm::test_utils::{sign, PRIVATE_KEY, SENDER};
+    use crate::evm::types::{Address, Signature};
+    use crate::evm::{EVMError, EVMLegacyTransaction, EVMSignableTransaction};
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_parse_eth_address() {
+        assert_eq!(
+            super::parse_eth_address(SENDER),
+            SENDER.parse::<Address>().unwrap()
+        );
+        assert_eq!(
+            super::parse_eth_address(&format!("0x{SENDER}")),
+            SENDER.parse::<Address>().unwrap()
+        );
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    #[should_panic(expected = "address should be hex encoded")]
+    fn test_parse_eth_address_panics_on_invalid_hex() {
+        super::parse_eth_address("zz5521d79134822a342d330bd91da67976569af1");
+    }
+
+    #[test]
+    fn test_recover_sender_of_eip_155_example() {
+        let tx = EVMLegacyTransaction {
+            chain_id: 1,
+            nonce: 9,
+            to: Some(
+                "3535353535353535353535353535353535353535"
+                    .parse::<Address>()
+                    .unwrap(),
+            ),
+            value: 1_000_000_000_000_000_000,
+            input: vec![],
+            gas_limit: 21_000,
+            gas_price: 20_000_000_000,
+        };
+        let signature = Signature {
+            v: 0,
+            r: hex::decode("28ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276")
+                .unwrap(),
+            s: hex::decode("67cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83")
+                .unwrap(),
+        };
+
+        assert_eq!(
+            tx.recover_sender(&signature).unwrap(),
+            SENDER.parse::<Address>().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_recover_public_key() {
+        let hash = [0xab; 32];
+        let signature = sign(&hash);
+
+        let public_key = recover_public_key(&hash, &signature).unwrap();
+
+        let verifying_key = SigningKey::from_bytes(&PRIVATE_KEY.into())
+            .unwrap()
+            .verifying_key()
+            .to_encoded_point(false);
+        assert_eq!(public_key[..], verifying_key.as_bytes()[1..]);
+        assert_eq!(
+            public_key_to_address(&public_key),
+            SENDER.parse::<Address>().unwrap()
+        );
+
+        // Flipping the recovery id recovers another key
+        let flipped = Signature {
+            v: 1 - signature.v,
+            ..signature
+        };
+        assert_ne!(
+            recover_address(&hash, &flipped).unwrap(),
+            SENDER.parse::<Address>().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_recover_rejects_invalid_signatures() {
+        let hash = [0xab; 32];
+        let signature = sign(&hash);
+
+        assert_eq!(
+            recover_address(
+                &hash,
+                &Signature {
+                    v: 27,
+                    ..signature.clone()
+                }
+            ),
+            Err(EVMError::InvalidRecoveryId(27))
+        );
+
+        // The same signature with s replaced by n - s
+        let s = k256::NonZeroScalar::try_from(signature.s.as_slice()).unwrap();
+        let high_s = Signature {
+            v: 1 - signature.v,
+            r: signature.r.clone(),
+            s: (-s).to_bytes().to_vec(),
+        };
+        assert!(matches!(
+            recover_address(&hash, &high_s),
+            Err(EVMError::InvalidSignature(_))
+        ));
+
+        let zero_r = Signature {
+            r: vec![0; 32],
+            ..signature
+        };
+        assert!(matches!(
+            recover_address(&hash, &zero_r),
+            Err(EVMError::InvalidSignature(_))
+        ));
+    }
 }
@@ -7,6 +7,7 @@
 //! - NEAR
 //! - EVM chains (including Ethereum and L2s)
 //! - Bitcoin
+//! - Zcash (transparent)
 //!
 //! ### Installation
 //! ```toml
@@ -46,7 +47,7 @@
 //!
 //! ```rust
 //! let to_address_str = "d8dA6BF26964aF9D7eEd9e03E53415D37aA96045";
-//! let to_address = parse_eth_address(to_address_str);
+//! let to_address = to_address_str.parse::<Address>().unwrap();
 //! let max_gas_fee: u128 = 20_000_000_000;
 //! let max_priority_fee_per_gas: u128 = 1_000_000_000;
 //! let gas_limit: u128 = 21_000;
@@ -116,6 +117,7 @@
 //! - bitcoin
 //! - evm
 //! - near
+//! - zcash
 //!
 //! By default 'all' the features are enabled. However, you can customize the behaviour like this:
 //!
@@ -140,6 +142,8 @@ pub mod near;
 pub mod signer;
 mod transaction_builder;
 mod transaction_builders;
+#[cfg(feature = "zcash")]
+pub mod zcash;
 
 pub use transaction_builder::{TransactionBuilder, TxBuilder};
 /// Alias for BitcoinTransactionBuilder
@@ -151,3 +155,6 @@ pub use transaction_builders::EVM;
 /// Alias for NearTransactionBuilder
 #[cfg(feature = "near")]
 pub use transaction_builders::NEAR;
+/// Alias for ZcashTransactionBuilder
+#[cfg(feature = "zcash")]
+pub use transaction_builders::ZCASH;
@@ -27,7 +27,7 @@ mod tests {
         TransferAction as OmniTransferAction, U128,
     };
     use crate::{
-        evm::utils::parse_eth_address,
+        evm::types::Address as OmniAddress,
         transaction_builders::{EVM, NEAR},
     };
     use alloy::{
@@ -89,7 +89,7 @@ mod tests {
         let data: Vec<u8> = vec![];
         let chain_id = 1;
         let to_address_str = "d8dA6BF26964aF9D7eEd9e03E53415D37aA96045";
-        let to_address = parse_eth_address(to_address_str);
+        let to_address = to_address_str.parse::<OmniAddress>().unwrap();
 
         let tx = OmniTransactionBuilder::new::<EVM>()
             .chain_id(chain_id)
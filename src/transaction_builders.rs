@@ -8,6 +8,9 @@ use crate::evm::EVMTransactionBuilder;
 #[cfg(feature = "near")]
 use crate::near::NearTransactionBuilder;
 
+#[cfg(feature = "zcash")]
+use crate::zcash::ZcashTransactionBuilder;
+
 #[cfg(feature = "near")]
 pub type NEAR = NearTransactionBuilder;
 
@@ -16,3 +19,6 @@ pub type EVM = EVMTransactionBuilder;
 
 #[cfg(feature = "bitcoin")]
 pub type BITCOIN = BitcoinTransactionBuilder;
+
+#[cfg(feature = "zcash")]
+pub type ZCASH = ZcashTransactionBuilder;
@@ -0,0 +1,202 @@
+use std::fmt;
+
+use crate::bitcoin::{
+    address::{base58, Payload},
+    hashes::hash160,
+    types::ScriptBuf,
+    BitcoinError,
+};
+
+/// The Zcash network a transparent address is valid on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ZcashNetwork {
+    /// Zcash mainnet, with `t1` and `t3` addresses.
+    Mainnet,
+    /// Zcash testnet and regtest, with `tm` and `t2` addresses.
+    Testnet,
+}
+
+impl ZcashNetwork {
+    /// Two byte prefix of base58 encoded P2PKH addresses.
+    pub const fn p2pkh_prefix(self) -> [u8; 2] {
+        match self {
+            Self::Mainnet => [0x1c, 0xb8],
+            Self::Testnet => [0x1d, 0x25],
+        }
+    }
+
+    /// Two byte prefix of base58 encoded P2SH addresses.
+    pub const fn p2sh_prefix(self) -> [u8; 2] {
+        match self {
+            Self::Mainnet => [0x1c, 0xbd],
+            Self::Testnet => [0x1c, 0xba],
+        }
+    }
+}
+
+impl fmt::Display for ZcashNetwork {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Mainnet => write!(f, "zcash"),
+            Self::Testnet => write!(f, "zcash-testnet"),
+        }
+    }
+}
+
+/// A Zcash transparent address (t-address), bound to the [`ZcashNetwork`] it is valid on.
+///
+/// ###### Example:
+///
+/// ```rust
+/// let address = TransparentAddress::parse("t1UYsZVJkLPeMjxEtACvSxfWuNmddpWfxzs", ZcashNetwork::Mainnet).unwrap();
+///
+/// let txout = TxOut {
+///     value: Amount::from_sat(10000),
+///     script_pubkey: address.script_pubkey(),
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransparentAddress {
+    network: ZcashNetwork,
+    payload: Payload,
+}
+
+impl TransparentAddress {
+    /// Creates a P2PKH address from a serialized (compressed or uncompressed) public key.
+    pub fn p2pkh(public_key: &[u8], network: ZcashNetwork) -> Self {
+        Self {
+            network,
+            payload: Payload::PubkeyHash(hash160(public_key)),
+        }
+    }
+
+    /// Creates a P2SH address from a redeem script.
+    pub fn p2sh(redeem_script: &ScriptBuf, network: ZcashNetwork) -> Self {
+        Self {
+            network,
+            payload: Payload::ScriptHash(hash160(redeem_script.as_bytes())),
+        }
+    }
+
+    /// Creates the address paying to the given output script.
+    pub fn from_script(
+        script_pubkey: &ScriptBuf,
+        network: ZcashNetwork,
+    ) -> Result<Self, BitcoinError> {
+        let bytes = script_pubkey.as_bytes();
+        let payload = if script_pubkey.is_p2pkh() {
+            Payload::PubkeyHash(bytes[3..23].try_into().expect("20 bytes"))
+        } else if script_pubkey.is_p2sh() {
+            Payload::ScriptHash(bytes[2..22].try_into().expect("20 bytes"))
+        } else {
+            return Err(BitcoinError::UnsupportedScript);
+        };
+
+        Ok(Self { network, payload })
+    }
+
+    /// Parses a transparent address, checking that it is valid on the given network.
+    pub fn parse(address: &str, network: ZcashNetwork) -> Result<Self, BitcoinError> {
+        let data = base58::decode_check(address).map_err(BitcoinError::InvalidAddress)?;
+        if data.len() != 22 {
+            return Err(BitcoinError::InvalidAddress(format!(
+                "Invalid transparent address length: {}",
+                data.len()
+            )));
+        }
+        let hash: [u8; 20] = data[2..].try_into().expect("20 bytes");
+        let payload = match [data[0], data[1]] {
+            prefix if prefix == network.p2pkh_prefix() => Payload::PubkeyHash(hash),
+            prefix if prefix == network.p2sh_prefix() => Payload::ScriptHash(hash),
+            _ => {
+                return Err(BitcoinError::InvalidAddress(format!(
+                    "Address {address} is not valid on {network}"
+                )))
+            }
+        };
+
+        Ok(Self { network, payload })
+    }
+
+    /// Returns the network the address is valid on.
+    pub const fn network(&self) -> ZcashNetwork {
+        self.network
+    }
+
+    /// Returns the payload of the address.
+    pub const fn payload(&self) -> &Payload {
+        &self.payload
+    }
+
+    /// Generates the output script paying to this address.
+    pub fn script_pubkey(&self) -> ScriptBuf {
+        match &self.payload {
+            Payload::PubkeyHash(hash) => ScriptBuf::new_p2pkh(hash),
+            Payload::ScriptHash(hash) => ScriptBuf::new_p2sh(hash),
+            Payload::WitnessProgram { .. } => unreachable!("transparent addresses have no witness"),
+        }
+    }
+}
+
+impl fmt::Display for TransparentAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (prefix, hash) = match &self.payload {
+            Payload::PubkeyHash(hash) => (self.network.p2pkh_prefix(), hash),
+            Payload::ScriptHash(hash) => (self.network.p2sh_prefix(), hash),
+            Payload::WitnessProgram { .. } => unreachable!("transparent addresses have no witness"),
+        };
+        let mut data = prefix.to_vec();
+        data.extend_from_slice(hash);
+        write!(f, "{}", base58::encode_check(&data))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUBLIC_KEY: &str = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+
+    #[test]
+    fn test_p2pkh_addresses() {
+        let public_key = hex::decode(PUBLIC_KEY).unwrap();
+
+        let mainnet = TransparentAddress::p2pkh(&public_key, ZcashNetwork::Mainnet);
+        let testnet = TransparentAddress::p2pkh(&public_key, ZcashNetwork::Testnet);
+
+        assert_eq!(mainnet.to_string(), "t1UYsZVJkLPeMjxEtACvSxfWuNmddpWfxzs");
+        assert_eq!(testnet.to_string(), "tmLPctKo9j49rtCSKpwEBpLBeykiTGomGQs");
+        assert!(mainnet.script_pubkey().is_p2pkh());
+    }
+
+    #[test]
+    fn test_parse_roundtrip() {
+        for (address, network) in [
+            ("t1UYsZVJkLPeMjxEtACvSxfWuNmddpWfxzs", ZcashNetwork::Mainnet),
+            ("t3VEtV2oBtHxjq7wKHJb3PHsqXHvMRgUmVw", ZcashNetwork::Mainnet),
+            ("tmLPctKo9j49rtCSKpwEBpLBeykiTGomGQs", ZcashNetwork::Testnet),
+            ("t2HE5XhuKkka7NpX4D3b5vv4Udn9XGqUwEt", ZcashNetwork::Testnet),
+        ] {
+            let parsed = TransparentAddress::parse(address, network).unwrap();
+            assert_eq!(parsed.to_string(), address);
+            assert_eq!(
+                TransparentAddress::from_script(&parsed.script_pubkey(), network).unwrap(),
+                parsed
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_rejects_other_network() {
+        assert!(matches!(
+            TransparentAddress::parse("t1UYsZVJkLPeMjxEtACvSxfWuNmddpWfxzs", ZcashNetwork::Testnet),
+            Err(BitcoinError::InvalidAddress(_))
+        ));
+        // A Bitcoin address has a single byte prefix.
+        assert!(TransparentAddress::parse(
+            "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH",
+            ZcashNetwork::Mainnet
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,18 @@
+//! Transaction builder, encoders and types for Zcash transparent transactions.
+//!
+//! Zcash v5 transactions (ZIP-225) carry the same transparent inputs and outputs as Bitcoin,
+//! so the Bitcoin [`TxIn`](crate::bitcoin::types::TxIn) and
+//! [`TxOut`](crate::bitcoin::types::TxOut) types are reused, next to empty Sapling and Orchard
+//! bundles.
+mod address;
+mod zcash_transaction;
+mod zcash_transaction_builder;
+
+/// Zcash transparent address
+pub use address::{TransparentAddress, ZcashNetwork};
+/// Zcash transaction
+pub use zcash_transaction::{
+    ZcashTransaction, NU5_BRANCH_ID, NU6_1_BRANCH_ID, NU6_BRANCH_ID, V5_VERSION_GROUP_ID,
+};
+/// Zcash transaction builder
+pub use zcash_transaction_builder::ZcashTransactionBuilder;
@@ -0,0 +1,337 @@
+use blake2::{
+    digest::{
+        core_api::{CoreWrapper, VariableOutputCore},
+        Update,
+    },
+    Blake2bVarCore,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+
+use crate::bitcoin::{
+    encoding::Encodable,
+    types::{EcdsaSighashType, Hash, LockTime, TxIn, TxOut, Txid},
+    BitcoinError,
+};
+
+/// The header of a v5 transaction: version 5 with the `fOverwintered` flag set.
+const V5_HEADER: u32 = (1 << 31) | 5;
+
+/// The version group id of v5 transactions (ZIP-225).
+pub const V5_VERSION_GROUP_ID: u32 = 0x26a7_270a;
+
+/// The consensus branch id of the NU5 network upgrade.
+pub const NU5_BRANCH_ID: u32 = 0xc2d6_d0b4;
+
+/// The consensus branch id of the NU6 network upgrade.
+pub const NU6_BRANCH_ID: u32 = 0xc8e7_1055;
+
+/// The consensus branch id of the NU6.1 network upgrade.
+pub const NU6_1_BRANCH_ID: u32 = 0x4dec_4df0;
+
+/// A Zcash v5 transaction (ZIP-225) with a transparent bundle only.
+///
+/// The Sapling and Orchard bundles are always empty, so the transaction moves transparent
+/// funds between t-addresses. Transactions and signatures commit to the consensus branch id,
+/// which must be the one of the network upgrade active at the block the transaction is mined in.
+///
+/// ###### Example:
+///
+/// ```rust
+/// let zcash_tx = ZcashTransaction {
+///     consensus_branch_id: NU6_1_BRANCH_ID,
+///     lock_time: LockTime::from_height(0).unwrap(),
+///     expiry_height: 3_100_000,
+///     input: vec![txin],
+///     output: vec![txout],
+/// };
+///
+/// let sighash = zcash_tx
+///     .signature_hash(0, &spent_outputs, EcdsaSighashType::All)
+///     .unwrap();
+/// ```
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
+    JsonSchema,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ZcashTransaction {
+    /// The consensus branch id of the network upgrade the transaction is valid in.
+    pub consensus_branch_id: u32,
+    /// Block height or timestamp. Transaction cannot be included in a block until this height/time.
+    pub lock_time: LockTime,
+    /// Block height after which the transaction can no longer be mined, or 0 to disable expiry.
+    pub expiry_height: u32,
+    /// List of transparent inputs. Their witnesses are ignored.
+    pub input: Vec<TxIn>,
+    /// List of transparent outputs.
+    pub output: Vec<TxOut>,
+}
+
+impl ZcashTransaction {
+    /// Encode the transaction into a vector of bytes (ZIP-225)
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+
+        self.encode_header(&mut buffer);
+        let _ = self.input.encode(&mut buffer);
+        let _ = self.output.encode(&mut buffer);
+        // No Sapling spends or outputs, and no Orchard actions.
+        buffer.extend_from_slice(&[0x00, 0x00, 0x00]);
+
+        buffer
+    }
+
+    /// Computes the transaction id, the ZIP-244 digest of the transaction
+    pub fn compute_txid(&self) -> Txid {
+        let transparent_digest = if self.input.is_empty() && self.output.is_empty() {
+            blake2b_256(b"ZTxIdTranspaHash", &[])
+        } else {
+            let mut data = Vec::new();
+            data.extend_from_slice(&self.prevouts_digest());
+            data.extend_from_slice(&self.sequence_digest());
+            data.extend_from_slice(&self.outputs_digest());
+            blake2b_256(b"ZTxIdTranspaHash", &data)
+        };
+
+        Txid(Hash::from_internal_bytes(
+            self.transaction_digest(&transparent_digest),
+        ))
+    }
+
+    /// Computes the ZIP-244 signature digest of a transparent input.
+    ///
+    /// `spent_outputs` must contain the outputs spent by every input of the transaction, in
+    /// order, since the digest commits to all their amounts and scripts. The signature must be
+    /// followed by the hash type byte, as in Bitcoin.
+    pub fn signature_hash(
+        &self,
+        input_index: usize,
+        spent_outputs: &[TxOut],
+        sighash_type: EcdsaSighashType,
+    ) -> Result<[u8; 32], BitcoinError> {
+        let input = self
+            .input
+            .get(input_index)
+            .ok_or(BitcoinError::InputIndexOutOfRange {
+                index: input_index,
+                len: self.input.len(),
+            })?;
+        if spent_outputs.len() != self.input.len() {
+            return Err(BitcoinError::SpentOutputsMismatch {
+                expected: self.input.len(),
+                actual: spent_outputs.len(),
+            });
+        }
+
+        let mut amounts = Vec::new();
+        let mut script_pubkeys = Vec::new();
+        for spent_output in spent_outputs {
+            let _ = spent_output.value.encode(&mut amounts);
+            let _ = spent_output.script_pubkey.encode(&mut script_pubkeys);
+        }
+
+        let mut txin = Vec::new();
+        let spent_output = &spent_outputs[input_index];
+        let _ = input.previous_output.encode(&mut txin);
+        let _ = spent_output.value.encode(&mut txin);
+        let _ = spent_output.script_pubkey.encode(&mut txin);
+        let _ = input.sequence.encode(&mut txin);
+
+        let mut data = vec![sighash_type as u8];
+        data.extend_from_slice(&self.prevouts_digest());
+        data.extend_from_slice(&blake2b_256(b"ZTxTrAmountsHash", &amounts));
+        data.extend_from_slice(&blake2b_256(b"ZTxTrScriptsHash", &script_pubkeys));
+        data.extend_from_slice(&self.sequence_digest());
+        data.extend_from_slice(&self.outputs_digest());
+        data.extend_from_slice(&blake2b_256(b"Zcash___TxInHash", &txin));
+
+        Ok(self.transaction_digest(&blake2b_256(b"ZTxIdTranspaHash", &data)))
+    }
+
+    fn encode_header(&self, buffer: &mut Vec<u8>) {
+        buffer.extend_from_slice(&V5_HEADER.to_le_bytes());
+        buffer.extend_from_slice(&V5_VERSION_GROUP_ID.to_le_bytes());
+        buffer.extend_from_slice(&self.consensus_branch_id.to_le_bytes());
+        let _ = self.lock_time.encode(buffer);
+        buffer.extend_from_slice(&self.expiry_height.to_le_bytes());
+    }
+
+    /// The digest of the whole transaction, given the digest of its transparent bundle.
+    fn transaction_digest(&self, transparent_digest: &[u8; 32]) -> [u8; 32] {
+        let mut header = Vec::new();
+        self.encode_header(&mut header);
+
+        let mut data = Vec::new();
+        data.extend_from_slice(&blake2b_256(b"ZTxIdHeadersHash", &header));
+        data.extend_from_slice(transparent_digest);
+        data.extend_from_slice(&blake2b_256(b"ZTxIdSaplingHash", &[]));
+        data.extend_from_slice(&blake2b_256(b"ZTxIdOrchardHash", &[]));
+
+        let mut personalization = *b"ZcashTxHash_\0\0\0\0";
+        personalization[12..].copy_from_slice(&self.consensus_branch_id.to_le_bytes());
+        blake2b_256(&personalization, &data)
+    }
+
+    fn prevouts_digest(&self) -> [u8; 32] {
+        let mut data = Vec::new();
+        for input in &self.input {
+            let _ = input.previous_output.encode(&mut data);
+        }
+        blake2b_256(b"ZTxIdPrevoutHash", &data)
+    }
+
+    fn sequence_digest(&self) -> [u8; 32] {
+        let mut data = Vec::new();
+        for input in &self.input {
+            let _ = input.sequence.encode(&mut data);
+        }
+        blake2b_256(b"ZTxIdSequencHash", &data)
+    }
+
+    fn outputs_digest(&self) -> [u8; 32] {
+        let mut data = Vec::new();
+        for output in &self.output {
+            let _ = output.encode(&mut data);
+        }
+        blake2b_256(b"ZTxIdOutputsHash", &data)
+    }
+}
+
+/// BLAKE2b with a 256 bit output and a 16 byte personalization, as used throughout ZIP-244.
+fn blake2b_256(personalization: &[u8; 16], data: &[u8]) -> [u8; 32] {
+    let mut hasher =
+        CoreWrapper::from_core(Blake2bVarCore::new_with_params(&[], personalization, 0, 32));
+    hasher.update(data);
+
+    let (mut core, mut buffer) = hasher.decompose();
+    let mut output = Default::default();
+    core.finalize_variable_core(&mut buffer, &mut output);
+    output[..32].try_into().expect("32 byte digest")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitcoin::types::{Amount, OutPoint, ScriptBuf, Sequence, Witness};
+
+    // Expected values were computed independently from the ZIP-225 and ZIP-244 specifications.
+    fn p2pkh_script() -> ScriptBuf {
+        ScriptBuf::new_p2pkh(&core::array::from_fn(|i| i as u8))
+    }
+
+    fn p2sh_script() -> ScriptBuf {
+        ScriptBuf::new_p2sh(&core::array::from_fn(|i| i as u8 + 20))
+    }
+
+    fn zcash_tx() -> ZcashTransaction {
+        ZcashTransaction {
+            consensus_branch_id: NU6_1_BRANCH_ID,
+            lock_time: LockTime::from_height(0).unwrap(),
+            expiry_height: 3_100_000,
+            input: vec![
+                TxIn {
+                    previous_output: OutPoint::new(Txid(Hash([0x11; 32])), 0),
+                    script_sig: ScriptBuf::default(),
+                    sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+                    witness: Witness::default(),
+                },
+                TxIn {
+                    previous_output: OutPoint::new(Txid(Hash([0x22; 32])), 1),
+                    script_sig: ScriptBuf::default(),
+                    sequence: Sequence::MAX,
+                    witness: Witness::default(),
+                },
+            ],
+            output: vec![
+                TxOut {
+                    value: Amount::from_sat(50_000),
+                    script_pubkey: p2pkh_script(),
+                },
+                TxOut {
+                    value: Amount::from_sat(25_000),
+                    script_pubkey: p2sh_script(),
+                },
+            ],
+        }
+    }
+
+    fn spent_outputs() -> Vec<TxOut> {
+        vec![
+            TxOut {
+                value: Amount::from_sat(100_000),
+                script_pubkey: p2pkh_script(),
+            },
+            TxOut {
+                value: Amount::from_sat(200_000),
+                script_pubkey: p2sh_script(),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_serialize_v5_transparent_transaction() {
+        let expected = "050000800a27a726f04dec4d00000000604d2f00021111111111111111111111111111111111111111111111111111111111111111000000000\
+            0fdffffff22222222222222222222222222222222222222222222222222222222222222220100000000ffffffff0250c300000000000019\
+            76a914000102030405060708090a0b0c0d0e0f1011121388aca86100000000000017a9141415161718191a1b1c1d1e1f20212223242526\
+            2787000000";
+
+        assert_eq!(hex::encode(zcash_tx().serialize()), expected);
+    }
+
+    #[test]
+    fn test_compute_txid() {
+        assert_eq!(
+            zcash_tx().compute_txid().to_string(),
+            "451d5c66104cf8fd6303e466135e1e154ea5e99547f5a6d59f54463860671760"
+        );
+    }
+
+    #[test]
+    fn test_signature_hash_all() {
+        let tx = zcash_tx();
+        let spent_outputs = spent_outputs();
+
+        let sighash_0 = tx
+            .signature_hash(0, &spent_outputs, EcdsaSighashType::All)
+            .unwrap();
+        let sighash_1 = tx
+            .signature_hash(1, &spent_outputs, EcdsaSighashType::All)
+            .unwrap();
+
+        assert_eq!(
+            hex::encode(sighash_0),
+            "42901200c58d626a3d089a42d63a6a63f6bd4bae040c36656abb9a614b6c30bd"
+        );
+        assert_eq!(
+            hex::encode(sighash_1),
+            "116619df89eb606bb2b6722cd061d7eb2729c03257c42167c1019471ba449ef6"
+        );
+    }
+
+    #[test]
+    fn test_signature_hash_errors() {
+        let tx = zcash_tx();
+        let spent_outputs = spent_outputs();
+
+        assert_eq!(
+            tx.signature_hash(2, &spent_outputs, EcdsaSighashType::All),
+            Err(BitcoinError::InputIndexOutOfRange { index: 2, len: 2 })
+        );
+        assert_eq!(
+            tx.signature_hash(0, &spent_outputs[..1], EcdsaSighashType::All),
+            Err(BitcoinError::SpentOutputsMismatch {
+                expected: 2,
+                actual: 1
+            })
+        );
+    }
+}
@@ -0,0 +1,90 @@
+//! Transaction builder for Zcash transparent transactions
+use super::zcash_transaction::ZcashTransaction;
+use crate::bitcoin::{
+    types::{LockTime, TxIn, TxOut},
+    BitcoinError,
+};
+use crate::transaction_builder::TxBuilder;
+
+#[derive(Clone)]
+pub struct ZcashTransactionBuilder {
+    pub consensus_branch_id: Option<u32>,
+    pub lock_time: Option<LockTime>,
+    pub expiry_height: Option<u32>,
+    pub inputs: Option<Vec<TxIn>>,
+    pub outputs: Option<Vec<TxOut>>,
+}
+
+impl Default for ZcashTransactionBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TxBuilder<ZcashTransaction> for ZcashTransactionBuilder {
+    fn build(&self) -> ZcashTransaction {
+        self.try_build().unwrap_or_else(|e| panic!("{e}"))
+    }
+}
+
+impl ZcashTransactionBuilder {
+    pub const fn new() -> Self {
+        Self {
+            consensus_branch_id: None,
+            lock_time: None,
+            expiry_height: None,
+            inputs: None,
+            outputs: None,
+        }
+    }
+
+    /// Builds the transaction, returning an error instead of panicking.
+    ///
+    /// The expiry height defaults to 0, which disables expiry.
+    pub fn try_build(&self) -> Result<ZcashTransaction, BitcoinError> {
+        Ok(ZcashTransaction {
+            consensus_branch_id: self
+                .consensus_branch_id
+                .ok_or(BitcoinError::MissingField("consensus branch id"))?,
+            lock_time: self
+                .lock_time
+                .ok_or(BitcoinError::MissingField("lock time"))?,
+            expiry_height: self.expiry_height.unwrap_or(0),
+            input: self
+                .inputs
+                .clone()
+                .ok_or(BitcoinError::MissingField("inputs"))?,
+            output: self
+                .outputs
+                .clone()
+                .ok_or(BitcoinError::MissingField("outputs"))?,
+        })
+    }
+
+    /// Consensus branch id of the network upgrade the transaction is valid in.
+    pub const fn consensus_branch_id(mut self, consensus_branch_id: u32) -> Self {
+        self.consensus_branch_id = Some(consensus_branch_id);
+        self
+    }
+
+    pub const fn lock_time(mut self, lock_time: LockTime) -> Self {
+        self.lock_time = Some(lock_time);
+        self
+    }
+
+    /// Block height after which the transaction can no longer be mined.
+    pub const fn expiry_height(mut self, expiry_height: u32) -> Self {
+        self.expiry_height = Some(expiry_height);
+        self
+    }
+
+    pub fn inputs(mut self, inputs: Vec<TxIn>) -> Self {
+        self.inputs = Some(inputs);
+        self
+    }
+
+    pub fn outputs(mut self, outputs: Vec<TxOut>) -> Self {
+        self.outputs = Some(outputs);
+        self
+    }
+}
@@ -8,8 +8,8 @@ use alloy_primitives::{keccak256, U256};
 use eyre::Result;
 use std::result::Result::Ok;
 
+use omni_transaction::evm::types::Address;
 use omni_transaction::evm::types::Signature as OmniSignature;
-use omni_transaction::evm::utils::parse_eth_address;
 use omni_transaction::EVM;
 use omni_transaction::{TransactionBuilder as OmniTransactionBuilder, TxBuilder};
 
@@ -21,7 +21,7 @@ const GAS_LIMIT: u128 = 21_000;
 async fn test_send_raw_transaction_created_with_omnitransactionbuilder_for_evm() -> Result<()> {
     let nonce: u64 = 0;
     let to_address_str = "d8dA6BF26964aF9D7eEd9e03E53415D37aA96045";
-    let to_address = parse_eth_address(to_address_str);
+    let to_address = to_address_str.parse::<Address>().unwrap();
     let value_as_128 = 10000000000000000u128; // 0.01 ETH
     let value = U256::from(value_as_128);
     let data: Vec<u8> = vec![];